@@ -0,0 +1,41 @@
+// adds 1M rows of (f64, i64, str) with a 20 element rolling mean on the f64
+// column, then prints the elapsed time and the peak resident set size
+//
+//     cargo bench --bench add_rows
+use dataframe::cell::types::datatypes::AnyType;
+use dataframe::column::RollingMean;
+use dataframe::frame::DataFrame;
+use std::time::Instant;
+
+const ROWS: usize = 1_000_000;
+
+fn main() {
+    let mut dataframe = DataFrame::new(vec!["price", "volume", "symbol"]).unwrap();
+    dataframe.update_column_rolling_mean("price", RollingMean::new(true, Some(20))).unwrap();
+    let symbols = ["AAPL", "MSFT", "GOOG", "AMZN"];
+
+    let start = Instant::now();
+    for index in 0..ROWS {
+        dataframe
+            .add_row(vec![
+                AnyType::Float64(100.0 + (index % 1000) as f64 / 10.0),
+                AnyType::Int64(index as i64),
+                AnyType::Utf8(symbols[index % symbols.len()].into()),
+            ])
+            .unwrap();
+    }
+    let elapsed = start.elapsed();
+
+    println!("added {} rows in {:.2?}", ROWS, elapsed);
+    match peak_rss_kb() {
+        Some(kb) => println!("peak rss {} MB", kb / 1024),
+        None => println!("peak rss unavailable on this platform"),
+    }
+}
+
+// VmHWM is the high water mark of the resident set, only reported on linux
+fn peak_rss_kb() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+    line.split_whitespace().nth(1)?.parse().ok()
+}
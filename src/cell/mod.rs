@@ -1,94 +1,93 @@
 pub mod types;
 
-use crate::row::{
-    RcRow
-};
-use std::cell::RefCell;
 use types::datatypes::{
     AnyType,
 };
 use std::rc::{ Rc };
 
-#[derive(Debug)]
+// a view over a single value, built on demand from the typed buffers held by a column
+#[derive(Debug, Clone)]
 pub struct Cell {
     value: AnyType,
-    row: RcRow,
-    column_name: &'static str,
+    row_index: usize,
+    column_name: Rc<str>,
     rolling_mean: Option<AnyType>,
+    rolling_values: Vec<(Rc<str>, AnyType)>,
 }
 
 impl Cell {
-    pub fn new(value: AnyType, row: &RcRow, column_name: &'static str) -> RcCell {
-        Rc::new(RefCell::new(Self {
-            value: value,
-            row: Rc::clone(&row),
-            column_name,
-            rolling_mean: None,
-        }))
+    pub fn new<S: Into<Rc<str>>>(value: AnyType, row_index: usize, column_name: S, rolling_mean: Option<AnyType>) -> Self {
+        Self {
+            value,
+            row_index,
+            column_name: column_name.into(),
+            rolling_mean,
+            rolling_values: vec![],
+        }
+    }
+
+    pub fn with_rolling_values(mut self, rolling_values: Vec<(Rc<str>, AnyType)>) -> Self {
+        self.rolling_values = rolling_values;
+        self
     }
 
-    pub fn get_row(&self) -> &RcRow {
-        &self.row
+    pub fn get_row_index(&self) -> usize {
+        self.row_index
     }
 
     pub fn get_value(&self) -> &AnyType {
         &self.value
     }
 
-    pub fn clone_row(&self) -> RcRow {
-        Rc::clone(&self.row)
+    pub fn get_column_name(&self) -> &str {
+        &self.column_name
     }
 
-    pub fn set_rolling_mean(&mut self, rolling_mean: Option<AnyType>) {
-        self.rolling_mean = rolling_mean;
+    pub fn clone_column_name(&self) -> Rc<str> {
+        Rc::clone(&self.column_name)
     }
 
     pub fn get_rolling_mean(&self) -> Option<AnyType> {
-        self.rolling_mean
+        self.rolling_mean.clone()
+    }
+
+    pub fn get_rolling_value(&self, name: &str) -> Option<&AnyType> {
+        self.rolling_values.iter().find(|(window, _)| &**window == name).map(|(_, value)| value)
     }
 }
 
 impl PartialEq for Cell {
     fn eq(&self, other: &Self) -> bool {
-        self.value == other.value && self.row.borrow().index == other.row.borrow().index
+        self.value == other.value && self.row_index == other.row_index
     }
 }
 impl Eq for Cell {}
 
-pub type AnyTypeCell = RefCell<Cell>;
-pub type RcCell = Rc<AnyTypeCell>;
-
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::row::{
-        Row,
-        RcRow
-    };
     #[test]
-    fn assign_row() {
+    fn get_row_index() {
         let value: AnyType = 67u16.into();
-        let row: RcRow = Row::new(3);
-        let cell: RcCell = Cell::new(value, &row, "timmeh");
+        let cell = Cell::new(value, 3, "timmeh", None);
 
-        assert!(&row == cell.borrow().get_row());
+        assert_eq!(cell.get_row_index(), 3);
     }
 
     #[test]
-    fn clone_row() {
+    fn get_column_name() {
         let value: AnyType = 67u16.into();
-        let row: RcRow = Row::new(3);
-        let cell: RcCell = Cell::new(value, &row, "timmeh");
+        let cell = Cell::new(value, 3, "timmeh", None);
 
-        assert!(row == cell.borrow().clone_row());
+        assert_eq!(cell.get_column_name(), "timmeh");
     }
 
     #[test]
     fn get_value() {
         let value: AnyType = 67u16.into();
-        let row: RcRow = Row::new(3);
-        let cell: RcCell = Cell::new(value, &row, "timmeh");
+        let cell = Cell::new(value.clone(), 3, "timmeh", Some(66u16.into()));
 
-        assert!(&value == cell.borrow().get_value());
+        assert!(&value == cell.get_value());
+        assert!(cell.get_rolling_mean() == Some(66u16.into()));
     }
-}
\ No newline at end of file
+}
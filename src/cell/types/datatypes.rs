@@ -1,6 +1,8 @@
 // https://github.com/pola-rs/polars/blob/master/polars/polars-core/src/datatypes.rs
 
+use std::cmp::Ordering;
 use std::hash::{Hash, Hasher};
+use std::rc::Rc;
 use std::fmt::{
     Display,
     Formatter,
@@ -12,12 +14,20 @@ use std::ops::{
     Mul,
     Sub,
 };
+use chrono::{
+    DateTime,
+    Datelike,
+    NaiveDate,
+    NaiveTime,
+    Timelike,
+    Utc,
+};
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 pub enum AnyType {
     Null,
     Boolean(bool),
-    Utf8(&'static str),
+    Utf8(Rc<str>),
     UInt8(u8),
     UInt16(u16),
     UInt32(u32),
@@ -30,10 +40,10 @@ pub enum AnyType {
     ISize(isize),
     Float32(f32),
     Float64(f64),
-    // Date,
-    // Datetime,
-    // Duration,
-    // Time,
+    Date(NaiveDate),
+    Datetime(DateTime<Utc>),
+    Duration(chrono::Duration),
+    Time(NaiveTime),
 }
 
 impl<T> From<Option<T>> for AnyType
@@ -100,6 +110,77 @@ where
 //     }
 // }
 
+impl AnyType {
+    pub fn dtype(&self) -> DataType {
+        match self {
+            AnyType::Null => DataType::Null,
+            AnyType::Boolean(_) => DataType::Boolean,
+            AnyType::Utf8(_) => DataType::Utf8,
+            AnyType::UInt8(_) => DataType::UInt8,
+            AnyType::UInt16(_) => DataType::UInt16,
+            AnyType::UInt32(_) => DataType::UInt32,
+            AnyType::UInt64(_) => DataType::UInt64,
+            AnyType::USize(_) => DataType::USize,
+            AnyType::Int8(_) => DataType::Int8,
+            AnyType::Int16(_) => DataType::Int16,
+            AnyType::Int32(_) => DataType::Int32,
+            AnyType::Int64(_) => DataType::Int64,
+            AnyType::ISize(_) => DataType::ISize,
+            AnyType::Float32(_) => DataType::Float32,
+            AnyType::Float64(_) => DataType::Float64,
+            AnyType::Date(_) => DataType::Date,
+            AnyType::Datetime(_) => DataType::Datetime,
+            AnyType::Duration(_) => DataType::Duration,
+            AnyType::Time(_) => DataType::Time,
+        }
+    }
+
+    // numeric view used by windowed calculations, durations are measured in nanoseconds
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            AnyType::Boolean(val) => Some(*val as u8 as f64),
+            AnyType::UInt8(val) => Some(*val as f64),
+            AnyType::UInt16(val) => Some(*val as f64),
+            AnyType::UInt32(val) => Some(*val as f64),
+            AnyType::UInt64(val) => Some(*val as f64),
+            AnyType::USize(val) => Some(*val as f64),
+            AnyType::Int8(val) => Some(*val as f64),
+            AnyType::Int16(val) => Some(*val as f64),
+            AnyType::Int32(val) => Some(*val as f64),
+            AnyType::Int64(val) => Some(*val as f64),
+            AnyType::ISize(val) => Some(*val as f64),
+            AnyType::Float32(val) => Some(*val as f64),
+            AnyType::Float64(val) => Some(*val),
+            AnyType::Duration(val) => match val.num_nanoseconds() {
+                Some(nanoseconds) => Some(nanoseconds as f64),
+                None => val.num_microseconds().map(|microseconds| microseconds as f64 * 1_000f64),
+            },
+            AnyType::Null | AnyType::Utf8(_) | AnyType::Date(_) | AnyType::Datetime(_) | AnyType::Time(_) => None,
+        }
+    }
+
+    // the reverse of as_f64, integers are truncated towards zero and saturate at the bounds of their type
+    pub fn from_f64(dtype: DataType, value: f64) -> AnyType {
+        match dtype {
+            DataType::Boolean => AnyType::Boolean(value != 0f64),
+            DataType::UInt8 => AnyType::UInt8(value as u8),
+            DataType::UInt16 => AnyType::UInt16(value as u16),
+            DataType::UInt32 => AnyType::UInt32(value as u32),
+            DataType::UInt64 => AnyType::UInt64(value as u64),
+            DataType::USize => AnyType::USize(value as usize),
+            DataType::Int8 => AnyType::Int8(value as i8),
+            DataType::Int16 => AnyType::Int16(value as i16),
+            DataType::Int32 => AnyType::Int32(value as i32),
+            DataType::Int64 => AnyType::Int64(value as i64),
+            DataType::ISize => AnyType::ISize(value as isize),
+            DataType::Float32 => AnyType::Float32(value as f32),
+            DataType::Float64 => AnyType::Float64(value),
+            DataType::Duration => AnyType::Duration(chrono::Duration::nanoseconds(value as i64)),
+            DataType::Null | DataType::Utf8 | DataType::Date | DataType::Datetime | DataType::Time => AnyType::Null,
+        }
+    }
+}
+
 impl Hash for AnyType {
     fn hash<H: Hasher>(&self, state: &mut H) {
         use AnyType::*;
@@ -119,6 +200,19 @@ impl Hash for AnyType {
             Boolean(v) => state.write_u8(*v as u8),
             Float32(v) => state.write_i32(v.floor() as i32),
             Float64(v) => state.write_i64(v.floor() as i64),
+            Date(v) => state.write_i32(v.num_days_from_ce()),
+            Datetime(v) => {
+                state.write_i64(v.timestamp());
+                state.write_u32(v.timestamp_subsec_nanos());
+            },
+            Duration(v) => {
+                state.write_i64(v.num_seconds());
+                state.write_i32(v.subsec_nanos());
+            },
+            Time(v) => {
+                state.write_u32(v.num_seconds_from_midnight());
+                state.write_u32(v.nanosecond());
+            },
         }
     }
 }
@@ -140,7 +234,11 @@ impl Display for AnyType {
             AnyType::Int64(val) => write!(f, "{}", val),
             AnyType::ISize(val) => write!(f, "{}", val),
             AnyType::Float32(val) => write!(f, "{}", val),
-            AnyType::Float64(val) => write!(f, "{}", val)
+            AnyType::Float64(val) => write!(f, "{}", val),
+            AnyType::Date(val) => write!(f, "{}", val),
+            AnyType::Datetime(val) => write!(f, "{}", val.to_rfc3339()),
+            AnyType::Duration(val) => write!(f, "{}", val),
+            AnyType::Time(val) => write!(f, "{}", val),
         }
     }
 }
@@ -150,16 +248,16 @@ impl Add for AnyType {
     fn add(self, rhs: Self) -> Self::Output {
         use AnyType::*;
         match (self, rhs) {
-            (UInt8(lv), UInt8(rv)) => UInt8(lv + rv),
-            (UInt16(lv), UInt16(rv)) => UInt16(lv + rv),
-            (UInt32(lv), UInt32(rv)) => UInt32(lv + rv),
-            (UInt64(lv), UInt64(rv)) => UInt64(lv + rv),
-            (USize(lv), USize(rv)) => USize(lv + rv),
-            (Int8(lv), Int8(rv)) => Int8(lv + rv),
-            (Int16(lv), Int16(rv)) => Int16(lv + rv),
-            (Int32(lv), Int32(rv)) => Int32(lv + rv),
-            (Int64(lv), Int64(rv)) => Int64(lv + rv),
-            (ISize(lv), ISize(rv)) => ISize(lv + rv),
+            (UInt8(lv), UInt8(rv)) => lv.checked_add(rv).map_or(Null, UInt8),
+            (UInt16(lv), UInt16(rv)) => lv.checked_add(rv).map_or(Null, UInt16),
+            (UInt32(lv), UInt32(rv)) => lv.checked_add(rv).map_or(Null, UInt32),
+            (UInt64(lv), UInt64(rv)) => lv.checked_add(rv).map_or(Null, UInt64),
+            (USize(lv), USize(rv)) => lv.checked_add(rv).map_or(Null, USize),
+            (Int8(lv), Int8(rv)) => lv.checked_add(rv).map_or(Null, Int8),
+            (Int16(lv), Int16(rv)) => lv.checked_add(rv).map_or(Null, Int16),
+            (Int32(lv), Int32(rv)) => lv.checked_add(rv).map_or(Null, Int32),
+            (Int64(lv), Int64(rv)) => lv.checked_add(rv).map_or(Null, Int64),
+            (ISize(lv), ISize(rv)) => lv.checked_add(rv).map_or(Null, ISize),
             (Float32(lv), Float32(rv)) => Float32(lv + rv),
             (Float64(lv), Float64(rv)) => Float64(lv + rv),
             (Null, UInt8(rv)) => UInt8(rv),
@@ -174,6 +272,12 @@ impl Add for AnyType {
             (Null, ISize(rv)) => ISize(rv),
             (Null, Float32(rv)) => Float32(rv),
             (Null, Float64(rv)) => Float64(rv),
+            (Duration(lv), Duration(rv)) => lv.checked_add(&rv).map_or(Null, Duration),
+            (Datetime(lv), Duration(rv)) => lv.checked_add_signed(rv).map_or(Null, Datetime),
+            (Duration(lv), Datetime(rv)) => rv.checked_add_signed(lv).map_or(Null, Datetime),
+            (Date(lv), Duration(rv)) => lv.checked_add_signed(rv).map_or(Null, Date),
+            (Time(lv), Duration(rv)) => Time(lv.overflowing_add_signed(rv).0),
+            (Null, Duration(rv)) => Duration(rv),
             (_, _) => Null 
         }     
     }
@@ -184,34 +288,36 @@ impl Div for AnyType {
     fn div(self, rhs: Self) -> Self::Output {
         use AnyType::*;
         match (self, rhs) {
-            (UInt8(lv), UInt8(rv)) => UInt8(lv / rv),
-            (UInt16(lv), UInt16(rv)) => UInt16(lv / rv),
-            (UInt32(lv), UInt32(rv)) => UInt32(lv / rv),
-            (UInt64(lv), UInt64(rv)) => UInt64(lv / rv),
-            (USize(lv), USize(rv)) => USize(lv / rv),
-            (Int8(lv), Int8(rv)) => Int8(lv / rv),
-            (Int16(lv), Int16(rv)) => Int16(lv / rv),
-            (Int32(lv), Int32(rv)) => Int32(lv / rv),
-            (Int64(lv), Int64(rv)) => Int64(lv / rv),
-            (ISize(lv), ISize(rv)) => ISize(lv / rv),
+            (UInt8(lv), UInt8(rv)) => lv.checked_div(rv).map_or(Null, UInt8),
+            (UInt16(lv), UInt16(rv)) => lv.checked_div(rv).map_or(Null, UInt16),
+            (UInt32(lv), UInt32(rv)) => lv.checked_div(rv).map_or(Null, UInt32),
+            (UInt64(lv), UInt64(rv)) => lv.checked_div(rv).map_or(Null, UInt64),
+            (USize(lv), USize(rv)) => lv.checked_div(rv).map_or(Null, USize),
+            (Int8(lv), Int8(rv)) => lv.checked_div(rv).map_or(Null, Int8),
+            (Int16(lv), Int16(rv)) => lv.checked_div(rv).map_or(Null, Int16),
+            (Int32(lv), Int32(rv)) => lv.checked_div(rv).map_or(Null, Int32),
+            (Int64(lv), Int64(rv)) => lv.checked_div(rv).map_or(Null, Int64),
+            (ISize(lv), ISize(rv)) => lv.checked_div(rv).map_or(Null, ISize),
             (Float32(lv), Float32(rv)) => Float32(lv / rv),
             (Float64(lv), Float64(rv)) => Float64(lv / rv),
-            (UInt8(lv), USize(rv)) => UInt8(lv / rv as u8),
-            (UInt16(lv), USize(rv)) => UInt16(lv / rv as u16),
-            (UInt32(lv), USize(rv)) => UInt32(lv / rv as u32),
-            (UInt64(lv), USize(rv)) => UInt64(lv / rv as u64),
-            (Int8(lv), USize(rv)) => Int8(lv / rv as i8),
-            (Int16(lv), USize(rv)) => Int16(lv / rv as i16),
-            (Int32(lv), USize(rv)) => Int32(lv / rv as i32),
-            (Int64(lv), USize(rv)) => Int64(lv / rv as i64),
+            (UInt8(lv), USize(rv)) => u8::try_from(rv).ok().and_then(|rv| lv.checked_div(rv)).map_or(Null, UInt8),
+            (UInt16(lv), USize(rv)) => u16::try_from(rv).ok().and_then(|rv| lv.checked_div(rv)).map_or(Null, UInt16),
+            (UInt32(lv), USize(rv)) => u32::try_from(rv).ok().and_then(|rv| lv.checked_div(rv)).map_or(Null, UInt32),
+            (UInt64(lv), USize(rv)) => u64::try_from(rv).ok().and_then(|rv| lv.checked_div(rv)).map_or(Null, UInt64),
+            (Int8(lv), USize(rv)) => i8::try_from(rv).ok().and_then(|rv| lv.checked_div(rv)).map_or(Null, Int8),
+            (Int16(lv), USize(rv)) => i16::try_from(rv).ok().and_then(|rv| lv.checked_div(rv)).map_or(Null, Int16),
+            (Int32(lv), USize(rv)) => i32::try_from(rv).ok().and_then(|rv| lv.checked_div(rv)).map_or(Null, Int32),
+            (Int64(lv), USize(rv)) => i64::try_from(rv).ok().and_then(|rv| lv.checked_div(rv)).map_or(Null, Int64),
             (Float32(lv), USize(rv)) => Float32(lv / rv as f32),
             (Float64(lv), USize(rv)) => Float64(lv / rv as f64),
-            (Int8(lv), ISize(rv)) => Int8(lv / rv as i8),
-            (Int16(lv), ISize(rv)) => Int16(lv / rv as i16),
-            (Int32(lv), ISize(rv)) => Int32(lv / rv as i32),
-            (Int64(lv), ISize(rv)) => Int64(lv / rv as i64),
+            (Int8(lv), ISize(rv)) => i8::try_from(rv).ok().and_then(|rv| lv.checked_div(rv)).map_or(Null, Int8),
+            (Int16(lv), ISize(rv)) => i16::try_from(rv).ok().and_then(|rv| lv.checked_div(rv)).map_or(Null, Int16),
+            (Int32(lv), ISize(rv)) => i32::try_from(rv).ok().and_then(|rv| lv.checked_div(rv)).map_or(Null, Int32),
+            (Int64(lv), ISize(rv)) => i64::try_from(rv).ok().and_then(|rv| lv.checked_div(rv)).map_or(Null, Int64),
             (Float32(lv), ISize(rv)) => Float32(lv / rv as f32),
             (Float64(lv), ISize(rv)) => Float64(lv / rv as f64),
+            (Duration(lv), USize(rv)) => i32::try_from(rv).ok().filter(|rv| *rv != 0).map_or(Null, |rv| Duration(lv / rv)),
+            (Duration(lv), ISize(rv)) => i32::try_from(rv).ok().filter(|rv| *rv != 0).map_or(Null, |rv| Duration(lv / rv)),
             (_, _) => Null 
         }     
     }
@@ -222,32 +328,32 @@ impl Mul for AnyType {
     fn mul(self, rhs: Self) -> Self::Output {
         use AnyType::*;
         match (self, rhs) {
-            (UInt8(lv), UInt8(rv)) => USize(lv as usize * rv as usize),
-            (UInt16(lv), UInt16(rv)) => USize(lv as usize * rv as usize),
-            (UInt32(lv), UInt32(rv)) => USize(lv as usize * rv as usize),
-            (UInt64(lv), UInt64(rv)) => USize(lv as usize * rv as usize),
-            (USize(lv), USize(rv)) => USize(lv * rv),
-            (Int8(lv), Int8(rv)) => ISize(lv as isize * rv as isize),
-            (Int16(lv), Int16(rv)) => ISize(lv as isize * rv as isize),
-            (Int32(lv), Int32(rv)) => ISize(lv as isize * rv as isize),
-            (Int64(lv), Int64(rv)) => ISize(lv as isize * rv as isize),
-            (ISize(lv), ISize(rv)) => ISize(lv * rv),
+            (UInt8(lv), UInt8(rv)) => (lv as usize).checked_mul(rv as usize).map_or(Null, USize),
+            (UInt16(lv), UInt16(rv)) => (lv as usize).checked_mul(rv as usize).map_or(Null, USize),
+            (UInt32(lv), UInt32(rv)) => (lv as usize).checked_mul(rv as usize).map_or(Null, USize),
+            (UInt64(lv), UInt64(rv)) => (lv as usize).checked_mul(rv as usize).map_or(Null, USize),
+            (USize(lv), USize(rv)) => lv.checked_mul(rv).map_or(Null, USize),
+            (Int8(lv), Int8(rv)) => (lv as isize).checked_mul(rv as isize).map_or(Null, ISize),
+            (Int16(lv), Int16(rv)) => (lv as isize).checked_mul(rv as isize).map_or(Null, ISize),
+            (Int32(lv), Int32(rv)) => (lv as isize).checked_mul(rv as isize).map_or(Null, ISize),
+            (Int64(lv), Int64(rv)) => (lv as isize).checked_mul(rv as isize).map_or(Null, ISize),
+            (ISize(lv), ISize(rv)) => lv.checked_mul(rv).map_or(Null, ISize),
             (Float32(lv), Float32(rv)) => Float64(lv as f64 * rv as f64),
             (Float64(lv), Float64(rv)) => Float64(lv * rv),
-            (UInt8(lv), USize(rv)) => USize(lv as usize * rv),
-            (UInt16(lv), USize(rv)) => USize(lv as usize * rv),
-            (UInt32(lv), USize(rv)) => USize(lv as usize * rv),
-            (UInt64(lv), USize(rv)) => USize(lv as usize * rv),
-            (Int8(lv), USize(rv)) => ISize(lv as isize * rv as isize),
-            (Int16(lv), USize(rv)) => ISize(lv as isize * rv as isize),
-            (Int32(lv), USize(rv)) => ISize(lv as isize * rv as isize),
-            (Int64(lv), USize(rv)) => ISize(lv as isize * rv as isize),
+            (UInt8(lv), USize(rv)) => (lv as usize).checked_mul(rv).map_or(Null, USize),
+            (UInt16(lv), USize(rv)) => (lv as usize).checked_mul(rv).map_or(Null, USize),
+            (UInt32(lv), USize(rv)) => (lv as usize).checked_mul(rv).map_or(Null, USize),
+            (UInt64(lv), USize(rv)) => (lv as usize).checked_mul(rv).map_or(Null, USize),
+            (Int8(lv), USize(rv)) => isize::try_from(rv).ok().and_then(|rv| (lv as isize).checked_mul(rv)).map_or(Null, ISize),
+            (Int16(lv), USize(rv)) => isize::try_from(rv).ok().and_then(|rv| (lv as isize).checked_mul(rv)).map_or(Null, ISize),
+            (Int32(lv), USize(rv)) => isize::try_from(rv).ok().and_then(|rv| (lv as isize).checked_mul(rv)).map_or(Null, ISize),
+            (Int64(lv), USize(rv)) => isize::try_from(rv).ok().and_then(|rv| (lv as isize).checked_mul(rv)).map_or(Null, ISize),
             (Float32(lv), USize(rv)) => Float64(lv as f64 * rv as f64),
             (Float64(lv), USize(rv)) => Float64(lv * rv as f64),
-            (Int8(lv), ISize(rv)) => ISize(lv as isize * rv as isize),
-            (Int16(lv), ISize(rv)) => ISize(lv as isize * rv as isize),
-            (Int32(lv), ISize(rv)) => ISize(lv as isize * rv as isize),
-            (Int64(lv), ISize(rv)) => ISize(lv as isize * rv as isize),
+            (Int8(lv), ISize(rv)) => (lv as isize).checked_mul(rv).map_or(Null, ISize),
+            (Int16(lv), ISize(rv)) => (lv as isize).checked_mul(rv).map_or(Null, ISize),
+            (Int32(lv), ISize(rv)) => (lv as isize).checked_mul(rv).map_or(Null, ISize),
+            (Int64(lv), ISize(rv)) => (lv as isize).checked_mul(rv).map_or(Null, ISize),
             (Float32(lv), ISize(rv)) => Float64(lv as f64 * rv as f64),
             (Float64(lv), ISize(rv)) => Float64(lv * rv as f64),
             (_, _) => Null 
@@ -260,24 +366,38 @@ impl Sub for AnyType {
     fn sub(self, rhs: Self) -> Self::Output {
         use AnyType::*;
         match (self, rhs) {
-            (UInt8(lv), UInt8(rv)) => ISize(lv as isize - rv as isize),
-            (UInt16(lv), UInt16(rv)) => ISize(lv as isize - rv as isize),
-            (UInt32(lv), UInt32(rv)) => ISize(lv as isize - rv as isize),
-            (UInt64(lv), UInt64(rv)) => ISize(lv as isize - rv as isize),
-            (USize(lv), USize(rv)) => ISize(lv as isize - rv as isize),
-            (Int8(lv), Int8(rv)) => ISize(lv as isize - rv as isize),
-            (Int16(lv), Int16(rv)) => ISize(lv as isize - rv as isize),
-            (Int32(lv), Int32(rv)) => ISize(lv as isize - rv as isize),
-            (Int64(lv), Int64(rv)) => ISize(lv as isize - rv as isize),
-            (ISize(lv), ISize(rv)) => ISize(lv as isize - rv as isize),
+            (UInt8(lv), UInt8(rv)) => isize_difference(lv as i128, rv as i128),
+            (UInt16(lv), UInt16(rv)) => isize_difference(lv as i128, rv as i128),
+            (UInt32(lv), UInt32(rv)) => isize_difference(lv as i128, rv as i128),
+            (UInt64(lv), UInt64(rv)) => isize_difference(lv as i128, rv as i128),
+            (USize(lv), USize(rv)) => isize_difference(lv as i128, rv as i128),
+            (Int8(lv), Int8(rv)) => isize_difference(lv as i128, rv as i128),
+            (Int16(lv), Int16(rv)) => isize_difference(lv as i128, rv as i128),
+            (Int32(lv), Int32(rv)) => isize_difference(lv as i128, rv as i128),
+            (Int64(lv), Int64(rv)) => isize_difference(lv as i128, rv as i128),
+            (ISize(lv), ISize(rv)) => isize_difference(lv as i128, rv as i128),
             (Float32(lv), Float32(rv)) => Float32(lv - rv),
             (Float64(lv), Float64(rv)) => Float64(lv - rv),
+            (Datetime(lv), Datetime(rv)) => Duration(lv.signed_duration_since(rv)),
+            (Date(lv), Date(rv)) => Duration(lv.signed_duration_since(rv)),
+            (Time(lv), Time(rv)) => Duration(lv.signed_duration_since(rv)),
+            (Duration(lv), Duration(rv)) => lv.checked_sub(&rv).map_or(Null, Duration),
+            (Datetime(lv), Duration(rv)) => lv.checked_sub_signed(rv).map_or(Null, Datetime),
+            (Date(lv), Duration(rv)) => lv.checked_sub_signed(rv).map_or(Null, Date),
+            (Time(lv), Duration(rv)) => Time(lv.overflowing_sub_signed(rv).0),
             (_, _) => Null 
         }     
     }
 }
 
-// #[derive(Clone, PartialEq, Eq, Debug, Hash)]
+// integer differences are taken without overflow and are Null when they do not fit in an isize
+fn isize_difference(lv: i128, rv: i128) -> AnyType {
+    lv.checked_sub(rv)
+        .and_then(|difference| isize::try_from(difference).ok())
+        .map_or(AnyType::Null, AnyType::ISize)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
 pub enum DataType {
     Null,
     Boolean,
@@ -293,7 +413,11 @@ pub enum DataType {
     Int64,
     ISize,
     Float32,
-    Float64
+    Float64,
+    Date,
+    Datetime,
+    Duration,
+    Time,
 }
 
 impl PartialEq for AnyType {
@@ -313,8 +437,13 @@ impl PartialEq for AnyType {
             (Int32(val), Int32(rhs)) => val == rhs,
             (Int64(val), Int64(rhs)) => val == rhs,
             (ISize(val), ISize(rhs)) => val == rhs,
-            (Float32(val), Float32(rhs)) => val == rhs,
-            (Float64(val), Float64(rhs)) => val == rhs,
+            // NaN equals itself so it can be grouped and ordered like any other value
+            (Float32(val), Float32(rhs)) => val == rhs || (val.is_nan() && rhs.is_nan()),
+            (Float64(val), Float64(rhs)) => val == rhs || (val.is_nan() && rhs.is_nan()),
+            (Date(val), Date(rhs)) => val == rhs,
+            (Datetime(val), Datetime(rhs)) => val == rhs,
+            (Duration(val), Duration(rhs)) => val == rhs,
+            (Time(val), Time(rhs)) => val == rhs,
             (_, _) => false
         }
     }
@@ -322,14 +451,120 @@ impl PartialEq for AnyType {
 
 impl Eq for AnyType {}
 
-// #[derive(Debug, Clone)]
-// pub struct Date {}
-// #[derive(Debug, Clone)]
-// pub struct Datetime {}
-// #[derive(Debug, Clone)]
-// pub struct Duration {}
-// #[derive(Debug, Clone)]
-// pub struct Time {}
+// nulls come first, then booleans, numbers, strings and the temporal types in turn,
+// numbers of different widths are compared by value and NaN is above every other number
+impl Ord for AnyType {
+    fn cmp(&self, other: &Self) -> Ordering {
+        use AnyType::*;
+        match (self, other) {
+            (Null, Null) => Ordering::Equal,
+            (Boolean(val), Boolean(rhs)) => val.cmp(rhs),
+            (Utf8(val), Utf8(rhs)) => val.cmp(rhs),
+            (Date(val), Date(rhs)) => val.cmp(rhs),
+            (Datetime(val), Datetime(rhs)) => val.cmp(rhs),
+            (Duration(val), Duration(rhs)) => val.cmp(rhs),
+            (Time(val), Time(rhs)) => val.cmp(rhs),
+            (val, rhs) => match (val.numeric(), rhs.numeric()) {
+                // equal numbers of different types still need an order to agree with Eq
+                (Some(left), Some(right)) => left.compare(&right).then_with(|| val.rank().cmp(&rhs.rank())),
+                _ => val.rank().cmp(&rhs.rank()),
+            },
+        }
+    }
+}
+
+impl PartialOrd for AnyType {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// an exact view of any numeric value for comparisons between types
+#[derive(Debug, Clone, Copy)]
+enum Numeric {
+    Integer(i128),
+    Float(f64),
+}
+
+impl Numeric {
+    fn compare(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Numeric::Integer(left), Numeric::Integer(right)) => left.cmp(right),
+            (Numeric::Float(left), Numeric::Float(right)) => compare_floats(*left, *right),
+            (Numeric::Integer(left), Numeric::Float(right)) => compare_integer_to_float(*left, *right),
+            (Numeric::Float(left), Numeric::Integer(right)) => compare_integer_to_float(*right, *left).reverse(),
+        }
+    }
+}
+
+fn compare_floats(left: f64, right: f64) -> Ordering {
+    match (left.is_nan(), right.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        // only NaN has no order, and -0.0 is equal to 0.0
+        (false, false) => left.partial_cmp(&right).unwrap_or(Ordering::Equal),
+    }
+}
+
+fn compare_integer_to_float(integer: i128, float: f64) -> Ordering {
+    if float.is_nan() || float >= i128::MAX as f64 {
+        return Ordering::Less;
+    }
+    if float < i128::MIN as f64 {
+        return Ordering::Greater;
+    }
+    let floor = float.floor();
+    match integer.cmp(&(floor as i128)) {
+        Ordering::Equal if float > floor => Ordering::Less,
+        ordering => ordering,
+    }
+}
+
+impl AnyType {
+    fn numeric(&self) -> Option<Numeric> {
+        match self {
+            AnyType::UInt8(val) => Some(Numeric::Integer(*val as i128)),
+            AnyType::UInt16(val) => Some(Numeric::Integer(*val as i128)),
+            AnyType::UInt32(val) => Some(Numeric::Integer(*val as i128)),
+            AnyType::UInt64(val) => Some(Numeric::Integer(*val as i128)),
+            AnyType::USize(val) => Some(Numeric::Integer(*val as i128)),
+            AnyType::Int8(val) => Some(Numeric::Integer(*val as i128)),
+            AnyType::Int16(val) => Some(Numeric::Integer(*val as i128)),
+            AnyType::Int32(val) => Some(Numeric::Integer(*val as i128)),
+            AnyType::Int64(val) => Some(Numeric::Integer(*val as i128)),
+            AnyType::ISize(val) => Some(Numeric::Integer(*val as i128)),
+            AnyType::Float32(val) => Some(Numeric::Float(*val as f64)),
+            AnyType::Float64(val) => Some(Numeric::Float(*val)),
+            _ => None,
+        }
+    }
+
+    // position of each type in the ordering, numbers share a place and are separated by this only when equal
+    fn rank(&self) -> u8 {
+        match self {
+            AnyType::Null => 0,
+            AnyType::Boolean(_) => 1,
+            AnyType::UInt8(_) => 2,
+            AnyType::UInt16(_) => 3,
+            AnyType::UInt32(_) => 4,
+            AnyType::UInt64(_) => 5,
+            AnyType::USize(_) => 6,
+            AnyType::Int8(_) => 7,
+            AnyType::Int16(_) => 8,
+            AnyType::Int32(_) => 9,
+            AnyType::Int64(_) => 10,
+            AnyType::ISize(_) => 11,
+            AnyType::Float32(_) => 12,
+            AnyType::Float64(_) => 13,
+            AnyType::Utf8(_) => 14,
+            AnyType::Date(_) => 15,
+            AnyType::Datetime(_) => 16,
+            AnyType::Duration(_) => 17,
+            AnyType::Time(_) => 18,
+        }
+    }
+}
 
 impl Display for DataType {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
@@ -348,12 +583,26 @@ impl Display for DataType {
             DataType::ISize => "isize",
             DataType::Float32 => "f32",
             DataType::Float64 => "f64",
-            DataType::Utf8 => "&'static str",
+            DataType::Utf8 => "str",
+            DataType::Date => "date",
+            DataType::Datetime => "datetime",
+            DataType::Duration => "duration",
+            DataType::Time => "time",
         };
         f.write_str(s)
     }
 }
 
+impl DataType {
+    pub fn is_numeric(&self) -> bool {
+        !matches!(self, DataType::Null | DataType::Boolean | DataType::Utf8) && !self.is_temporal()
+    }
+
+    pub fn is_temporal(&self) -> bool {
+        matches!(self, DataType::Date | DataType::Datetime | DataType::Duration | DataType::Time)
+    }
+}
+
 pub trait DType {
     fn dtype() -> DataType where Self: Sized;
 }
@@ -361,6 +610,7 @@ pub trait DType {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::TimeZone;
     #[test]
     fn input_none() {
         let none: Option<u8> = None;
@@ -494,4 +744,150 @@ mod tests {
 
         assert_eq!(None, into);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn input_string() {
+        let owned = String::from("whoop");
+        let value: AnyType = owned.clone().into();
+        let borrowed: AnyType = "whoop".into();
+
+        assert!(value == borrowed);
+        assert_eq!(format!("{}", value), "whoop");
+
+        let into: Option<String> = value.into();
+        assert_eq!(into, Some(owned));
+    }
+
+    #[test]
+    fn utf8_hash() {
+        use std::collections::hash_map::DefaultHasher;
+        let value: AnyType = String::from("whoop").into();
+        let borrowed: AnyType = "whoop".into();
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        let mut borrowed_hasher = DefaultHasher::new();
+        borrowed.hash(&mut borrowed_hasher);
+
+        assert_eq!(hasher.finish(), borrowed_hasher.finish());
+    }
+
+    #[test]
+    fn datetime_subtraction() {
+        let initial: AnyType = Utc.with_ymd_and_hms(2021, 6, 1, 12, 5, 0).unwrap().into();
+        let secondary: AnyType = Utc.with_ymd_and_hms(2021, 6, 1, 12, 0, 0).unwrap().into();
+
+        let value = initial - secondary;
+        assert!(value == AnyType::Duration(chrono::Duration::minutes(5)));
+    }
+
+    #[test]
+    fn datetime_duration_addition() {
+        let initial: AnyType = Utc.with_ymd_and_hms(2021, 6, 1, 12, 0, 0).unwrap().into();
+        let secondary: AnyType = chrono::Duration::minutes(5).into();
+
+        let value = initial + secondary;
+        assert!(value == AnyType::Datetime(Utc.with_ymd_and_hms(2021, 6, 1, 12, 5, 0).unwrap()));
+    }
+
+    #[test]
+    fn date_and_time_arithmetic() {
+        let date: AnyType = NaiveDate::from_ymd_opt(2021, 6, 1).into();
+        let next_date: AnyType = date.clone() + chrono::Duration::days(1).into();
+        assert!(next_date - date == AnyType::Duration(chrono::Duration::days(1)));
+
+        let time: AnyType = NaiveTime::from_hms_opt(23, 30, 0).into();
+        let wrapped = time + chrono::Duration::hours(1).into();
+        assert!(wrapped == AnyType::Time(NaiveTime::from_hms_opt(0, 30, 0).unwrap()));
+    }
+
+    #[test]
+    fn subtraction_overflow() {
+        assert!(AnyType::Int64(i64::MIN) - AnyType::Int64(1) == AnyType::Null);
+        assert!(AnyType::ISize(isize::MAX) - AnyType::ISize(-1) == AnyType::Null);
+        assert!(AnyType::UInt64(u64::MAX) - AnyType::UInt64(u64::MAX - 5) == AnyType::ISize(5));
+        assert!(AnyType::USize(0) - AnyType::USize(usize::MAX) == AnyType::Null);
+        assert!(AnyType::Int64(i64::MAX) - AnyType::Int64(i64::MAX - 1) == AnyType::ISize(1));
+    }
+
+    #[test]
+    fn arithmetic_overflow() {
+        assert!(AnyType::UInt8(200) + AnyType::UInt8(100) == AnyType::Null);
+        assert!(AnyType::Int8(-100) + AnyType::Int8(-100) == AnyType::Null);
+        assert!(AnyType::UInt8(200) + AnyType::UInt8(55) == AnyType::UInt8(255));
+        assert!(AnyType::Int64(1) / AnyType::Int64(0) == AnyType::Null);
+        assert!(AnyType::Int64(i64::MIN) / AnyType::Int64(-1) == AnyType::Null);
+        assert!(AnyType::Int32(-9) / AnyType::ISize(0) == AnyType::Null);
+        assert!(AnyType::UInt8(1) / AnyType::USize(256) == AnyType::Null);
+        assert!(AnyType::UInt8(200) / AnyType::USize(2) == AnyType::UInt8(100));
+        assert!(AnyType::USize(usize::MAX) * AnyType::USize(2) == AnyType::Null);
+        assert!(AnyType::Int64(i64::MIN) * AnyType::Int64(-1) == AnyType::Null);
+        assert!(AnyType::Int8(-3) * AnyType::USize(usize::MAX) == AnyType::Null);
+        assert!(AnyType::UInt8(200) * AnyType::UInt8(200) == AnyType::USize(40000));
+    }
+
+    #[test]
+    fn duration_division() {
+        let duration: AnyType = chrono::Duration::seconds(6).into();
+        assert!(duration.clone() / AnyType::USize(3) == AnyType::Duration(chrono::Duration::seconds(2)));
+        assert!(duration.clone() / AnyType::ISize(-2) == AnyType::Duration(chrono::Duration::seconds(-3)));
+        assert!(duration.clone() / AnyType::USize(0) == AnyType::Null);
+        assert!(duration.clone() / AnyType::ISize(0) == AnyType::Null);
+        assert!(duration.clone() / AnyType::USize(1 << 32) == AnyType::Null);
+        assert!(duration / AnyType::ISize(i32::MIN as isize - 1) == AnyType::Null);
+    }
+
+    #[test]
+    fn temporal_display_and_hash() {
+        use std::collections::HashSet;
+        let datetime: AnyType = Utc.with_ymd_and_hms(2021, 6, 1, 12, 0, 0).unwrap().into();
+        assert_eq!(format!("{}", datetime), "2021-06-01T12:00:00+00:00");
+        assert_eq!(datetime.dtype(), DataType::Datetime);
+
+        let mut set = HashSet::new();
+        set.insert(datetime.clone());
+        set.insert(Utc.with_ymd_and_hms(2021, 6, 1, 12, 0, 0).unwrap().into());
+        set.insert(chrono::Duration::seconds(3).into());
+        assert_eq!(set.len(), 2);
+
+        let into: Option<DateTime<Utc>> = datetime.into();
+        assert_eq!(into, Some(Utc.with_ymd_and_hms(2021, 6, 1, 12, 0, 0).unwrap()));
+        let none: Option<NaiveTime> = None;
+        assert!(AnyType::from(none) == AnyType::Null);
+    }
+
+    #[test]
+    fn total_ordering() {
+        let mut values: Vec<AnyType> = vec![
+            "apple".into(),
+            f64::NAN.into(),
+            3u8.into(),
+            AnyType::Null,
+            (-2i64).into(),
+            2.5f32.into(),
+            true.into(),
+            u64::MAX.into(),
+        ];
+        values.sort();
+
+        assert_eq!(values, vec![
+            AnyType::Null,
+            true.into(),
+            (-2i64).into(),
+            2.5f32.into(),
+            3u8.into(),
+            u64::MAX.into(),
+            f64::NAN.into(),
+            "apple".into(),
+        ]);
+    }
+
+    #[test]
+    fn ordering_agrees_with_equality() {
+        assert!(AnyType::from(1u8) != AnyType::from(1i64));
+        assert_ne!(AnyType::from(1u8).cmp(&1i64.into()), Ordering::Equal);
+        assert!(AnyType::from(1u8) < AnyType::from(1.5f64));
+        assert!(AnyType::from(i64::MAX) < AnyType::from(1e19f64));
+        assert!(AnyType::from(f64::NAN) == AnyType::from(f64::NAN));
+        assert_eq!(AnyType::from(0f64).cmp(&(-0f64).into()), Ordering::Equal);
+    }
+}
@@ -0,0 +1,32 @@
+use super::datatypes::{
+    DType,
+    DataType,
+    AnyType
+};
+use chrono::{
+    NaiveDate
+};
+
+#[derive(Debug, Clone)]
+pub struct Date {}
+
+impl DType for Date {
+    fn dtype() -> DataType {
+        DataType::Date
+    }
+}
+
+impl From<NaiveDate> for AnyType {
+    fn from(value: NaiveDate) -> Self {
+        AnyType::Date(value)
+    }
+}
+
+impl From<AnyType> for Option<NaiveDate> {
+    fn from(any_type: AnyType) -> Option<NaiveDate> {
+        match any_type {
+            AnyType::Date(val) => Some(val),
+            _ => None
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use super::datatypes::{
+    DType,
+    DataType,
+    AnyType
+};
+use chrono::{
+    DateTime,
+    Utc
+};
+
+#[derive(Debug, Clone)]
+pub struct Datetime {}
+
+impl DType for Datetime {
+    fn dtype() -> DataType {
+        DataType::Datetime
+    }
+}
+
+impl From<DateTime<Utc>> for AnyType {
+    fn from(value: DateTime<Utc>) -> Self {
+        AnyType::Datetime(value)
+    }
+}
+
+impl From<AnyType> for Option<DateTime<Utc>> {
+    fn from(any_type: AnyType) -> Option<DateTime<Utc>> {
+        match any_type {
+            AnyType::Datetime(val) => Some(val),
+            _ => None
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use super::datatypes::{
+    DType,
+    DataType,
+    AnyType
+};
+
+#[derive(Debug, Clone)]
+pub struct Duration {}
+
+impl DType for Duration {
+    fn dtype() -> DataType {
+        DataType::Duration
+    }
+}
+
+impl From<chrono::Duration> for AnyType {
+    fn from(value: chrono::Duration) -> Self {
+        AnyType::Duration(value)
+    }
+}
+
+impl From<AnyType> for Option<chrono::Duration> {
+    fn from(any_type: AnyType) -> Option<chrono::Duration> {
+        match any_type {
+            AnyType::Duration(val) => Some(val),
+            _ => None
+        }
+    }
+}
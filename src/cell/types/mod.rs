@@ -1,5 +1,8 @@
 pub mod datatypes;
 mod bool;
+mod date;
+mod datetime;
+mod duration;
 mod f32;
 mod f64;
 mod i8;
@@ -7,6 +10,7 @@ mod i16;
 mod i32;
 mod i64;
 mod isize;
+mod time;
 mod u8;
 mod u16;
 mod u32;
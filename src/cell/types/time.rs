@@ -0,0 +1,32 @@
+use super::datatypes::{
+    DType,
+    DataType,
+    AnyType
+};
+use chrono::{
+    NaiveTime
+};
+
+#[derive(Debug, Clone)]
+pub struct Time {}
+
+impl DType for Time {
+    fn dtype() -> DataType {
+        DataType::Time
+    }
+}
+
+impl From<NaiveTime> for AnyType {
+    fn from(value: NaiveTime) -> Self {
+        AnyType::Time(value)
+    }
+}
+
+impl From<AnyType> for Option<NaiveTime> {
+    fn from(any_type: AnyType) -> Option<NaiveTime> {
+        match any_type {
+            AnyType::Time(val) => Some(val),
+            _ => None
+        }
+    }
+}
@@ -3,6 +3,7 @@ use super::datatypes::{
     DataType,
     AnyType
 };
+use std::rc::Rc;
 
 #[derive(Debug, Clone)]
 pub struct Utf8 {}
@@ -13,17 +14,38 @@ impl DType for Utf8 {
     }
 }
 
-impl From<&'static str> for AnyType {
-    fn from(value: &'static str) -> Self {
+impl From<&str> for AnyType {
+    fn from(value: &str) -> Self {
+        AnyType::Utf8(Rc::from(value))
+    }
+}
+
+impl From<String> for AnyType {
+    fn from(value: String) -> Self {
+        AnyType::Utf8(Rc::from(value))
+    }
+}
+
+impl From<Rc<str>> for AnyType {
+    fn from(value: Rc<str>) -> Self {
         AnyType::Utf8(value)
     }
 }
 
-impl From<AnyType> for Option<&'static str> {
-    fn from(any_type: AnyType) -> Option<&'static str> {
+impl From<AnyType> for Option<Rc<str>> {
+    fn from(any_type: AnyType) -> Option<Rc<str>> {
         match any_type {
             AnyType::Utf8(val) => Some(val),
             _ => None
         }
     }
-}
\ No newline at end of file
+}
+
+impl From<AnyType> for Option<String> {
+    fn from(any_type: AnyType) -> Option<String> {
+        match any_type {
+            AnyType::Utf8(val) => Some(val.to_string()),
+            _ => None
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use std::cell::RefCell;
+use std::fmt::Debug;
+use chrono::{
+    DateTime,
+    Duration,
+    Utc
+};
+
+pub trait Clock: Debug {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+#[derive(Debug, Default)]
+pub struct SystemClock {}
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+// a clock which only moves when told to, for replaying recorded feeds and deterministic tests
+#[derive(Debug)]
+pub struct ManualClock {
+    datetime: RefCell<DateTime<Utc>>
+}
+
+impl ManualClock {
+    pub fn new(datetime: DateTime<Utc>) -> Self {
+        Self {
+            datetime: RefCell::new(datetime)
+        }
+    }
+
+    pub fn set(&self, datetime: DateTime<Utc>) {
+        *self.datetime.borrow_mut() = datetime;
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        let mut datetime = self.datetime.borrow_mut();
+        *datetime += duration;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.datetime.borrow()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    #[test]
+    fn manual_clock() {
+        let start = Utc.with_ymd_and_hms(2021, 6, 1, 12, 0, 0).unwrap();
+        let clock = ManualClock::new(start);
+        assert_eq!(clock.now(), start);
+
+        clock.advance(Duration::seconds(90));
+        assert_eq!(clock.now(), Utc.with_ymd_and_hms(2021, 6, 1, 12, 1, 30).unwrap());
+
+        clock.set(start);
+        assert_eq!(clock.now(), start);
+    }
+}
@@ -0,0 +1,247 @@
+use crate::cell::types::datatypes::{
+    AnyType,
+    DataType,
+};
+use crate::column::{
+    Column,
+    buffer::{
+        TypedBuffer,
+        bitmap::Bitmap,
+    },
+};
+use crate::error::{
+    DataFrameError,
+    Result,
+};
+use std::cmp::Ordering;
+
+pub fn count(column: &Column) -> usize {
+    column.len() - column.null_count()
+}
+
+pub fn sum(column: &Column) -> Result<Option<AnyType>> {
+    check_not_empty(column)?;
+    let buffer = column.get_buffer();
+    let validity = buffer.get_validity();
+    if count(column) == 0 {
+        return Ok(None);
+    }
+    let sum = match buffer.get_values() {
+        TypedBuffer::Null => None,
+        TypedBuffer::Boolean(values) => Some(AnyType::UInt64(valid(values, validity).filter(|v| *v).count() as u64)),
+        TypedBuffer::UInt8(values) => Some(unsigned_total(column, sum_integers(valid(values, validity).map(|v| v as i128)))?),
+        TypedBuffer::UInt16(values) => Some(unsigned_total(column, sum_integers(valid(values, validity).map(|v| v as i128)))?),
+        TypedBuffer::UInt32(values) => Some(unsigned_total(column, sum_integers(valid(values, validity).map(|v| v as i128)))?),
+        TypedBuffer::UInt64(values) => Some(unsigned_total(column, sum_integers(valid(values, validity).map(|v| v as i128)))?),
+        TypedBuffer::USize(values) => Some(unsigned_total(column, sum_integers(valid(values, validity).map(|v| v as i128)))?),
+        TypedBuffer::Int8(values) => Some(signed_total(column, sum_integers(valid(values, validity).map(|v| v as i128)))?),
+        TypedBuffer::Int16(values) => Some(signed_total(column, sum_integers(valid(values, validity).map(|v| v as i128)))?),
+        TypedBuffer::Int32(values) => Some(signed_total(column, sum_integers(valid(values, validity).map(|v| v as i128)))?),
+        TypedBuffer::Int64(values) => Some(signed_total(column, sum_integers(valid(values, validity).map(|v| v as i128)))?),
+        TypedBuffer::ISize(values) => Some(signed_total(column, sum_integers(valid(values, validity).map(|v| v as i128)))?),
+        TypedBuffer::Float32(values) => Some(AnyType::Float64(sum_floats(valid(values, validity).map(|v| v as f64)))),
+        TypedBuffer::Float64(values) => Some(AnyType::Float64(sum_floats(valid(values, validity)))),
+        TypedBuffer::Duration(values) => Some(AnyType::Duration(sum_durations(column, valid(values, validity))?)),
+        TypedBuffer::Utf8(_) | TypedBuffer::Date(_) | TypedBuffer::Datetime(_) | TypedBuffer::Time(_) => return Err(unsupported(column, "sum")),
+    };
+    Ok(sum)
+}
+
+// integer means are taken from the exact i128 total, floats from a compensated f64 total
+pub fn mean(column: &Column) -> Result<Option<AnyType>> {
+    check_not_empty(column)?;
+    let number = count(column);
+    if number == 0 || column.dtype() == DataType::Null {
+        return Ok(None);
+    }
+    let buffer = column.get_buffer();
+    let validity = buffer.get_validity();
+    let total = match buffer.get_values() {
+        TypedBuffer::Duration(values) => {
+            let total = sum_durations(column, valid(values, validity))?;
+            let number = i32::try_from(number).map_err(|_| DataFrameError::Overflow(column.name.to_string()))?;
+            return Ok(Some(AnyType::Duration(total / number)));
+        },
+        TypedBuffer::Float32(values) => sum_floats(valid(values, validity).map(|v| v as f64)),
+        TypedBuffer::Float64(values) => sum_floats(valid(values, validity)),
+        _ => match as_integers(column) {
+            Some(values) => sum_integers(values) as f64,
+            None => return Err(unsupported(column, "mean")),
+        },
+    };
+    Ok(Some(AnyType::Float64(total / number as f64)))
+}
+
+pub fn min(column: &Column) -> Result<Option<AnyType>> {
+    extreme(column, Ordering::Less)
+}
+
+pub fn max(column: &Column) -> Result<Option<AnyType>> {
+    extreme(column, Ordering::Greater)
+}
+
+// sample variance (n - 1 degrees of freedom) using Welford's online algorithm
+pub fn variance(column: &Column) -> Result<Option<AnyType>> {
+    check_not_empty(column)?;
+    if column.dtype() == DataType::Null {
+        return Ok(None);
+    }
+    let values = as_floats(column).ok_or_else(|| unsupported(column, "variance"))?;
+    let mut welford = Welford::default();
+    for value in values {
+        welford.push(value);
+    }
+    Ok(welford.variance().map(AnyType::Float64))
+}
+
+pub fn std(column: &Column) -> Result<Option<AnyType>> {
+    let variance = variance(column)?;
+    Ok(variance.map(|v| match v {
+        AnyType::Float64(v) => AnyType::Float64(v.sqrt()),
+        _ => AnyType::Null,
+    }))
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Welford {
+    count: usize,
+    mean: f64,
+    m2: f64,
+}
+
+impl Welford {
+    pub fn push(&mut self, value: f64) {
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    pub fn variance(&self) -> Option<f64> {
+        if self.count < 2 {
+            return None;
+        }
+        Some(self.m2 / (self.count - 1) as f64)
+    }
+}
+
+// Neumaier's variant of Kahan summation, which also copes with terms larger than the running total
+pub fn sum_floats<I: Iterator<Item = f64>>(values: I) -> f64 {
+    let mut total = 0f64;
+    let mut compensation = 0f64;
+    for value in values {
+        let next = total + value;
+        if total.abs() >= value.abs() {
+            compensation += (total - next) + value;
+        } else {
+            compensation += (value - next) + total;
+        }
+        total = next;
+    }
+    total + compensation
+}
+
+fn sum_integers<I: Iterator<Item = i128>>(values: I) -> i128 {
+    values.sum()
+}
+
+fn valid<'a, T: Clone>(values: &'a [T], validity: &'a Bitmap) -> impl Iterator<Item = T> + 'a {
+    values.iter().enumerate().filter(move |(index, _)| validity.get(*index)).map(|(_, value)| value.clone())
+}
+
+fn as_integers(column: &Column) -> Option<Box<dyn Iterator<Item = i128> + '_>> {
+    let buffer = column.get_buffer();
+    let validity = buffer.get_validity();
+    let values: Box<dyn Iterator<Item = i128>> = match buffer.get_values() {
+        TypedBuffer::Boolean(values) => Box::new(valid(values, validity).map(|v| v as i128)),
+        TypedBuffer::UInt8(values) => Box::new(valid(values, validity).map(|v| v as i128)),
+        TypedBuffer::UInt16(values) => Box::new(valid(values, validity).map(|v| v as i128)),
+        TypedBuffer::UInt32(values) => Box::new(valid(values, validity).map(|v| v as i128)),
+        TypedBuffer::UInt64(values) => Box::new(valid(values, validity).map(|v| v as i128)),
+        TypedBuffer::USize(values) => Box::new(valid(values, validity).map(|v| v as i128)),
+        TypedBuffer::Int8(values) => Box::new(valid(values, validity).map(|v| v as i128)),
+        TypedBuffer::Int16(values) => Box::new(valid(values, validity).map(|v| v as i128)),
+        TypedBuffer::Int32(values) => Box::new(valid(values, validity).map(|v| v as i128)),
+        TypedBuffer::Int64(values) => Box::new(valid(values, validity).map(|v| v as i128)),
+        TypedBuffer::ISize(values) => Box::new(valid(values, validity).map(|v| v as i128)),
+        _ => return None,
+    };
+    Some(values)
+}
+
+pub fn as_floats(column: &Column) -> Option<Box<dyn Iterator<Item = f64> + '_>> {
+    let buffer = column.get_buffer();
+    let validity = buffer.get_validity();
+    match buffer.get_values() {
+        TypedBuffer::Float32(values) => Some(Box::new(valid(values, validity).map(|v| v as f64))),
+        TypedBuffer::Float64(values) => Some(Box::new(valid(values, validity))),
+        _ => as_integers(column).map(|values| Box::new(values.map(|v| v as f64)) as Box<dyn Iterator<Item = f64>>),
+    }
+}
+
+fn sum_durations<I: Iterator<Item = chrono::Duration>>(column: &Column, values: I) -> Result<chrono::Duration> {
+    let mut total = chrono::Duration::zero();
+    for value in values {
+        total = total.checked_add(&value).ok_or_else(|| DataFrameError::Overflow(column.name.to_string()))?;
+    }
+    Ok(total)
+}
+
+fn unsigned_total(column: &Column, total: i128) -> Result<AnyType> {
+    u64::try_from(total).map(AnyType::UInt64).map_err(|_| DataFrameError::Overflow(column.name.to_string()))
+}
+
+fn signed_total(column: &Column, total: i128) -> Result<AnyType> {
+    i64::try_from(total).map(AnyType::Int64).map_err(|_| DataFrameError::Overflow(column.name.to_string()))
+}
+
+fn extreme(column: &Column, ordering: Ordering) -> Result<Option<AnyType>> {
+    check_not_empty(column)?;
+    let buffer = column.get_buffer();
+    let validity = buffer.get_validity();
+    let value = match buffer.get_values() {
+        TypedBuffer::Null => None,
+        TypedBuffer::Boolean(values) => pick(valid(values, validity), ordering).map(AnyType::Boolean),
+        TypedBuffer::Utf8(values) => pick(valid(values, validity), ordering).map(AnyType::Utf8),
+        TypedBuffer::UInt8(values) => pick(valid(values, validity), ordering).map(AnyType::UInt8),
+        TypedBuffer::UInt16(values) => pick(valid(values, validity), ordering).map(AnyType::UInt16),
+        TypedBuffer::UInt32(values) => pick(valid(values, validity), ordering).map(AnyType::UInt32),
+        TypedBuffer::UInt64(values) => pick(valid(values, validity), ordering).map(AnyType::UInt64),
+        TypedBuffer::USize(values) => pick(valid(values, validity), ordering).map(AnyType::USize),
+        TypedBuffer::Int8(values) => pick(valid(values, validity), ordering).map(AnyType::Int8),
+        TypedBuffer::Int16(values) => pick(valid(values, validity), ordering).map(AnyType::Int16),
+        TypedBuffer::Int32(values) => pick(valid(values, validity), ordering).map(AnyType::Int32),
+        TypedBuffer::Int64(values) => pick(valid(values, validity), ordering).map(AnyType::Int64),
+        TypedBuffer::ISize(values) => pick(valid(values, validity), ordering).map(AnyType::ISize),
+        // NaN has no place in the ordering so is skipped
+        TypedBuffer::Float32(values) => pick(valid(values, validity).filter(|v| !v.is_nan()), ordering).map(AnyType::Float32),
+        TypedBuffer::Float64(values) => pick(valid(values, validity).filter(|v| !v.is_nan()), ordering).map(AnyType::Float64),
+        TypedBuffer::Date(values) => pick(valid(values, validity), ordering).map(AnyType::Date),
+        TypedBuffer::Datetime(values) => pick(valid(values, validity), ordering).map(AnyType::Datetime),
+        TypedBuffer::Duration(values) => pick(valid(values, validity), ordering).map(AnyType::Duration),
+        TypedBuffer::Time(values) => pick(valid(values, validity), ordering).map(AnyType::Time),
+    };
+    Ok(value)
+}
+
+fn pick<T: PartialOrd, I: Iterator<Item = T>>(values: I, ordering: Ordering) -> Option<T> {
+    values.fold(None, |best, value| match best {
+        Some(best) if value.partial_cmp(&best) != Some(ordering) => Some(best),
+        _ => Some(value),
+    })
+}
+
+fn check_not_empty(column: &Column) -> Result<()> {
+    if column.is_empty() {
+        return Err(DataFrameError::EmptyColumn(column.name.to_string()));
+    }
+    Ok(())
+}
+
+fn unsupported(column: &Column, operation: &'static str) -> DataFrameError {
+    DataFrameError::UnsupportedDtype {
+        column: column.name.to_string(),
+        operation,
+        dtype: column.dtype(),
+    }
+}
@@ -0,0 +1,122 @@
+// one bit per value, set when the value is present and unset when it is null
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Bitmap {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl Bitmap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn push(&mut self, bit: bool) {
+        if self.len.is_multiple_of(64) {
+            self.words.push(0);
+        }
+        self.len += 1;
+        self.set(self.len - 1, bit);
+    }
+
+    pub fn get(&self, index: usize) -> bool {
+        index < self.len && self.words[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    pub fn set(&mut self, index: usize, bit: bool) {
+        let word = &mut self.words[index / 64];
+        if bit {
+            *word |= 1 << (index % 64);
+        } else {
+            *word &= !(1 << (index % 64));
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) -> bool {
+        let bit = self.get(index);
+        let word = index / 64;
+        // bits below the removed one stay put, the rest of its word moves down one
+        let below = (1u64 << (index % 64)) - 1;
+        self.words[word] = (self.words[word] & below) | ((self.words[word] >> 1) & !below);
+        // every later word moves down one, handing its lowest bit to the word before
+        for position in word + 1..self.words.len() {
+            self.words[position - 1] |= (self.words[position] & 1) << 63;
+            self.words[position] >>= 1;
+        }
+        self.len -= 1;
+        if self.len.is_multiple_of(64) {
+            self.words.pop();
+        }
+        bit
+    }
+
+    pub fn count_unset(&self) -> usize {
+        let set: u32 = self.words.iter().map(|word| word.count_ones()).sum();
+        self.len - set as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn push_and_get() {
+        let mut bitmap = Bitmap::new();
+        for index in 0..130 {
+            bitmap.push(index % 3 != 0);
+        }
+
+        assert_eq!(bitmap.len(), 130);
+        assert!(!bitmap.get(0));
+        assert!(bitmap.get(65));
+        assert!(!bitmap.get(129));
+        assert!(!bitmap.get(130));
+        assert_eq!(bitmap.count_unset(), 44);
+    }
+
+    #[test]
+    fn remove() {
+        let mut bitmap = Bitmap::new();
+        for index in 0..65 {
+            bitmap.push(index != 1);
+        }
+        assert!(!bitmap.remove(1));
+        assert_eq!(bitmap.len(), 64);
+        assert_eq!(bitmap.count_unset(), 0);
+
+        bitmap.set(63, false);
+        assert!(!bitmap.remove(63));
+        assert_eq!(bitmap.count_unset(), 0);
+    }
+
+    #[test]
+    fn remove_across_words() {
+        let mut bitmap = Bitmap::new();
+        let mut expected = Vec::new();
+        for index in 0..200 {
+            let bit = index % 7 == 0 || index % 5 == 3;
+            bitmap.push(bit);
+            expected.push(bit);
+        }
+        for index in [150, 0, 63, 64, 127, 70, 1, 190] {
+            let index = index.min(expected.len() - 1);
+            assert_eq!(bitmap.remove(index), expected.remove(index));
+            assert_eq!(bitmap.len(), expected.len());
+            for (position, bit) in expected.iter().enumerate() {
+                assert_eq!(bitmap.get(position), *bit);
+            }
+            assert_eq!(bitmap.count_unset(), expected.iter().filter(|bit| !**bit).count());
+        }
+        while !expected.is_empty() {
+            assert_eq!(bitmap.remove(0), expected.remove(0));
+        }
+        assert_eq!(bitmap, Bitmap::new());
+    }
+}
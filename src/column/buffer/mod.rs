@@ -0,0 +1,371 @@
+pub mod bitmap;
+
+use crate::cell::types::datatypes::{
+    AnyType,
+    DataType,
+};
+use bitmap::Bitmap;
+use chrono::{
+    DateTime,
+    NaiveDate,
+    NaiveTime,
+    Utc,
+};
+use std::rc::Rc;
+
+#[derive(Debug, Clone)]
+pub enum TypedBuffer {
+    Null,
+    Boolean(Vec<bool>),
+    Utf8(Vec<Rc<str>>),
+    UInt8(Vec<u8>),
+    UInt16(Vec<u16>),
+    UInt32(Vec<u32>),
+    UInt64(Vec<u64>),
+    USize(Vec<usize>),
+    Int8(Vec<i8>),
+    Int16(Vec<i16>),
+    Int32(Vec<i32>),
+    Int64(Vec<i64>),
+    ISize(Vec<isize>),
+    Float32(Vec<f32>),
+    Float64(Vec<f64>),
+    Date(Vec<NaiveDate>),
+    Datetime(Vec<DateTime<Utc>>),
+    Duration(Vec<chrono::Duration>),
+    Time(Vec<NaiveTime>),
+}
+
+impl TypedBuffer {
+    fn with_placeholders(dtype: DataType, len: usize) -> Self {
+        match dtype {
+            DataType::Null => TypedBuffer::Null,
+            DataType::Boolean => TypedBuffer::Boolean(vec![Default::default(); len]),
+            DataType::Utf8 => TypedBuffer::Utf8(vec![Default::default(); len]),
+            DataType::UInt8 => TypedBuffer::UInt8(vec![Default::default(); len]),
+            DataType::UInt16 => TypedBuffer::UInt16(vec![Default::default(); len]),
+            DataType::UInt32 => TypedBuffer::UInt32(vec![Default::default(); len]),
+            DataType::UInt64 => TypedBuffer::UInt64(vec![Default::default(); len]),
+            DataType::USize => TypedBuffer::USize(vec![Default::default(); len]),
+            DataType::Int8 => TypedBuffer::Int8(vec![Default::default(); len]),
+            DataType::Int16 => TypedBuffer::Int16(vec![Default::default(); len]),
+            DataType::Int32 => TypedBuffer::Int32(vec![Default::default(); len]),
+            DataType::Int64 => TypedBuffer::Int64(vec![Default::default(); len]),
+            DataType::ISize => TypedBuffer::ISize(vec![Default::default(); len]),
+            DataType::Float32 => TypedBuffer::Float32(vec![Default::default(); len]),
+            DataType::Float64 => TypedBuffer::Float64(vec![Default::default(); len]),
+            DataType::Date => TypedBuffer::Date(vec![Default::default(); len]),
+            DataType::Datetime => TypedBuffer::Datetime(vec![Default::default(); len]),
+            DataType::Duration => TypedBuffer::Duration(vec![Default::default(); len]),
+            DataType::Time => TypedBuffer::Time(vec![Default::default(); len]),
+        }
+    }
+
+    pub fn dtype(&self) -> DataType {
+        match self {
+            TypedBuffer::Null => DataType::Null,
+            TypedBuffer::Boolean(_) => DataType::Boolean,
+            TypedBuffer::Utf8(_) => DataType::Utf8,
+            TypedBuffer::UInt8(_) => DataType::UInt8,
+            TypedBuffer::UInt16(_) => DataType::UInt16,
+            TypedBuffer::UInt32(_) => DataType::UInt32,
+            TypedBuffer::UInt64(_) => DataType::UInt64,
+            TypedBuffer::USize(_) => DataType::USize,
+            TypedBuffer::Int8(_) => DataType::Int8,
+            TypedBuffer::Int16(_) => DataType::Int16,
+            TypedBuffer::Int32(_) => DataType::Int32,
+            TypedBuffer::Int64(_) => DataType::Int64,
+            TypedBuffer::ISize(_) => DataType::ISize,
+            TypedBuffer::Float32(_) => DataType::Float32,
+            TypedBuffer::Float64(_) => DataType::Float64,
+            TypedBuffer::Date(_) => DataType::Date,
+            TypedBuffer::Datetime(_) => DataType::Datetime,
+            TypedBuffer::Duration(_) => DataType::Duration,
+            TypedBuffer::Time(_) => DataType::Time,
+        }
+    }
+
+    fn get(&self, index: usize) -> AnyType {
+        match self {
+            TypedBuffer::Null => AnyType::Null,
+            TypedBuffer::Boolean(values) => AnyType::Boolean(values[index]),
+            TypedBuffer::Utf8(values) => AnyType::Utf8(Rc::clone(&values[index])),
+            TypedBuffer::UInt8(values) => AnyType::UInt8(values[index]),
+            TypedBuffer::UInt16(values) => AnyType::UInt16(values[index]),
+            TypedBuffer::UInt32(values) => AnyType::UInt32(values[index]),
+            TypedBuffer::UInt64(values) => AnyType::UInt64(values[index]),
+            TypedBuffer::USize(values) => AnyType::USize(values[index]),
+            TypedBuffer::Int8(values) => AnyType::Int8(values[index]),
+            TypedBuffer::Int16(values) => AnyType::Int16(values[index]),
+            TypedBuffer::Int32(values) => AnyType::Int32(values[index]),
+            TypedBuffer::Int64(values) => AnyType::Int64(values[index]),
+            TypedBuffer::ISize(values) => AnyType::ISize(values[index]),
+            TypedBuffer::Float32(values) => AnyType::Float32(values[index]),
+            TypedBuffer::Float64(values) => AnyType::Float64(values[index]),
+            TypedBuffer::Date(values) => AnyType::Date(values[index]),
+            TypedBuffer::Datetime(values) => AnyType::Datetime(values[index]),
+            TypedBuffer::Duration(values) => AnyType::Duration(values[index]),
+            TypedBuffer::Time(values) => AnyType::Time(values[index]),
+        }
+    }
+
+    fn push_placeholder(&mut self) {
+        match self {
+            TypedBuffer::Null => {},
+            TypedBuffer::Boolean(values) => values.push(Default::default()),
+            TypedBuffer::Utf8(values) => values.push(Default::default()),
+            TypedBuffer::UInt8(values) => values.push(Default::default()),
+            TypedBuffer::UInt16(values) => values.push(Default::default()),
+            TypedBuffer::UInt32(values) => values.push(Default::default()),
+            TypedBuffer::UInt64(values) => values.push(Default::default()),
+            TypedBuffer::USize(values) => values.push(Default::default()),
+            TypedBuffer::Int8(values) => values.push(Default::default()),
+            TypedBuffer::Int16(values) => values.push(Default::default()),
+            TypedBuffer::Int32(values) => values.push(Default::default()),
+            TypedBuffer::Int64(values) => values.push(Default::default()),
+            TypedBuffer::ISize(values) => values.push(Default::default()),
+            TypedBuffer::Float32(values) => values.push(Default::default()),
+            TypedBuffer::Float64(values) => values.push(Default::default()),
+            TypedBuffer::Date(values) => values.push(Default::default()),
+            TypedBuffer::Datetime(values) => values.push(Default::default()),
+            TypedBuffer::Duration(values) => values.push(Default::default()),
+            TypedBuffer::Time(values) => values.push(Default::default()),
+        }
+    }
+
+    fn remove(&mut self, index: usize) {
+        match self {
+            TypedBuffer::Null => {},
+            TypedBuffer::Boolean(values) => { values.remove(index); },
+            TypedBuffer::Utf8(values) => { values.remove(index); },
+            TypedBuffer::UInt8(values) => { values.remove(index); },
+            TypedBuffer::UInt16(values) => { values.remove(index); },
+            TypedBuffer::UInt32(values) => { values.remove(index); },
+            TypedBuffer::UInt64(values) => { values.remove(index); },
+            TypedBuffer::USize(values) => { values.remove(index); },
+            TypedBuffer::Int8(values) => { values.remove(index); },
+            TypedBuffer::Int16(values) => { values.remove(index); },
+            TypedBuffer::Int32(values) => { values.remove(index); },
+            TypedBuffer::Int64(values) => { values.remove(index); },
+            TypedBuffer::ISize(values) => { values.remove(index); },
+            TypedBuffer::Float32(values) => { values.remove(index); },
+            TypedBuffer::Float64(values) => { values.remove(index); },
+            TypedBuffer::Date(values) => { values.remove(index); },
+            TypedBuffer::Datetime(values) => { values.remove(index); },
+            TypedBuffer::Duration(values) => { values.remove(index); },
+            TypedBuffer::Time(values) => { values.remove(index); },
+        }
+    }
+}
+
+// values are stored contiguously by type with nulls recorded in the validity bitmap,
+// null positions hold a placeholder in the typed buffer so every index lines up with its row
+#[derive(Debug, Clone)]
+pub struct ColumnBuffer {
+    values: TypedBuffer,
+    validity: Bitmap,
+}
+
+impl Default for ColumnBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ColumnBuffer {
+    pub fn new() -> Self {
+        Self::with_dtype(DataType::Null)
+    }
+
+    // a buffer created with a type only accepts values of that type,
+    // one created as Null takes the type of the first non-null value pushed to it
+    pub fn with_dtype(dtype: DataType) -> Self {
+        Self {
+            values: TypedBuffer::with_placeholders(dtype, 0),
+            validity: Bitmap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.validity.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.validity.is_empty()
+    }
+
+    pub fn null_count(&self) -> usize {
+        self.validity.count_unset()
+    }
+
+    pub fn dtype(&self) -> DataType {
+        self.values.dtype()
+    }
+
+    pub fn get_values(&self) -> &TypedBuffer {
+        &self.values
+    }
+
+    pub fn get_validity(&self) -> &Bitmap {
+        &self.validity
+    }
+
+    pub fn is_valid(&self, index: usize) -> bool {
+        self.validity.get(index)
+    }
+
+    pub fn get(&self, index: usize) -> Option<AnyType> {
+        if index >= self.len() {
+            return None;
+        }
+        if !self.validity.get(index) {
+            return Some(AnyType::Null);
+        }
+        Some(self.values.get(index))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = AnyType> + '_ {
+        (0..self.len()).map(move |index| if self.validity.get(index) {
+            self.values.get(index)
+        } else {
+            AnyType::Null
+        })
+    }
+
+    // the buffer takes the type of the first non-null value pushed to it,
+    // on a type mismatch the buffer's type is returned and nothing is stored
+    pub fn push(&mut self, value: AnyType) -> Result<(), DataType> {
+        if value == AnyType::Null {
+            self.values.push_placeholder();
+            self.validity.push(false);
+            return Ok(());
+        }
+        if let TypedBuffer::Null = self.values {
+            self.values = TypedBuffer::with_placeholders(value.dtype(), self.len());
+        }
+        match (&mut self.values, value) {
+            (TypedBuffer::Boolean(values), AnyType::Boolean(value)) => values.push(value),
+            (TypedBuffer::Utf8(values), AnyType::Utf8(value)) => values.push(value),
+            (TypedBuffer::UInt8(values), AnyType::UInt8(value)) => values.push(value),
+            (TypedBuffer::UInt16(values), AnyType::UInt16(value)) => values.push(value),
+            (TypedBuffer::UInt32(values), AnyType::UInt32(value)) => values.push(value),
+            (TypedBuffer::UInt64(values), AnyType::UInt64(value)) => values.push(value),
+            (TypedBuffer::USize(values), AnyType::USize(value)) => values.push(value),
+            (TypedBuffer::Int8(values), AnyType::Int8(value)) => values.push(value),
+            (TypedBuffer::Int16(values), AnyType::Int16(value)) => values.push(value),
+            (TypedBuffer::Int32(values), AnyType::Int32(value)) => values.push(value),
+            (TypedBuffer::Int64(values), AnyType::Int64(value)) => values.push(value),
+            (TypedBuffer::ISize(values), AnyType::ISize(value)) => values.push(value),
+            (TypedBuffer::Float32(values), AnyType::Float32(value)) => values.push(value),
+            (TypedBuffer::Float64(values), AnyType::Float64(value)) => values.push(value),
+            (TypedBuffer::Date(values), AnyType::Date(value)) => values.push(value),
+            (TypedBuffer::Datetime(values), AnyType::Datetime(value)) => values.push(value),
+            (TypedBuffer::Duration(values), AnyType::Duration(value)) => values.push(value),
+            (TypedBuffer::Time(values), AnyType::Time(value)) => values.push(value),
+            (values, _) => return Err(values.dtype()),
+        }
+        self.validity.push(true);
+        Ok(())
+    }
+
+    pub fn accepts(&self, value: &AnyType) -> bool {
+        *value == AnyType::Null || self.dtype() == DataType::Null || self.dtype() == value.dtype()
+    }
+
+    // replaces the value at an index which must be in range, returning the previous value
+    // or the buffer's type if the new value does not match it
+    pub fn set(&mut self, index: usize, value: AnyType) -> Result<AnyType, DataType> {
+        let previous = self.get(index).unwrap_or(AnyType::Null);
+        if value == AnyType::Null {
+            self.validity.set(index, false);
+            return Ok(previous);
+        }
+        if let TypedBuffer::Null = self.values {
+            self.values = TypedBuffer::with_placeholders(value.dtype(), self.len());
+        }
+        match (&mut self.values, value) {
+            (TypedBuffer::Boolean(values), AnyType::Boolean(value)) => values[index] = value,
+            (TypedBuffer::Utf8(values), AnyType::Utf8(value)) => values[index] = value,
+            (TypedBuffer::UInt8(values), AnyType::UInt8(value)) => values[index] = value,
+            (TypedBuffer::UInt16(values), AnyType::UInt16(value)) => values[index] = value,
+            (TypedBuffer::UInt32(values), AnyType::UInt32(value)) => values[index] = value,
+            (TypedBuffer::UInt64(values), AnyType::UInt64(value)) => values[index] = value,
+            (TypedBuffer::USize(values), AnyType::USize(value)) => values[index] = value,
+            (TypedBuffer::Int8(values), AnyType::Int8(value)) => values[index] = value,
+            (TypedBuffer::Int16(values), AnyType::Int16(value)) => values[index] = value,
+            (TypedBuffer::Int32(values), AnyType::Int32(value)) => values[index] = value,
+            (TypedBuffer::Int64(values), AnyType::Int64(value)) => values[index] = value,
+            (TypedBuffer::ISize(values), AnyType::ISize(value)) => values[index] = value,
+            (TypedBuffer::Float32(values), AnyType::Float32(value)) => values[index] = value,
+            (TypedBuffer::Float64(values), AnyType::Float64(value)) => values[index] = value,
+            (TypedBuffer::Date(values), AnyType::Date(value)) => values[index] = value,
+            (TypedBuffer::Datetime(values), AnyType::Datetime(value)) => values[index] = value,
+            (TypedBuffer::Duration(values), AnyType::Duration(value)) => values[index] = value,
+            (TypedBuffer::Time(values), AnyType::Time(value)) => values[index] = value,
+            (values, _) => return Err(values.dtype()),
+        }
+        self.validity.set(index, true);
+        Ok(previous)
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<AnyType> {
+        let value = self.get(index)?;
+        self.values.remove(index);
+        self.validity.remove(index);
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn push_and_get() {
+        let mut buffer = ColumnBuffer::new();
+        buffer.push(AnyType::Null).unwrap();
+        buffer.push(6u8.into()).unwrap();
+        buffer.push(AnyType::Null).unwrap();
+
+        assert_eq!(buffer.dtype(), DataType::UInt8);
+        assert_eq!(buffer.len(), 3);
+        assert_eq!(buffer.null_count(), 2);
+        assert!(buffer.get(0) == Some(AnyType::Null));
+        assert!(buffer.get(1) == Some(6u8.into()));
+        assert!(buffer.get(3).is_none());
+        assert!(matches!(buffer.get_values(), TypedBuffer::UInt8(values) if values.len() == 3));
+    }
+
+    #[test]
+    fn push_type_mismatch() {
+        let mut buffer = ColumnBuffer::new();
+        buffer.push("whoop".into()).unwrap();
+
+        assert_eq!(buffer.push(6u8.into()), Err(DataType::Utf8));
+        assert_eq!(buffer.len(), 1);
+    }
+
+    #[test]
+    fn remove() {
+        let mut buffer = ColumnBuffer::new();
+        buffer.push(6i64.into()).unwrap();
+        buffer.push(AnyType::Null).unwrap();
+        buffer.push(8i64.into()).unwrap();
+
+        assert!(buffer.remove(1) == Some(AnyType::Null));
+        assert!(buffer.iter().collect::<Vec<AnyType>>() == vec![6i64.into(), 8i64.into()]);
+        assert_eq!(buffer.null_count(), 0);
+    }
+
+    #[test]
+    fn set() {
+        let mut buffer = ColumnBuffer::new();
+        buffer.push(AnyType::Null).unwrap();
+        buffer.push(AnyType::Null).unwrap();
+
+        assert!(buffer.set(1, 6i64.into()) == Ok(AnyType::Null));
+        assert_eq!(buffer.dtype(), DataType::Int64);
+        assert!(buffer.set(1, AnyType::Null) == Ok(6i64.into()));
+        assert_eq!(buffer.set(0, "whoop".into()), Err(DataType::Int64));
+        assert_eq!(buffer.null_count(), 2);
+    }
+}
@@ -1,16 +1,26 @@
 #![allow(unused_assignments)]
 
-pub mod types;
+pub mod aggregate;
+pub mod buffer;
+pub mod rolling;
 
 use crate::cell::{
-    RcCell,
+    Cell,
     types::datatypes::{
         AnyType,
+        DataType,
     },
 };
-use std::cell::{
-    RefCell,
-    Ref,
+use crate::error::{
+    DataFrameError,
+    Result,
+};
+use crate::schema::Field;
+use buffer::ColumnBuffer;
+use rolling::{
+    Rolling,
+    RollingAggregation,
+    RollingWindow,
 };
 use std::rc::{ Rc };
 use std::collections::HashMap;
@@ -19,10 +29,13 @@ use chrono::{
     Utc
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RollingMean {
     should_calculate: bool,
-    mean_over: usize
+    mean_over: usize,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::optional_nanos"))]
+    duration: Option<chrono::Duration>
 }
 
 impl RollingMean {
@@ -35,235 +48,552 @@ impl RollingMean {
         }
         Self {
             should_calculate,
-            mean_over
+            mean_over,
+            duration: None
+        }
+    }
+
+    // mean of the values in rows stamped within the duration of each row
+    pub fn over_duration(duration: chrono::Duration) -> Self {
+        Self {
+            should_calculate: true,
+            mean_over: 0,
+            duration: Some(duration)
+        }
+    }
+
+    pub fn should_calculate(&self) -> bool {
+        self.should_calculate
+    }
+
+    pub fn mean_over(&self) -> usize {
+        self.mean_over
+    }
+
+    pub fn duration(&self) -> Option<chrono::Duration> {
+        self.duration
+    }
+
+    // a count based rolling mean needs every row in it to be present, a duration based one any row
+    pub(crate) fn window(&self) -> Option<RollingWindow> {
+        if !self.should_calculate {
+            return None;
+        }
+        match self.duration {
+            Some(duration) if duration > chrono::Duration::zero() => Some(RollingWindow::over_duration("rolling_mean", duration, RollingAggregation::Mean)),
+            Some(_) => None,
+            None if self.mean_over == 0 => None,
+            None => Some(RollingWindow::new("rolling_mean", self.mean_over, RollingAggregation::Mean)),
         }
     }
 }
 
-#[derive(Debug)]
+// how each returns value is taken from a value and the one the given number of rows before it
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ReturnsKind {
+    // current - previous, in the column's own arithmetic
+    Difference(usize),
+    // current / previous - 1 as a Float64
+    Percentage(usize),
+    // ln(current / previous) as a Float64
+    Log(usize),
+}
+
+impl ReturnsKind {
+    pub fn lag(&self) -> usize {
+        match self {
+            ReturnsKind::Difference(lag) | ReturnsKind::Percentage(lag) | ReturnsKind::Log(lag) => *lag,
+        }
+    }
+}
+
+impl Default for ReturnsKind {
+    fn default() -> Self {
+        ReturnsKind::Difference(1)
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Returns {
     pub should_calculate: bool,
-    pub column_name: Option<&'static str>
+    pub column_name: Option<Rc<str>>,
+    pub kind: ReturnsKind
 }
 
 impl Returns {
-    pub fn new(should_calculate: bool, column_name: Option<&'static str>) -> Self {
+    pub fn new(should_calculate: bool, column_name: Option<&str>) -> Self {
         Self {
             should_calculate,
-            column_name
+            column_name: column_name.map(Rc::from),
+            kind: ReturnsKind::default()
         }
     } 
+
+    pub fn with_kind(mut self, kind: ReturnsKind) -> Self {
+        self.kind = kind;
+        self
+    }
 }
 
+pub type RateOfChange = (Vec<(f64, f64)>, Vec<(f64, f64)>);
+
 #[derive(Debug)]
 pub struct Column {
-    cells: RefCell<Vec<RcCell>>,
-    grouped_values: HashMap<AnyType, RefCell<Vec<RcCell>>>,
-    pub name: &'static str,
+    buffer: ColumnBuffer,
+    mean_window: Option<Rolling>,
+    rolling_windows: Vec<Rolling>,
+    grouped_values: HashMap<AnyType, Vec<usize>>,
+    nullable: bool,
+    pub name: Rc<str>,
     pub rolling_mean: RollingMean,
     pub returns: Returns
 }
 
 impl Column {
-    pub fn new(name: &'static str, rolling_mean: RollingMean, returns: Returns) -> Self {
+    pub fn new<S: Into<Rc<str>>>(name: S, rolling_mean: RollingMean, returns: Returns) -> Self {
+        Self::from_field(Field::new(name, DataType::Null, true), rolling_mean, returns)
+    }
+
+    pub fn from_field(field: Field, rolling_mean: RollingMean, returns: Returns) -> Self {
         Self {
-            cells: RefCell::new(vec![]),
+            buffer: ColumnBuffer::with_dtype(field.dtype),
+            mean_window: rolling_mean.window().map(Rolling::new),
+            rolling_windows: vec![],
             grouped_values: HashMap::new(),
-            name,
+            nullable: field.nullable,
+            name: field.name,
             rolling_mean,
             returns
         }
     }
 
-    pub fn get_cells(&self) -> Ref<Vec<RcCell>> {
-        self.cells.borrow()
+    // a plain column holding the values of the given rows in that order, without any rolling windows or returns
+    pub fn take(&self, indices: &[usize]) -> Result<Column> {
+        let mut column = Column::from_field(self.get_field(), RollingMean::new(false, None), Returns::new(false, None));
+        for index in indices.iter() {
+            let value = self.get_value(*index)
+                .ok_or(DataFrameError::IndexOutOfRange { index: *index, len: self.len() })?;
+            column.push(value)?;
+        }
+        Ok(column)
     }
 
-    pub fn add_cell(&mut self, cell: &RcCell) {
-        self.add_to_grouped_values(cell);
-        self.cells.borrow_mut().push(Rc::clone(cell));
-        if self.rolling_mean.should_calculate {
-            self.cell_rolling_mean(self.rolling_mean.mean_over, cell);
+    // the same column with its rows in the given order, rolling windows are calculated afresh over the new order
+    // apart from duration windows, which still take the rows in time order
+    pub fn reorder(&self, indices: &[usize], datetimes: &[DateTime<Utc>]) -> Result<Column> {
+        let mut column = Column::from_field(self.get_field(), RollingMean::new(false, None), self.returns.clone());
+        for index in indices.iter() {
+            let value = self.get_value(*index)
+                .ok_or(DataFrameError::IndexOutOfRange { index: *index, len: self.len() })?;
+            column.push(value)?;
         }
+        column.rolling_mean = self.rolling_mean.clone();
+        column.mean_window = self.rolling_mean.window().map(Rolling::new);
+        column.rolling_windows = self.rolling_windows.iter().map(|rolling| Rolling::new(rolling.get_window().clone())).collect();
+        column.rebuild_rolling_windows(datetimes);
+        Ok(column)
     }
 
-    pub fn drop_cell(&mut self, cell: RcCell) {
-        self.remove_from_grouped_values(&cell);
-        let mut cells = self.cells.borrow_mut();
-        let cell_location = cell.borrow().get_row().borrow().index;
-        cells.remove(cell_location);
-        // drop before calling recalculate so we can free the vec which needs to be borrowed later on
-        drop(cells);
-        self.recalculate_rolling_means(&cell_location);
+    pub fn get_field(&self) -> Field {
+        Field::new(Rc::clone(&self.name), self.dtype(), self.nullable)
     }
 
-    fn recalculate_rolling_means(&self, cell_location: &usize) {
-        if self.rolling_mean.should_calculate {
-            let cells_to_recalculate = &self.cells.borrow()[*cell_location..(cell_location + self.rolling_mean.mean_over - 1)];
-            for cell in cells_to_recalculate.iter() {
-                self.cell_rolling_mean(self.rolling_mean.mean_over, cell);
-            }
+    pub fn is_nullable(&self) -> bool {
+        self.nullable
+    }
+
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    pub fn dtype(&self) -> DataType {
+        self.buffer.dtype()
+    }
+
+    pub fn get_buffer(&self) -> &ColumnBuffer {
+        &self.buffer
+    }
+
+    pub fn get_value(&self, index: usize) -> Option<AnyType> {
+        self.buffer.get(index)
+    }
+
+    pub fn iter_values(&self) -> impl Iterator<Item = AnyType> + '_ {
+        self.buffer.iter()
+    }
+
+    pub fn has_rolling_mean(&self) -> bool {
+        self.mean_window.is_some()
+    }
+
+    pub fn get_rolling_mean(&self, index: usize) -> Option<AnyType> {
+        self.mean_window.as_ref()?.get(index).map(|mean| AnyType::from_f64(self.dtype(), mean))
+    }
+
+    pub fn get_rolling_value(&self, name: &str, index: usize) -> Option<AnyType> {
+        let dtype = self.dtype();
+        self.rolling_windows.iter().find(|rolling| rolling.name() == name)?.get_as(index, dtype)
+    }
+
+    pub fn get_rolling_windows(&self) -> impl Iterator<Item = &RollingWindow> + '_ {
+        self.rolling_windows.iter().map(|rolling| rolling.get_window())
+    }
+
+    // datetimes are those of the rows already in the column, only needed by duration windows
+    pub fn add_rolling_window(&mut self, window: RollingWindow, datetimes: &[DateTime<Utc>]) -> Result<()> {
+        self.restore_rolling_window(window, datetimes, None)
+    }
+
+    // results saved with the window, one for each row, are taken as they are rather than calculated again
+    pub fn restore_rolling_window(&mut self, window: RollingWindow, datetimes: &[DateTime<Utc>], results: Option<Vec<Option<f64>>>) -> Result<()> {
+        window.validate()?;
+        match self.dtype() {
+            DataType::Utf8 | DataType::Date | DataType::Datetime | DataType::Time => return Err(DataFrameError::UnsupportedDtype {
+                column: self.name.to_string(),
+                operation: "rolling",
+                dtype: self.dtype(),
+            }),
+            _ => {},
         }
+        if self.rolling_windows.iter().any(|rolling| rolling.name() == &*window.name) {
+            return Err(DataFrameError::InvalidArgument(format!("column '{}' already has a rolling window named '{}'", self.name, window.name)));
+        }
+        let mut rolling = Rolling::new(window);
+        self.fill(&mut rolling, datetimes, results)?;
+        self.rolling_windows.push(rolling);
+        Ok(())
     }
 
-    fn add_to_grouped_values(&mut self, cell: &RcCell) {
-        if let Some(entry) = self.grouped_values.get_mut(cell.borrow().get_value()) {
-            entry.borrow_mut().push(Rc::clone(cell));
-        } else {
-            self.grouped_values.insert(cell.borrow().get_value().clone(), RefCell::new(vec![Rc::clone(cell)]));
+    // the results of the rolling mean, if there is one, followed by those of each rolling window
+    pub fn get_rolling_results(&self) -> impl Iterator<Item = &[Option<f64>]> + '_ {
+        self.mean_window.iter().chain(self.rolling_windows.iter()).map(|rolling| rolling.results())
+    }
+
+    fn fill(&self, rolling: &mut Rolling, datetimes: &[DateTime<Utc>], results: Option<Vec<Option<f64>>>) -> Result<()> {
+        match results {
+            Some(results) if results.len() != self.len() => return Err(DataFrameError::ArityMismatch { expected: self.len(), found: results.len() }),
+            Some(results) => rolling.restore(results, timed_values(&self.buffer, datetimes)),
+            None => rolling.rebuild(timed_values(&self.buffer, datetimes)),
         }
+        Ok(())
     }
 
-    pub fn get_grouped_values(&self, value: AnyType) -> Option<&RefCell<Vec<RcCell>>> {
-        self.grouped_values.get(&value)
+    pub fn remove_rolling_window(&mut self, name: &str) -> Option<RollingWindow> {
+        let index = self.rolling_windows.iter().position(|rolling| rolling.name() == name)?;
+        Some(self.rolling_windows.remove(index).get_window().clone())
+    }
+
+    pub fn get_cell(&self, index: usize) -> Option<Cell> {
+        let value = self.buffer.get(index)?;
+        let dtype = self.dtype();
+        let rolling_values = self.rolling_windows.iter()
+            .filter_map(|rolling| rolling.get_as(index, dtype).map(|value| (Rc::clone(&rolling.get_window().name), value)))
+            .collect();
+        Some(Cell::new(value, index, Rc::clone(&self.name), self.get_rolling_mean(index)).with_rolling_values(rolling_values))
+    }
+
+    pub fn accepts(&self, value: &AnyType) -> bool {
+        (self.nullable || *value != AnyType::Null) && self.buffer.accepts(value)
     }
 
-    fn remove_from_grouped_values(& mut self, cell: &RcCell) {
-        if let Some(entry) = self.grouped_values.get_mut(cell.borrow().get_value()) {
-            entry.borrow_mut().retain(|c| c != cell);
+    pub fn check_value(&self, value: &AnyType) -> Result<()> {
+        if !self.accepts(value) {
+            return Err(DataFrameError::TypeMismatch {
+                column: self.name.to_string(),
+                expected: self.dtype(),
+                found: value.dtype(),
+            });
         }
+        Ok(())
     }
 
-    pub fn mean(&self) -> Option<AnyType> {
-        let cells = self.cells.borrow();
-        let cell = cells[0].borrow();
-        let value = cell.get_value();
-        match value {
-            AnyType::Null => None,
-            AnyType::Boolean(_) => types::bool::mean(self),
-            AnyType::Float32(_) => types::f32::mean(self),
-            AnyType::Float64(_) => types::f64::mean(self),
-            AnyType::Int8(_) => types::i8::mean(self),
-            AnyType::Int16(_) => types::i16::mean(self),
-            AnyType::Int32(_) => types::i32::mean(self),
-            AnyType::Int64(_) => types::i64::mean(self),
-            AnyType::ISize(_) => types::isize::mean(self),
-            AnyType::UInt8(_) => types::u8::mean(self),
-            AnyType::UInt16(_) => types::u16::mean(self),
-            AnyType::UInt32(_) => types::u32::mean(self),
-            AnyType::UInt64(_) => types::u64::mean(self),
-            AnyType::USize(_) => types::usize::mean(self),
-            AnyType::Utf8(_) => types::utf8::mean(self),
+    // rows are added to duration windows in time order, so none can be stamped before the latest row
+    pub fn check_datetime(&self, datetime: DateTime<Utc>) -> Result<()> {
+        match self.mean_window.iter().chain(self.rolling_windows.iter()).find(|rolling| !rolling.accepts(Some(datetime))) {
+            Some(rolling) => Err(DataFrameError::InvalidArgument(format!(
+                "column '{}' has the duration window '{}' so cannot take a row stamped before its latest one",
+                self.name,
+                rolling.name()
+            ))),
+            None => Ok(()),
         }
     }
 
-    pub fn update_rolling_mean(&mut self, rolling_mean: RollingMean) {
-        if rolling_mean.should_calculate != self.rolling_mean.should_calculate || rolling_mean.mean_over != self.rolling_mean.mean_over {
-            self.rolling_mean = rolling_mean;
+    pub fn push(&mut self, value: AnyType) -> Result<()> {
+        self.push_with_datetime(value, None)
+    }
+
+    pub fn push_at(&mut self, value: AnyType, datetime: DateTime<Utc>) -> Result<()> {
+        self.push_with_datetime(value, Some(datetime))
+    }
+
+    fn push_with_datetime(&mut self, value: AnyType, datetime: Option<DateTime<Utc>>) -> Result<()> {
+        self.check_value(&value)?;
+        if let Some(datetime) = datetime {
+            self.check_datetime(datetime)?;
+        }
+        let index = self.buffer.len();
+        self.buffer.push(value.clone()).map_err(|expected| DataFrameError::TypeMismatch {
+            column: self.name.to_string(),
+            expected,
+            found: value.dtype(),
+        })?;
+        let numeric = value.as_f64();
+        self.add_to_grouped_values(value, index);
+        for rolling in self.mean_window.iter_mut().chain(self.rolling_windows.iter_mut()) {
+            rolling.push(numeric, datetime);
         }
+        Ok(())
+    }
 
-        for cell in self.cells.borrow().iter() {
-            if self.rolling_mean.should_calculate {
-                self.cell_rolling_mean(self.rolling_mean.mean_over, &cell);
-            } else {
-                cell.borrow_mut().set_rolling_mean(None);
-            }            
-        }        
+    pub fn remove(&mut self, index: usize) -> Result<AnyType> {
+        let value = self.buffer.remove(index)
+            .ok_or(DataFrameError::IndexOutOfRange { index, len: self.buffer.len() })?;
+        self.remove_from_grouped_values(&value, index);
+        // only the windows that held the removed row are replayed
+        let buffer = &self.buffer;
+        for rolling in self.mean_window.iter_mut().chain(self.rolling_windows.iter_mut()) {
+            rolling.remove(index, buffer.len(), |position| buffer.get(position).and_then(|value| value.as_f64()));
+        }
+        Ok(value)
     }
 
-    pub fn update_returns(&mut self, returns: Returns) -> Option<Vec<AnyType>> {
-        if returns.should_calculate != self.returns.should_calculate {
-            self.returns = returns;
+    // replaces the value in a row, returning the one it held
+    pub fn set(&mut self, index: usize, value: AnyType) -> Result<AnyType> {
+        let mut previous = self.set_many(vec![(index, value)])?;
+        Ok(previous.remove(0))
+    }
+
+    // every update is checked before any is applied, and rolling windows are replayed around each changed row at the end
+    pub fn set_many(&mut self, updates: Vec<(usize, AnyType)>) -> Result<Vec<AnyType>> {
+        self.check_updates(&updates)?;
+
+        let mut previous_values = Vec::with_capacity(updates.len());
+        let mut changed: Vec<usize> = updates.iter().map(|(index, _)| *index).collect();
+        changed.sort_unstable();
+        changed.dedup();
+        for (index, value) in updates.into_iter() {
+            let previous = self.buffer.set(index, value.clone()).map_err(|expected| DataFrameError::TypeMismatch {
+                column: self.name.to_string(),
+                expected,
+                found: value.dtype(),
+            })?;
+            if let Some(entry) = self.grouped_values.get_mut(&previous) {
+                entry.retain(|i| *i != index);
+                if entry.is_empty() {
+                    self.grouped_values.remove(&previous);
+                }
+            }
+            let entry = self.grouped_values.entry(value).or_default();
+            let position = entry.partition_point(|i| *i < index);
+            entry.insert(position, index);
+            previous_values.push(previous);
         }
+        let buffer = &self.buffer;
+        for rolling in self.mean_window.iter_mut().chain(self.rolling_windows.iter_mut()) {
+            for index in changed.iter() {
+                rolling.update(*index, buffer.len(), |position| buffer.get(position).and_then(|value| value.as_f64()));
+            }
+        }
+        Ok(previous_values)
+    }
 
-        if self.returns.should_calculate {
-            let differences = self.get_all_difference_to_last();
-            return Some(differences);
-        } else {
-            return None;
+    // a column without a type takes the first non-null value's, which later values then have to match
+    pub fn check_updates(&self, updates: &[(usize, AnyType)]) -> Result<()> {
+        let mut dtype = self.dtype();
+        for (index, value) in updates.iter() {
+            if *index >= self.len() {
+                return Err(DataFrameError::IndexOutOfRange { index: *index, len: self.len() });
+            }
+            let accepted = match value {
+                AnyType::Null => self.nullable,
+                value => dtype == DataType::Null || dtype == value.dtype(),
+            };
+            if !accepted {
+                return Err(DataFrameError::TypeMismatch {
+                    column: self.name.to_string(),
+                    expected: dtype,
+                    found: value.dtype(),
+                });
+            }
+            if dtype == DataType::Null {
+                dtype = value.dtype();
+            }
         }
-        
+        Ok(())
     }
 
-    pub fn cell_rolling_mean(&self, mean_over: usize, cell: &RcCell) -> Option<AnyType> {
-        let mut rolling_mean = None;
-        let cells = &self.cells.borrow();
-        let cell_location = cell.borrow().get_row().borrow().index;
-        if cells.len() < mean_over || cell_location == 0 {
-            rolling_mean = None;
-        } else {            
-            let column_slice = &cells[(cell_location - (mean_over - 1))..=cell_location];
-            let value: Vec<AnyType> = self.sum_slice_values(column_slice);
-            rolling_mean = Some(value[0] / value[1]);
+    pub fn rebuild_rolling_windows(&mut self, datetimes: &[DateTime<Utc>]) {
+        for rolling in self.mean_window.iter_mut().chain(self.rolling_windows.iter_mut()) {
+            rolling.rebuild(timed_values(&self.buffer, datetimes));
         }
-        cell.borrow_mut().set_rolling_mean(rolling_mean);
-        rolling_mean        
     }
 
-    fn sum_slice_values(&self, slice: &[RcCell]) -> Vec<AnyType> {
-        slice.iter().fold(vec![AnyType::Null, 0usize.into()], |mut acc, x| if x.borrow().get_value() != &AnyType::Null {
-            acc[0] = acc[0] + *x.borrow().get_value();
-            acc[1] = acc[1] + 1usize.into();
-            acc
+    fn add_to_grouped_values(&mut self, value: AnyType, index: usize) {
+        self.grouped_values.entry(value).or_default().push(index);
+    }
+
+    pub fn get_grouped_values(&self, value: AnyType) -> Option<&Vec<usize>> {
+        self.grouped_values.get(&value)
+    }
+
+    fn remove_from_grouped_values(&mut self, value: &AnyType, index: usize) {
+        if let Some(entry) = self.grouped_values.get_mut(value) {
+            entry.retain(|i| *i != index);
+            if entry.is_empty() {
+                self.grouped_values.remove(value);
+            }
+        }
+        // every row after the one removed has moved up by one
+        for entry in self.grouped_values.values_mut() {
+            for i in entry.iter_mut().filter(|i| **i > index) {
+                *i -= 1;
+            }
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        aggregate::count(self)
+    }
+
+    pub fn null_count(&self) -> usize {
+        self.buffer.null_count()
+    }
+
+    pub fn sum(&self) -> Result<Option<AnyType>> {
+        aggregate::sum(self)
+    }
+
+    pub fn mean(&self) -> Result<Option<AnyType>> {
+        aggregate::mean(self)
+    }
+
+    pub fn min(&self) -> Result<Option<AnyType>> {
+        aggregate::min(self)
+    }
+
+    pub fn max(&self) -> Result<Option<AnyType>> {
+        aggregate::max(self)
+    }
+
+    pub fn variance(&self) -> Result<Option<AnyType>> {
+        aggregate::variance(self)
+    }
+
+    pub fn std(&self) -> Result<Option<AnyType>> {
+        aggregate::std(self)
+    }
+
+    // results saved with the rolling mean, one for each row, are taken as they are rather than calculated again
+    pub fn restore_rolling_mean(&mut self, rolling_mean: RollingMean, datetimes: &[DateTime<Utc>], results: Option<Vec<Option<f64>>>) -> Result<()> {
+        let mut mean_window = rolling_mean.window().map(Rolling::new);
+        if let Some(rolling) = mean_window.as_mut() {
+            self.fill(rolling, datetimes, results)?;
+        }
+        self.rolling_mean = rolling_mean;
+        self.mean_window = mean_window;
+        Ok(())
+    }
+
+    pub fn update_rolling_mean(&mut self, rolling_mean: RollingMean, datetimes: &[DateTime<Utc>]) {
+        self.rolling_mean = rolling_mean;
+        self.mean_window = self.rolling_mean.window().map(Rolling::new);
+        if let Some(rolling) = self.mean_window.as_mut() {
+            rolling.rebuild(timed_values(&self.buffer, datetimes));
+        }
+    }
+
+    pub fn update_returns(&mut self, returns: Returns) -> Option<Vec<AnyType>> {
+        self.returns = returns;
+
+        if self.returns.should_calculate {
+            Some((0..self.len()).map(|index| self.get_returns(index)).collect())
         } else {
-            acc
-        })
+            None
+        }
     }
 
-    pub fn get_all_difference_to_last(&self) -> Vec<AnyType> {
-        let mut differences: Vec<AnyType> = vec![];
-        let cells = self.cells.borrow();
-        for (index, _) in cells.iter().enumerate() {
-            let value = self.get_difference_to_last(index);
-            differences.push(value);
+    // the returns value for a row using the column's returns kind, null until there are enough earlier rows
+    pub fn get_returns(&self, index: usize) -> AnyType {
+        let lag = self.returns.kind.lag();
+        if lag == 0 || index < lag || index >= self.len() {
+            return AnyType::Null;
+        }
+        let previous_value = self.buffer.get(index - lag).unwrap_or(AnyType::Null);
+        let current_value = self.buffer.get(index).unwrap_or(AnyType::Null);
+        let ratio = match (current_value.as_f64(), previous_value.as_f64()) {
+            (Some(current), Some(previous)) if previous != 0f64 => Some(current / previous),
+            _ => None,
+        };
+
+        match self.returns.kind {
+            ReturnsKind::Difference(_) => current_value - previous_value,
+            ReturnsKind::Percentage(_) => ratio.map_or(AnyType::Null, |ratio| AnyType::Float64(ratio - 1f64)),
+            ReturnsKind::Log(_) => ratio.filter(|ratio| *ratio > 0f64).map_or(AnyType::Null, |ratio| AnyType::Float64(ratio.ln())),
         }
-        differences
+    }
+
+    pub fn get_all_difference_to_last(&self) -> Vec<AnyType> {
+        (0..self.len()).map(|index| self.get_difference_to_last(index)).collect()
     }
 
     pub fn get_difference_to_last(&self, index: usize) -> AnyType {
-        let cells = self.cells.borrow();
-        if index == 0 {
+        if index == 0 || index >= self.len() {
             return AnyType::Null;
         }
 
-        let previous_value = cells[index - 1].borrow().get_value().clone();
-        let current_value = cells[index].borrow().get_value().clone();
+        let previous_value = self.buffer.get(index - 1).unwrap_or(AnyType::Null);
+        let current_value = self.buffer.get(index).unwrap_or(AnyType::Null);
 
-        (current_value - previous_value).into()
+        current_value - previous_value
     }
 
-    pub fn get_values_as_vec_with_datetime<T>(&self) -> Vec<(DateTime<Utc>, T)>
+    pub fn get_values_as_vec_with_datetime<T>(&self, datetimes: &[DateTime<Utc>]) -> Vec<(DateTime<Utc>, T)>
     where Option<T>: From<AnyType> {
         let mut values: Vec<(DateTime<Utc>, T)> = vec![];
-        for cell in self.cells.borrow().iter() {
-            let datetime = cell.borrow().get_row().borrow().get_datetime();
-            let value_option: Option<T> = cell.borrow().get_value().clone().into();
+        for (datetime, value) in datetimes.iter().zip(self.buffer.iter()) {
+            let value_option: Option<T> = value.into();
             if let Some(value) = value_option {
-                values.push((datetime, value));
+                values.push((*datetime, value));
             }
         }
 
         values
     }
 
-    pub fn get_values_as_vec_with_unix_datetime<T>(&self) -> Vec<(i64, T)>
+    pub fn get_values_as_vec_with_unix_datetime<T>(&self, datetimes: &[DateTime<Utc>]) -> Vec<(i64, T)>
     where Option<T>: From<AnyType> {
         let mut values: Vec<(i64, T)> = vec![];
-        for cell in self.cells.borrow().iter() {
-            let datetime = cell.borrow().get_row().borrow().get_datetime().timestamp();
-            let value_option: Option<T> = cell.borrow().get_value().clone().into();
+        for (datetime, value) in datetimes.iter().zip(self.buffer.iter()) {
+            let value_option: Option<T> = value.into();
             if let Some(value) = value_option {
-                values.push((datetime, value));
+                values.push((datetime.timestamp(), value));
             }
         }
 
         values
     }
 
-    pub fn get_values_as_vec_with_unix_datetime_diff<T>(&self) -> Vec<(i64, T)>
+    pub fn get_values_as_vec_with_unix_datetime_diff<T>(&self, datetimes: &[DateTime<Utc>]) -> Vec<(i64, T)>
     where Option<T>: From<AnyType>,
     T: Into<f64> {
         let mut values: Vec<(i64, T)> = vec![];
-        for (index, cell) in self.cells.borrow().iter().enumerate() {
-            let mut datetime = cell.borrow().get_row().borrow().get_datetime().timestamp();
-            if index == 0 {
-                datetime = 0i64;
-            } else  {
-                let previous_datetime = values[index - 1].0;
-                datetime = datetime - previous_datetime;
-            }
-            let value_option: Option<T> = cell.borrow().get_value().clone().into();
+        let mut previous_datetime: Option<i64> = None;
+        for (datetime, value) in datetimes.iter().zip(self.buffer.iter()) {
+            let timestamp = datetime.timestamp();
+            let datetime = match previous_datetime {
+                Some(previous) => timestamp - previous,
+                None => 0i64,
+            };
+            let value_option: Option<T> = value.into();
             if let Some(value) = value_option {
+                previous_datetime = Some(timestamp);
                 values.push((datetime, value));
             }
         }
@@ -271,43 +601,45 @@ impl Column {
         values
     }
 
-    pub fn get_rolling_means_as_vec_with_unix_datetime_diff<T>(&self) -> Vec<(f64, f64)>
+    pub fn get_rolling_means_as_vec_with_unix_datetime_diff<T>(&self, datetimes: &[DateTime<Utc>]) -> Vec<(f64, f64)>
     where Option<T>: From<AnyType>,
     T: Into<f64> {
         let mut values: Vec<(f64, f64)> = vec![];
-        let initial_datetime: f64 = self.cells.borrow()[0].borrow().get_row().borrow().get_datetime().timestamp() as f64;
-        for (index, cell) in self.cells.borrow().iter().enumerate() {
-            let mut datetime: f64 = cell.borrow().get_row().borrow().get_datetime().timestamp() as f64;
+        let initial_datetime: f64 = match datetimes.first() {
+            Some(datetime) => datetime.timestamp() as f64,
+            None => return values,
+        };
+        for (index, datetime) in datetimes.iter().enumerate().take(self.len()) {
+            let mut datetime: f64 = datetime.timestamp() as f64;
             if index == 0 {
                 datetime = 0f64;
             } else  {
-                datetime = datetime - initial_datetime;
+                datetime -= initial_datetime;
             }
-            let rolling_mean_option: Option<AnyType> = cell.borrow().get_rolling_mean().clone();
-            if let Some(rolling_mean) = rolling_mean_option {
+            if let Some(rolling_mean) = self.get_rolling_mean(index) {
                 let value_option: Option<T> = rolling_mean.into();
                 if let Some(value) = value_option {
                     values.push((datetime, value.into()));
                 }
-            }            
+            }
         }
 
         values
     }
 
-    pub fn rate_of_change_rolling_means_over_x_elements<T>(&self, x: usize) -> (Vec<(f64, f64)>, Vec<(f64, f64)>)
+    pub fn rate_of_change_rolling_means_over_x_elements<T>(&self, datetimes: &[DateTime<Utc>], x: usize) -> RateOfChange
     where Option<T>: From<AnyType>,
     T: Into<f64> {
-        let rolling_means: Vec<(f64, f64)> = self.get_rolling_means_as_vec_with_unix_datetime_diff::<T>();
+        let rolling_means: Vec<(f64, f64)> = self.get_rolling_means_as_vec_with_unix_datetime_diff::<T>(datetimes);
         let mut rate_of_change: Vec<(f64, f64)> = vec![];
         for (index, (datetime, _)) in rolling_means.iter().enumerate()  {
-            rate_of_change.push((datetime.clone(), self.least_squares(x, index, &rolling_means)));
+            rate_of_change.push((*datetime, self.least_squares(x, index, &rolling_means)));
         }
         (rolling_means, rate_of_change)
     }
 
-    fn least_squares(&self, slice_size: usize, index: usize, rolling_means: &Vec<(f64, f64)>) -> f64 {
-        if index < (slice_size - 1) {
+    fn least_squares(&self, slice_size: usize, index: usize, rolling_means: &[(f64, f64)]) -> f64 {
+        if slice_size == 0 || index + 1 < slice_size {
             0f64
         } else {
             let sums = rolling_means[(index - (slice_size - 1))..=index].iter().fold((0f64, 0f64), |mut acc, (datetime, rolling_mean)| {
@@ -329,143 +661,264 @@ impl Column {
     } 
 }
 
+fn timed_values<'a>(buffer: &'a ColumnBuffer, datetimes: &'a [DateTime<Utc>]) -> impl Iterator<Item = (Option<f64>, Option<DateTime<Utc>>)> + 'a {
+    buffer.iter().enumerate().map(|(index, value)| (value.as_f64(), datetimes.get(index).copied()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::cell::{
         types::datatypes::AnyType,
-        Cell,
-        RcCell,
     };
-    use crate::row::{
-        Row,
-        RcRow,
+    use crate::clock::{
+        Clock,
+        ManualClock,
     };
-    use core::time;
-    use std::rc::{ Rc };
-    use std::thread;
+    use chrono::TimeZone;
     #[test]
-    fn add_cell() {
+    fn push() {
         let value: AnyType = 67u16.into();
-        let row: RcRow = Row::new(3);
-        let cell: RcCell = Cell::new(value, &row, "timmeh");
 
         let mut column = Column::new("timmeh", RollingMean::new(false, None), Returns::new(false, None));
-        column.add_cell(&cell);
+        column.push(value.clone()).unwrap();
 
-        assert!(column.get_grouped_values(value) == Some(&RefCell::new(vec![Rc::clone(&cell)])));
-        assert!(column.get_cells()[0] == cell);
+        assert!(column.get_grouped_values(value.clone()) == Some(&vec![0]));
+        assert!(column.get_value(0) == Some(value));
+        assert_eq!(column.get_cell(0).unwrap().get_column_name(), "timmeh");
+        assert_eq!(column.dtype(), DataType::UInt16);
     }
 
     #[test]
-    fn add_cell_rolling_mean() {
-        let row: RcRow = Row::new(0);
-        let cell: RcCell = Cell::new(67u16.into(), &row, "timmeh");
-        let second_row: RcRow = Row::new(1);
-        let second_cell: RcCell = Cell::new(69u16.into(), &second_row, "timmeh");
+    fn push_type_mismatch() {
+        let mut column = Column::new("timmeh", RollingMean::new(false, None), Returns::new(false, None));
+        column.push(AnyType::Null).unwrap();
+        column.push(67u16.into()).unwrap();
+
+        assert_eq!(column.push("whoop".into()), Err(DataFrameError::TypeMismatch {
+            column: "timmeh".to_string(),
+            expected: DataType::UInt16,
+            found: DataType::Utf8,
+        }));
+        assert_eq!(column.len(), 2);
+    }
+
+    #[test]
+    fn push_owned_string() {
+        let mut column = Column::new("timmeh", RollingMean::new(false, None), Returns::new(false, None));
+        column.push(String::from("whoop").into()).unwrap();
+        column.push(format!("{}p", "whoo").into()).unwrap();
+
+        assert_eq!(column.get_grouped_values("whoop".into()).unwrap().len(), 2);
+    }
 
+    #[test]
+    fn push_rolling_mean() {
         let mut column = Column::new("timmeh", RollingMean::new(true, Some(2)), Returns::new(false, None));
-        column.add_cell(&cell);
-        column.add_cell(&second_cell);
+        column.push(67u16.into()).unwrap();
+        column.push(69u16.into()).unwrap();
 
-        assert!(column.get_cells()[0].borrow().get_rolling_mean() == None);
-        assert!(column.get_cells()[1].borrow().get_rolling_mean() == Some(68u16.into()));
+        assert!(column.get_rolling_mean(0).is_none());
+        assert!(column.get_rolling_mean(1) == Some(68u16.into()));
+        assert!(column.get_cell(1).unwrap().get_rolling_mean() == Some(68u16.into()));
     }
 
     #[test]
     fn update_rolling_mean() {
-        let row: RcRow = Row::new(0);
-        let cell: RcCell = Cell::new(67u16.into(), &row, "timmeh");
-        let second_row: RcRow = Row::new(1);
-        let second_cell: RcCell = Cell::new(69u16.into(), &second_row, "timmeh");
-
         let mut column = Column::new("timmeh", RollingMean::new(false, None), Returns::new(false, None));
-        column.add_cell(&cell);
-        column.add_cell(&second_cell);
+        column.push(67u16.into()).unwrap();
+        column.push(69u16.into()).unwrap();
+        column.push(71u16.into()).unwrap();
 
-        assert!(column.get_cells()[0].borrow().get_rolling_mean() == None);
-        assert!(column.get_cells()[1].borrow().get_rolling_mean() == None);
+        assert!(column.get_rolling_mean(0).is_none());
+        assert!(column.get_rolling_mean(1).is_none());
 
-        column.update_rolling_mean(RollingMean::new(true, Some(2)));
+        column.update_rolling_mean(RollingMean::new(true, Some(3)), &[]);
 
-        assert!(column.get_cells()[0].borrow().get_rolling_mean() == None);
-        assert!(column.get_cells()[1].borrow().get_rolling_mean() == Some(68u16.into()));
+        assert!(column.get_rolling_mean(0).is_none());
+        assert!(column.get_rolling_mean(1).is_none());
+        assert!(column.get_rolling_mean(2) == Some(69u16.into()));
     }
 
     #[test]
-    fn drop_cell() {
-        let row: RcRow = Row::new(0);
-        let cell: RcCell = Cell::new(67u16.into(), &row, "timmeh");
-        let second_row: RcRow = Row::new(1);
-        let second_cell: RcCell = Cell::new(69u16.into(), &second_row, "timmeh");
-        let third_row: RcRow = Row::new(2);
-        let third_cell: RcCell = Cell::new(71u16.into(), &third_row, "timmeh");
+    fn remove() {
+        let mut column = Column::new("timmeh", RollingMean::new(true, Some(2)), Returns::new(false, None));
+        column.push(67u16.into()).unwrap();
+        column.push(69u16.into()).unwrap();
+        column.push(71u16.into()).unwrap();
+
+        assert!(column.get_rolling_mean(0).is_none());
+        assert!(column.get_rolling_mean(1) == Some(68u16.into()));
+        assert!(column.get_rolling_mean(2) == Some(70u16.into()));
+        assert!(column.remove(1) == Ok(69u16.into()));
+
+        assert!(column.get_rolling_mean(0).is_none());
+        assert!(column.get_rolling_mean(1) == Some(69u16.into()));
+        assert!(column.get_grouped_values(69u16.into()).is_none());
+        assert!(column.get_grouped_values(71u16.into()) == Some(&vec![1]));
+        assert_eq!(column.remove(2), Err(DataFrameError::IndexOutOfRange { index: 2, len: 2 }));
+    }
 
+    #[test]
+    fn set() {
         let mut column = Column::new("timmeh", RollingMean::new(true, Some(2)), Returns::new(false, None));
-        column.add_cell(&cell);
-        column.add_cell(&second_cell);
-        column.add_cell(&third_cell);
+        column.push(67u16.into()).unwrap();
+        column.push(69u16.into()).unwrap();
+        column.push(67u16.into()).unwrap();
+
+        assert!(column.set(1, 67u16.into()) == Ok(69u16.into()));
+        assert!(column.get_grouped_values(67u16.into()) == Some(&vec![0, 1, 2]));
+        assert!(column.get_grouped_values(69u16.into()).is_none());
+        assert!(column.get_rolling_mean(2) == Some(67u16.into()));
+        assert_eq!(column.set(3, 1u16.into()), Err(DataFrameError::IndexOutOfRange { index: 3, len: 3 }));
+    }
 
-        assert!(column.get_cells()[0].borrow().get_rolling_mean() == None);
-        assert!(column.get_cells()[1].borrow().get_rolling_mean() == Some(68u16.into()));
-        assert!(column.get_cells()[2].borrow().get_rolling_mean() == Some(70u16.into()));
-        // a bit dodgy here, but need to update the index of the third row before dropping the second row
-        third_row.borrow_mut().update_index(1);
-        column.drop_cell(second_cell);
+    #[test]
+    fn mean() {
+        let mut column = Column::new("timmeh", RollingMean::new(true, Some(2)), Returns::new(false, None));
+        column.push(67u16.into()).unwrap();
+        column.push(69u16.into()).unwrap();
+        column.push(71u16.into()).unwrap();
 
-        assert!(column.get_cells()[0].borrow().get_rolling_mean() == None);
-        assert!(column.get_cells()[1].borrow().get_rolling_mean() == Some(69u16.into()));
+        assert!(column.mean() == Ok(Some(69f64.into())));
     }
 
     #[test]
-    fn mean() {
-        let row: RcRow = Row::new(0);
-        let cell: RcCell = Cell::new(67u16.into(), &row, "timmeh");
-        let second_row: RcRow = Row::new(1);
-        let second_cell: RcCell = Cell::new(69u16.into(), &second_row, "timmeh");
-        let third_row: RcRow = Row::new(2);
-        let third_cell: RcCell = Cell::new(71u16.into(), &third_row, "timmeh");
+    fn mean_empty_column() {
+        let column = Column::new("timmeh", RollingMean::new(false, None), Returns::new(false, None));
+
+        assert_eq!(column.mean(), Err(DataFrameError::EmptyColumn("timmeh".to_string())));
+    }
+
+    #[test]
+    fn sum_does_not_overflow_narrow_types() {
+        let mut column = Column::new("timmeh", RollingMean::new(false, None), Returns::new(false, None));
+        for _ in 0..10 {
+            column.push(250u8.into()).unwrap();
+        }
+        column.push(AnyType::Null).unwrap();
+
+        assert_eq!(column.sum(), Ok(Some(AnyType::UInt64(2500))));
+        assert_eq!(column.mean(), Ok(Some(AnyType::Float64(250.0))));
+        assert_eq!(column.count(), 10);
+        assert_eq!(column.null_count(), 1);
+    }
+
+    #[test]
+    fn sum_overflow() {
+        let mut column = Column::new("timmeh", RollingMean::new(false, None), Returns::new(false, None));
+        column.push(i64::MAX.into()).unwrap();
+        column.push(1i64.into()).unwrap();
+
+        assert_eq!(column.sum(), Err(DataFrameError::Overflow("timmeh".to_string())));
+        assert_eq!(column.mean(), Ok(Some(AnyType::Float64((i64::MAX as f64 + 1.0) / 2.0))));
+    }
+
+    #[test]
+    fn compensated_float_sum() {
+        let mut column = Column::new("timmeh", RollingMean::new(false, None), Returns::new(false, None));
+        column.push(1e100f64.into()).unwrap();
+        column.push(1f64.into()).unwrap();
+        column.push((-1e100f64).into()).unwrap();
+
+        assert_eq!(column.sum(), Ok(Some(AnyType::Float64(1.0))));
+    }
+
+    #[test]
+    fn all_null_aggregations() {
+        let mut column = Column::new("timmeh", RollingMean::new(false, None), Returns::new(false, None));
+        column.push(AnyType::Null).unwrap();
+        column.push(AnyType::Null).unwrap();
+
+        assert_eq!(column.sum(), Ok(None));
+        assert_eq!(column.mean(), Ok(None));
+        assert_eq!(column.min(), Ok(None));
+        assert_eq!(column.variance(), Ok(None));
+        assert_eq!(column.count(), 0);
+        assert_eq!(column.null_count(), 2);
+    }
 
+    #[test]
+    fn min_max() {
+        let mut column = Column::new("timmeh", RollingMean::new(false, None), Returns::new(false, None));
+        column.push(3.5f64.into()).unwrap();
+        column.push(f64::NAN.into()).unwrap();
+        column.push(AnyType::Null).unwrap();
+        column.push((-1.0f64).into()).unwrap();
+
+        assert_eq!(column.min(), Ok(Some(AnyType::Float64(-1.0))));
+        assert_eq!(column.max(), Ok(Some(AnyType::Float64(3.5))));
+
+        let mut column = Column::new("names", RollingMean::new(false, None), Returns::new(false, None));
+        column.push("bravo".into()).unwrap();
+        column.push("alpha".into()).unwrap();
+
+        assert_eq!(column.min(), Ok(Some("alpha".into())));
+        assert_eq!(column.max(), Ok(Some("bravo".into())));
+        assert_eq!(column.mean(), Err(DataFrameError::UnsupportedDtype {
+            column: "names".to_string(),
+            operation: "mean",
+            dtype: DataType::Utf8,
+        }));
+    }
+
+    #[test]
+    fn variance_and_std() {
+        let mut column = Column::new("timmeh", RollingMean::new(false, None), Returns::new(false, None));
+        for value in [2i32, 4, 4, 4, 5, 5, 7, 9] {
+            column.push(value.into()).unwrap();
+        }
+
+        assert_eq!(column.variance(), Ok(Some(AnyType::Float64(32.0 / 7.0))));
+        assert_eq!(column.std(), Ok(Some(AnyType::Float64((32.0f64 / 7.0).sqrt()))));
+
+        let mut column = Column::new("single", RollingMean::new(false, None), Returns::new(false, None));
+        column.push(1i32.into()).unwrap();
+
+        assert_eq!(column.variance(), Ok(None));
+    }
+
+    #[test]
+    fn boolean_sum_and_mean() {
+        let mut column = Column::new("timmeh", RollingMean::new(false, None), Returns::new(false, None));
+        column.push(true.into()).unwrap();
+        column.push(false.into()).unwrap();
+        column.push(true.into()).unwrap();
+        column.push(true.into()).unwrap();
+
+        assert_eq!(column.sum(), Ok(Some(AnyType::UInt64(3))));
+        assert_eq!(column.mean(), Ok(Some(AnyType::Float64(0.75))));
+    }
+
+    #[test]
+    fn duration_rolling_mean() {
         let mut column = Column::new("timmeh", RollingMean::new(true, Some(2)), Returns::new(false, None));
-        column.add_cell(&cell);
-        column.add_cell(&second_cell);
-        column.add_cell(&third_cell);
+        column.push(chrono::Duration::seconds(4).into()).unwrap();
+        column.push(chrono::Duration::seconds(6).into()).unwrap();
 
-        assert!(column.mean() == Some(69u16.into()));
+        assert!(column.get_rolling_mean(1) == Some(chrono::Duration::seconds(5).into()));
+        assert!(column.mean() == Ok(Some(chrono::Duration::seconds(5).into())));
     }
 
     #[test]
     fn get_all_difference_to_last() {
-        let row: RcRow = Row::new(0);
-        let cell: RcCell = Cell::new(67u16.into(), &row, "timmeh");
-        let second_row: RcRow = Row::new(1);
-        let second_cell: RcCell = Cell::new(69u16.into(), &second_row, "timmeh");
-        let third_row: RcRow = Row::new(2);
-        let third_cell: RcCell = Cell::new(71u16.into(), &third_row, "timmeh");
-
         let mut column = Column::new("timmeh", RollingMean::new(true, Some(2)), Returns::new(false, None));
-        column.add_cell(&cell);
-        column.add_cell(&second_cell);
-        column.add_cell(&third_cell);
+        column.push(67u16.into()).unwrap();
+        column.push(69u16.into()).unwrap();
+        column.push(71u16.into()).unwrap();
 
         assert!(column.get_all_difference_to_last() == vec![AnyType::Null, 2isize.into(), 2isize.into()]);
     }
 
     #[test]
     fn get_values_as_vec_with_datetime() {
-        let row: RcRow = Row::new(0);
-        let cell: RcCell = Cell::new(67u16.into(), &row, "timmeh");
-        let second_row: RcRow = Row::new(1);
-        let second_cell: RcCell = Cell::new(69u16.into(), &second_row, "timmeh");
-        let third_row: RcRow = Row::new(2);
-        let third_cell: RcCell = Cell::new(71u16.into(), &third_row, "timmeh");
-
+        let datetimes = vec![Utc::now(); 3];
         let mut column = Column::new("timmeh", RollingMean::new(false, None), Returns::new(false, None));
-        column.add_cell(&cell);
-        column.add_cell(&second_cell);
-        column.add_cell(&third_cell);
+        column.push(67u16.into()).unwrap();
+        column.push(69u16.into()).unwrap();
+        column.push(71u16.into()).unwrap();
 
-        let values_as_vec = column.get_values_as_vec_with_datetime::<u16>();
+        let values_as_vec = column.get_values_as_vec_with_datetime::<u16>(&datetimes);
 
         assert_eq!(values_as_vec.len(), 3);
         assert_eq!(values_as_vec[0].1, 67u16);
@@ -476,19 +929,13 @@ mod tests {
 
     #[test]
     fn get_values_as_vec_with_unix_datetime() {
-        let row: RcRow = Row::new(0);
-        let cell: RcCell = Cell::new(67u16.into(), &row, "timmeh");
-        let second_row: RcRow = Row::new(1);
-        let second_cell: RcCell = Cell::new(69u16.into(), &second_row, "timmeh");
-        let third_row: RcRow = Row::new(2);
-        let third_cell: RcCell = Cell::new(71u16.into(), &third_row, "timmeh");
-
+        let datetimes = vec![Utc::now(); 3];
         let mut column = Column::new("timmeh", RollingMean::new(false, None), Returns::new(false, None));
-        column.add_cell(&cell);
-        column.add_cell(&second_cell);
-        column.add_cell(&third_cell);
+        column.push(67u16.into()).unwrap();
+        column.push(69u16.into()).unwrap();
+        column.push(71u16.into()).unwrap();
 
-        let values_as_vec: Vec<(i64, u16)> = column.get_values_as_vec_with_unix_datetime::<u16>();
+        let values_as_vec: Vec<(i64, u16)> = column.get_values_as_vec_with_unix_datetime::<u16>(&datetimes);
 
         assert_eq!(values_as_vec.len(), 3);
         assert_eq!(values_as_vec[0].1, 67u16);
@@ -499,22 +946,14 @@ mod tests {
 
     #[test]
     fn get_rolling_means_as_vec_with_unix_datetime_diff() {
-        let row: RcRow = Row::new(0);
-        let cell: RcCell = Cell::new(67u16.into(), &row, "timmeh");
-        let second_row: RcRow = Row::new(1);
-        let second_cell: RcCell = Cell::new(69u16.into(), &second_row, "timmeh");
-        let third_row: RcRow = Row::new(2);
-        let third_cell: RcCell = Cell::new(71u16.into(), &third_row, "timmeh");
-        let fourth_row: RcRow = Row::new(3);
-        let fourth_cell: RcCell = Cell::new(77u16.into(), &fourth_row, "timmeh");
-
+        let datetimes = vec![Utc::now(); 4];
         let mut column = Column::new("timmeh", RollingMean::new(true, Some(2)), Returns::new(false, None));
-        column.add_cell(&cell);
-        column.add_cell(&second_cell);
-        column.add_cell(&third_cell);
-        column.add_cell(&fourth_cell);
+        column.push(67u16.into()).unwrap();
+        column.push(69u16.into()).unwrap();
+        column.push(71u16.into()).unwrap();
+        column.push(77u16.into()).unwrap();
 
-        let values_as_vec: Vec<(f64, f64)> = column.get_rolling_means_as_vec_with_unix_datetime_diff::<u16>();
+        let values_as_vec: Vec<(f64, f64)> = column.get_rolling_means_as_vec_with_unix_datetime_diff::<u16>(&datetimes);
 
         assert_eq!(values_as_vec.len(), 3);
         assert_eq!(values_as_vec[0].1, 68f64);
@@ -524,35 +963,27 @@ mod tests {
 
     #[test]
     fn rate_of_change_rolling_means_over_x_elements() {
-        let row: RcRow = Row::new(0);
-        let cell: RcCell = Cell::new(67u16.into(), &row, "timmeh");
-        // sleep to ensure that there is a duration over which an average can be found
-        thread::sleep(time::Duration::from_millis(2000));
-        let second_row: RcRow = Row::new(1);
-        let second_cell: RcCell = Cell::new(69u16.into(), &second_row, "timmeh");
-        thread::sleep(time::Duration::from_millis(1500));
-        let third_row: RcRow = Row::new(2);
-        let third_cell: RcCell = Cell::new(71u16.into(), &third_row, "timmeh");
-        thread::sleep(time::Duration::from_millis(2400));
-        let fourth_row: RcRow = Row::new(3);
-        let fourth_cell: RcCell = Cell::new(77u16.into(), &fourth_row, "timmeh");
-        thread::sleep(time::Duration::from_millis(6000));
-        let fifth_row: RcRow = Row::new(4);
-        let fifth_cell: RcCell = Cell::new(77u16.into(), &fifth_row, "timmeh");
+        // advance a manual clock to ensure that there is a duration over which an average can be found
+        let clock = ManualClock::new(Utc.with_ymd_and_hms(2021, 6, 1, 12, 0, 0).unwrap());
+        let mut datetimes = vec![clock.now()];
+        for milliseconds in [2000, 1500, 2400, 6000] {
+            clock.advance(chrono::Duration::milliseconds(milliseconds));
+            datetimes.push(clock.now());
+        }
 
         let mut column = Column::new("timmeh", RollingMean::new(true, Some(2)), Returns::new(false, None));
-        column.add_cell(&cell);
-        column.add_cell(&second_cell);
-        column.add_cell(&third_cell);
-        column.add_cell(&fourth_cell);
-        column.add_cell(&fifth_cell);
+        column.push(67u16.into()).unwrap();
+        column.push(69u16.into()).unwrap();
+        column.push(71u16.into()).unwrap();
+        column.push(77u16.into()).unwrap();
+        column.push(77u16.into()).unwrap();
 
-        let (rol, roc): (Vec<(f64, f64)>, Vec<(f64, f64)>) = column.rate_of_change_rolling_means_over_x_elements::<u16>(2);
+        let (_rol, roc): RateOfChange = column.rate_of_change_rolling_means_over_x_elements::<u16>(&datetimes, 2);
 
         assert_eq!(roc.len(), 4);
         assert_eq!(roc[0].1, 0f64);
-        // assert_ne!(roc[1].1, 0f64);
-        assert_ne!(roc[3].0, 0f64);      
+        assert_eq!(roc[1].1, 0.5f64);
+        assert_eq!(roc[3].0, 11f64);
     }
 
 }
\ No newline at end of file
@@ -0,0 +1,903 @@
+use crate::cell::types::datatypes::{
+    AnyType,
+    DataType,
+};
+use crate::error::{
+    DataFrameError,
+    Result,
+};
+use chrono::{
+    DateTime,
+    Duration,
+    Utc,
+};
+use std::cmp::{
+    Ordering,
+    Reverse,
+};
+use std::collections::{
+    BinaryHeap,
+    HashMap,
+    VecDeque,
+};
+use std::fmt::{
+    Debug,
+    Formatter,
+};
+use std::rc::Rc;
+
+pub type RollingFn = Rc<dyn Fn(&[f64]) -> Option<f64>>;
+
+#[derive(Clone)]
+pub enum RollingAggregation {
+    Mean,
+    Sum,
+    Min,
+    Max,
+    Variance,
+    Std,
+    Median,
+    Quantile(f64),
+    // called with the non-null values currently in the window, oldest first
+    Custom(RollingFn),
+}
+
+impl Debug for RollingAggregation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RollingAggregation::Mean => write!(f, "Mean"),
+            RollingAggregation::Sum => write!(f, "Sum"),
+            RollingAggregation::Min => write!(f, "Min"),
+            RollingAggregation::Max => write!(f, "Max"),
+            RollingAggregation::Variance => write!(f, "Variance"),
+            RollingAggregation::Std => write!(f, "Std"),
+            RollingAggregation::Median => write!(f, "Median"),
+            RollingAggregation::Quantile(quantile) => write!(f, "Quantile({})", quantile),
+            RollingAggregation::Custom(_) => write!(f, "Custom"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowAlignment {
+    // the result for a row covers that row and the ones before it
+    Trailing,
+    // the result for a row covers the rows either side of it, and is filled in once the later half has arrived
+    Centred,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowSpan {
+    Rows(usize),
+    // covers the earlier rows stamped within the duration of the current row, rows are expected in time order
+    Duration(Duration),
+}
+
+#[derive(Debug, Clone)]
+pub struct RollingWindow {
+    pub name: Rc<str>,
+    pub span: WindowSpan,
+    pub aggregation: RollingAggregation,
+    pub min_periods: usize,
+    pub alignment: WindowAlignment,
+}
+
+impl RollingWindow {
+    pub fn new<S: Into<Rc<str>>>(name: S, size: usize, aggregation: RollingAggregation) -> Self {
+        Self {
+            name: name.into(),
+            span: WindowSpan::Rows(size),
+            aggregation,
+            min_periods: size,
+            alignment: WindowAlignment::Trailing,
+        }
+    }
+
+    pub fn over_duration<S: Into<Rc<str>>>(name: S, duration: Duration, aggregation: RollingAggregation) -> Self {
+        Self {
+            name: name.into(),
+            span: WindowSpan::Duration(duration),
+            aggregation,
+            min_periods: 1,
+            alignment: WindowAlignment::Trailing,
+        }
+    }
+
+    pub fn with_min_periods(mut self, min_periods: usize) -> Self {
+        self.min_periods = min_periods;
+        self
+    }
+
+    pub fn with_alignment(mut self, alignment: WindowAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        match self.span {
+            WindowSpan::Rows(0) => return Err(DataFrameError::InvalidArgument(format!("rolling window '{}' must cover at least one row", self.name))),
+            WindowSpan::Rows(size) if self.min_periods > size => return Err(DataFrameError::InvalidArgument(format!("rolling window '{}' has min_periods larger than its size", self.name))),
+            WindowSpan::Duration(duration) if duration <= Duration::zero() => return Err(DataFrameError::InvalidArgument(format!("rolling window '{}' must cover a positive duration", self.name))),
+            WindowSpan::Duration(_) if self.alignment == WindowAlignment::Centred => return Err(DataFrameError::InvalidArgument(format!("rolling window '{}' can only be centred over a number of rows", self.name))),
+            _ => {},
+        }
+        if let RollingAggregation::Quantile(quantile) = self.aggregation {
+            if !(0f64..=1f64).contains(&quantile) {
+                return Err(DataFrameError::InvalidArgument(format!("rolling window '{}' has a quantile outside of 0 to 1", self.name)));
+            }
+        }
+        Ok(())
+    }
+
+    // the type a result is handed back as for a column of the given type
+    pub fn output_dtype(&self, dtype: DataType) -> DataType {
+        match (&self.aggregation, dtype) {
+            (RollingAggregation::Variance, _) | (RollingAggregation::Custom(_), _) => DataType::Float64,
+            (_, DataType::Duration) => DataType::Duration,
+            _ => DataType::Float64,
+        }
+    }
+
+    fn offset(&self) -> usize {
+        match (self.alignment, self.span) {
+            (WindowAlignment::Centred, WindowSpan::Rows(size)) => size / 2,
+            _ => 0,
+        }
+    }
+}
+
+// a rolling window together with the running state needed to extend it one row at a time
+#[derive(Debug)]
+pub struct Rolling {
+    window: RollingWindow,
+    state: WindowState,
+    values: Vec<Option<f64>>,
+    // only kept for duration windows so they can be replayed after a row is removed
+    timestamps: Vec<Option<DateTime<Utc>>>,
+    latest: Option<DateTime<Utc>>,
+    // a duration window over rows out of time order takes them in time order, and is replayed in full when one changes
+    in_time_order: bool,
+}
+
+impl Rolling {
+    pub fn new(window: RollingWindow) -> Self {
+        Self {
+            state: WindowState::new(&window),
+            window,
+            values: vec![],
+            timestamps: vec![],
+            latest: None,
+            in_time_order: true,
+        }
+    }
+
+    pub fn get_window(&self) -> &RollingWindow {
+        &self.window
+    }
+
+    pub fn name(&self) -> &str {
+        &self.window.name
+    }
+
+    pub fn get(&self, index: usize) -> Option<f64> {
+        self.values.get(index).copied().flatten()
+    }
+
+    pub fn get_as(&self, index: usize, dtype: DataType) -> Option<AnyType> {
+        self.get(index).map(|value| AnyType::from_f64(self.window.output_dtype(dtype), value))
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    // a row stamped before the latest one only sees the rows before it, so is checked with accepts first
+    pub fn accepts(&self, datetime: Option<DateTime<Utc>>) -> bool {
+        match (self.window.span, datetime, self.latest) {
+            (WindowSpan::Duration(_), Some(datetime), Some(latest)) => datetime >= latest,
+            _ => true,
+        }
+    }
+
+    pub fn push(&mut self, value: Option<f64>, datetime: Option<DateTime<Utc>>) {
+        if let WindowSpan::Duration(_) = self.window.span {
+            self.in_time_order &= self.accepts(datetime);
+            self.latest = self.latest.max(datetime);
+            self.timestamps.push(datetime);
+        }
+        let result = self.step(value, datetime);
+        self.values.push(None);
+        let offset = self.window.offset();
+        if self.values.len() > offset {
+            let index = self.values.len() - 1 - offset;
+            self.values[index] = result;
+        }
+    }
+
+    pub fn rebuild<I: Iterator<Item = (Option<f64>, Option<DateTime<Utc>>)>>(&mut self, values: I) {
+        self.state = WindowState::new(&self.window);
+        self.values.clear();
+        self.timestamps.clear();
+        self.latest = None;
+        self.in_time_order = true;
+        let values: Vec<(Option<f64>, Option<DateTime<Utc>>)> = values.collect();
+        if self.is_in_time_order(&values) {
+            for (value, datetime) in values {
+                self.push(value, datetime);
+            }
+            return;
+        }
+        // rows sharing a timestamp keep their order, and rows without one come first so hold nothing
+        let mut positions: Vec<usize> = (0..values.len()).collect();
+        positions.sort_by_key(|position| values[*position].1);
+        self.values = vec![None; values.len()];
+        for position in positions {
+            let (value, datetime) = values[position];
+            self.values[position] = self.step(value, datetime);
+        }
+        self.timestamps = values.into_iter().map(|(_, datetime)| datetime).collect();
+        self.latest = self.timestamps.iter().flatten().max().copied();
+        self.in_time_order = false;
+    }
+
+    // results saved from an earlier run over the same rows are taken as they are, and only the last window is replayed
+    // so rolling carries on from the final row
+    pub fn restore<I: Iterator<Item = (Option<f64>, Option<DateTime<Utc>>)>>(&mut self, results: Vec<Option<f64>>, rows: I) {
+        let rows: Vec<(Option<f64>, Option<DateTime<Utc>>)> = rows.collect();
+        if results.len() != rows.len() || !self.is_in_time_order(&rows) {
+            self.rebuild(rows.into_iter());
+            return;
+        }
+        self.state = WindowState::new(&self.window);
+        self.values = results;
+        self.timestamps = match self.window.span {
+            WindowSpan::Duration(_) => rows.iter().map(|(_, datetime)| *datetime).collect(),
+            WindowSpan::Rows(_) => vec![],
+        };
+        self.latest = self.timestamps.iter().flatten().max().copied();
+        self.in_time_order = true;
+        if let Some(last) = rows.len().checked_sub(1) {
+            for (value, datetime) in rows[self.window_start(last)..].iter() {
+                self.step(*value, *datetime);
+            }
+        }
+    }
+
+    pub fn results(&self) -> &[Option<f64>] {
+        &self.values
+    }
+
+    // value_at gives the values left once the row has been taken out, only the rows whose windows held it are recalculated
+    pub fn remove<F: Fn(usize) -> Option<f64>>(&mut self, index: usize, len: usize, value_at: F) {
+        let removed_at = match index < self.timestamps.len() {
+            true => self.timestamps.remove(index),
+            false => None,
+        };
+        self.latest = self.timestamps.iter().flatten().max().copied();
+        if !self.values.is_empty() {
+            let position = index.saturating_sub(self.window.offset()).min(self.values.len() - 1);
+            self.values.remove(position);
+        }
+        self.recalculate(index, removed_at, len, value_at);
+    }
+
+    // the value at index has changed, value_at gives the values as they are now
+    pub fn update<F: Fn(usize) -> Option<f64>>(&mut self, index: usize, len: usize, value_at: F) {
+        let changed_at = self.timestamp(index);
+        self.recalculate(index, changed_at, len, value_at);
+    }
+
+    // NaN is treated the same as a missing value, as is any row without a timestamp in a duration window
+    fn step(&mut self, value: Option<f64>, datetime: Option<DateTime<Utc>>) -> Option<f64> {
+        let value = value.filter(|value| !value.is_nan());
+        match self.window.span {
+            WindowSpan::Rows(size) => self.state.push_row(value, size),
+            WindowSpan::Duration(duration) => self.state.push_timed(value, datetime, duration),
+        }
+        if self.state.count >= self.window.min_periods.max(1) {
+            self.state.evaluate(&self.window.aggregation)
+        } else {
+            None
+        }
+    }
+
+    fn is_in_time_order(&self, rows: &[(Option<f64>, Option<DateTime<Utc>>)]) -> bool {
+        match self.window.span {
+            WindowSpan::Duration(_) => rows.iter().filter_map(|(_, datetime)| *datetime).is_sorted(),
+            WindowSpan::Rows(_) => true,
+        }
+    }
+
+    fn timestamp(&self, position: usize) -> Option<DateTime<Utc>> {
+        self.timestamps.get(position).copied().flatten()
+    }
+
+    // the first row in the window ending at position, assuming the rows are in time order
+    fn window_start(&self, position: usize) -> usize {
+        match self.window.span {
+            WindowSpan::Rows(size) => (position + 1).saturating_sub(size),
+            WindowSpan::Duration(duration) => match self.timestamp(position) {
+                Some(datetime) => {
+                    let cutoff = datetime - duration;
+                    let mut start = position;
+                    while start > 0 && self.timestamp(start - 1).is_some_and(|stamped| stamped > cutoff) {
+                        start -= 1;
+                    }
+                    start
+                },
+                None => position,
+            },
+        }
+    }
+
+    // one past the last row whose window holds the row changed at index, which was stamped changed_at
+    fn window_end(&self, index: usize, changed_at: Option<DateTime<Utc>>, len: usize) -> usize {
+        match self.window.span {
+            WindowSpan::Rows(size) => (index + size).min(len),
+            WindowSpan::Duration(duration) => match changed_at {
+                Some(changed_at) => (index..len)
+                    .find(|position| self.timestamp(*position).is_none_or(|stamped| stamped - duration >= changed_at))
+                    .unwrap_or(len),
+                None => len,
+            },
+        }
+    }
+
+    // replays the rows from the start of the first window that changed to the end of the last one, then rebuilds the
+    // running state from the final window so later pushes carry on from it
+    fn recalculate<F: Fn(usize) -> Option<f64>>(&mut self, index: usize, changed_at: Option<DateTime<Utc>>, len: usize, value_at: F) {
+        if !self.in_time_order {
+            let timestamps = std::mem::take(&mut self.timestamps);
+            self.rebuild((0..len).map(|position| (value_at(position), timestamps.get(position).copied().flatten())));
+            return;
+        }
+        let offset = self.window.offset();
+        let end = self.window_end(index, changed_at, len);
+        if index < len {
+            self.state = WindowState::new(&self.window);
+            for position in self.window_start(index)..end {
+                let result = self.step(value_at(position), self.timestamp(position));
+                if position >= index && position >= offset {
+                    self.values[position - offset] = result;
+                }
+            }
+        }
+        if end < len || index >= len {
+            self.state = WindowState::new(&self.window);
+            if len > 0 {
+                for position in self.window_start(len - 1)..len {
+                    self.step(value_at(position), self.timestamp(position));
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct WindowState {
+    window: VecDeque<(Option<f64>, Option<DateTime<Utc>>)>,
+    // position of the oldest row still in the window
+    first: usize,
+    pushed: usize,
+    count: usize,
+    mean: f64,
+    m2: f64,
+    sum: f64,
+    compensation: f64,
+    minima: VecDeque<(usize, f64)>,
+    maxima: VecDeque<(usize, f64)>,
+    // only kept for order statistics
+    order: Option<OrderStatistics>,
+    scratch: Vec<f64>,
+}
+
+impl WindowState {
+    fn new(window: &RollingWindow) -> Self {
+        let order = match window.aggregation {
+            RollingAggregation::Median => Some(OrderStatistics::new(0.5)),
+            RollingAggregation::Quantile(quantile) => Some(OrderStatistics::new(quantile)),
+            _ => None,
+        };
+        Self {
+            window: VecDeque::new(),
+            first: 0,
+            pushed: 0,
+            count: 0,
+            mean: 0f64,
+            m2: 0f64,
+            sum: 0f64,
+            compensation: 0f64,
+            minima: VecDeque::new(),
+            maxima: VecDeque::new(),
+            order,
+            scratch: vec![],
+        }
+    }
+
+    fn push_row(&mut self, value: Option<f64>, size: usize) {
+        if self.window.len() == size {
+            self.expire();
+        }
+        self.append(value, None);
+    }
+
+    fn push_timed(&mut self, value: Option<f64>, datetime: Option<DateTime<Utc>>, duration: Duration) {
+        let datetime = match datetime {
+            Some(datetime) => datetime,
+            None => {
+                while !self.window.is_empty() {
+                    self.expire();
+                }
+                self.append(None, None);
+                return;
+            },
+        };
+        self.append(value, Some(datetime));
+        // anything stamped at or before the cutoff has fallen out, which also clears rows without a timestamp
+        let cutoff = datetime - duration;
+        while self.window.front().is_some_and(|(_, stamped)| stamped.is_none_or(|stamped| stamped <= cutoff)) {
+            self.expire();
+        }
+    }
+
+    fn append(&mut self, value: Option<f64>, datetime: Option<DateTime<Utc>>) {
+        let position = self.pushed;
+        self.pushed += 1;
+        self.window.push_back((value, datetime));
+        if let Some(value) = value {
+            self.add(value, position);
+        }
+    }
+
+    fn expire(&mut self) {
+        if let Some((expired, _)) = self.window.pop_front() {
+            self.first += 1;
+            if let Some(expired) = expired {
+                self.remove(expired);
+            }
+        }
+        while self.minima.front().is_some_and(|(index, _)| *index < self.first) {
+            self.minima.pop_front();
+        }
+        while self.maxima.front().is_some_and(|(index, _)| *index < self.first) {
+            self.maxima.pop_front();
+        }
+    }
+
+    fn add(&mut self, value: f64, position: usize) {
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+        self.accumulate(value);
+        while self.minima.back().is_some_and(|(_, back)| *back >= value) {
+            self.minima.pop_back();
+        }
+        self.minima.push_back((position, value));
+        while self.maxima.back().is_some_and(|(_, back)| *back <= value) {
+            self.maxima.pop_back();
+        }
+        self.maxima.push_back((position, value));
+        if let Some(order) = self.order.as_mut() {
+            order.insert(value);
+        }
+    }
+
+    fn remove(&mut self, value: f64) {
+        self.count -= 1;
+        if self.count == 0 {
+            self.mean = 0f64;
+            self.m2 = 0f64;
+            self.sum = 0f64;
+            self.compensation = 0f64;
+        } else {
+            let delta = value - self.mean;
+            self.mean -= delta / self.count as f64;
+            self.m2 -= delta * (value - self.mean);
+            self.accumulate(-value);
+        }
+        if let Some(order) = self.order.as_mut() {
+            order.remove(value);
+        }
+    }
+
+    // Neumaier summation, kept alongside the mean so large sums do not lose their small terms
+    fn accumulate(&mut self, value: f64) {
+        let next = self.sum + value;
+        if self.sum.abs() >= value.abs() {
+            self.compensation += (self.sum - next) + value;
+        } else {
+            self.compensation += (value - next) + self.sum;
+        }
+        self.sum = next;
+    }
+
+    fn evaluate(&mut self, aggregation: &RollingAggregation) -> Option<f64> {
+        if self.count == 0 {
+            return None;
+        }
+        match aggregation {
+            RollingAggregation::Mean => Some((self.sum + self.compensation) / self.count as f64),
+            RollingAggregation::Sum => Some(self.sum + self.compensation),
+            RollingAggregation::Min => self.minima.front().map(|(_, value)| *value),
+            RollingAggregation::Max => self.maxima.front().map(|(_, value)| *value),
+            RollingAggregation::Variance => self.variance(),
+            RollingAggregation::Std => self.variance().map(f64::sqrt),
+            RollingAggregation::Median | RollingAggregation::Quantile(_) => self.order.as_ref()?.get(),
+            RollingAggregation::Custom(function) => {
+                self.scratch.clear();
+                self.scratch.extend(self.window.iter().filter_map(|(value, _)| *value));
+                function(&self.scratch)
+            },
+        }
+    }
+
+    fn variance(&self) -> Option<f64> {
+        if self.count < 2 {
+            return None;
+        }
+        Some(self.m2.max(0f64) / (self.count - 1) as f64)
+    }
+}
+
+// f64 ordered by total_cmp so it can be kept in a heap
+#[derive(Debug, Clone, Copy)]
+struct Ordered(f64);
+
+impl PartialEq for Ordered {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Ordered {}
+
+impl PartialOrd for Ordered {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Ordered {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+// the window split at the rank the quantile falls on, the lower part in a max heap and the upper part in a min heap.
+// values leaving the window are counted against their heap and only dropped once they reach its top
+#[derive(Debug)]
+struct OrderStatistics {
+    quantile: f64,
+    lower: BinaryHeap<Ordered>,
+    upper: BinaryHeap<Reverse<Ordered>>,
+    // how many values in each heap are still in the window
+    lower_len: usize,
+    upper_len: usize,
+    lower_removed: HashMap<u64, usize>,
+    upper_removed: HashMap<u64, usize>,
+}
+
+impl OrderStatistics {
+    fn new(quantile: f64) -> Self {
+        Self {
+            quantile,
+            lower: BinaryHeap::new(),
+            upper: BinaryHeap::new(),
+            lower_len: 0,
+            upper_len: 0,
+            lower_removed: HashMap::new(),
+            upper_removed: HashMap::new(),
+        }
+    }
+
+    fn insert(&mut self, value: f64) {
+        match self.lower.peek() {
+            Some(top) if value.total_cmp(&top.0).is_gt() => {
+                self.upper.push(Reverse(Ordered(value)));
+                self.upper_len += 1;
+            },
+            _ => {
+                self.lower.push(Ordered(value));
+                self.lower_len += 1;
+            },
+        }
+        self.rebalance();
+    }
+
+    // the value has to be in the window
+    fn remove(&mut self, value: f64) {
+        if self.lower.peek().is_some_and(|top| value.total_cmp(&top.0).is_le()) {
+            *self.lower_removed.entry(value.to_bits()).or_default() += 1;
+            self.lower_len -= 1;
+        } else {
+            *self.upper_removed.entry(value.to_bits()).or_default() += 1;
+            self.upper_len -= 1;
+        }
+        self.prune();
+        self.rebalance();
+    }
+
+    // linear interpolation between the two closest ranks
+    fn get(&self) -> Option<f64> {
+        let len = self.lower_len + self.upper_len;
+        if len == 0 {
+            return None;
+        }
+        let position = self.quantile * (len - 1) as f64;
+        let below = self.lower.peek()?.0;
+        let above = match position.ceil() > position.floor() {
+            true => self.upper.peek()?.0.0,
+            false => below,
+        };
+        Some(below + (above - below) * (position - position.floor()))
+    }
+
+    // the lower heap holds every value up to and including the rank below the quantile
+    fn rebalance(&mut self) {
+        let target = match self.lower_len + self.upper_len {
+            0 => 0,
+            len => (self.quantile * (len - 1) as f64).floor() as usize + 1,
+        };
+        while self.lower_len > target {
+            if let Some(Ordered(value)) = self.lower.pop() {
+                self.lower_len -= 1;
+                self.upper.push(Reverse(Ordered(value)));
+                self.upper_len += 1;
+            }
+            self.prune();
+        }
+        while self.lower_len < target {
+            if let Some(Reverse(Ordered(value))) = self.upper.pop() {
+                self.upper_len -= 1;
+                self.lower.push(Ordered(value));
+                self.lower_len += 1;
+            }
+            self.prune();
+        }
+        compact(&mut self.lower, &mut self.lower_removed, self.lower_len, |value| value.0.to_bits());
+        compact(&mut self.upper, &mut self.upper_removed, self.upper_len, |value| value.0.0.to_bits());
+    }
+
+    fn prune(&mut self) {
+        prune(&mut self.lower, &mut self.lower_removed, |value| value.0.to_bits());
+        prune(&mut self.upper, &mut self.upper_removed, |value| value.0.0.to_bits());
+    }
+}
+
+fn take_removed(removed: &mut HashMap<u64, usize>, key: u64) -> bool {
+    match removed.get_mut(&key) {
+        Some(count) => {
+            *count -= 1;
+            if *count == 0 {
+                removed.remove(&key);
+            }
+            true
+        },
+        None => false,
+    }
+}
+
+fn prune<T: Ord>(heap: &mut BinaryHeap<T>, removed: &mut HashMap<u64, usize>, key: fn(&T) -> u64) {
+    while heap.peek().is_some_and(|top| take_removed(removed, key(top))) {
+        heap.pop();
+    }
+}
+
+// rebuilds a heap once most of it is values that have already left the window
+fn compact<T: Ord>(heap: &mut BinaryHeap<T>, removed: &mut HashMap<u64, usize>, len: usize, key: fn(&T) -> u64) {
+    if heap.len() > 2 * len + 32 {
+        let values = std::mem::take(heap).into_vec();
+        *heap = values.into_iter().filter(|value| !take_removed(removed, key(value))).collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rolled(window: RollingWindow, values: &[Option<f64>]) -> Vec<Option<f64>> {
+        let mut rolling = Rolling::new(window);
+        for value in values {
+            rolling.push(*value, None);
+        }
+        (0..rolling.len()).map(|index| rolling.get(index)).collect()
+    }
+
+    #[test]
+    fn trailing_aggregations() {
+        let values = [Some(3f64), Some(1f64), Some(4f64), Some(1f64), Some(5f64)];
+
+        assert_eq!(rolled(RollingWindow::new("sum", 3, RollingAggregation::Sum), &values), vec![None, None, Some(8.0), Some(6.0), Some(10.0)]);
+        assert_eq!(rolled(RollingWindow::new("min", 3, RollingAggregation::Min), &values), vec![None, None, Some(1.0), Some(1.0), Some(1.0)]);
+        assert_eq!(rolled(RollingWindow::new("max", 2, RollingAggregation::Max), &values), vec![None, Some(3.0), Some(4.0), Some(4.0), Some(5.0)]);
+        assert_eq!(rolled(RollingWindow::new("median", 3, RollingAggregation::Median), &values), vec![None, None, Some(3.0), Some(1.0), Some(4.0)]);
+        assert_eq!(rolled(RollingWindow::new("q", 3, RollingAggregation::Quantile(0.25)), &values), vec![None, None, Some(2.0), Some(1.0), Some(2.5)]);
+    }
+
+    #[test]
+    fn variance_matches_two_pass() {
+        let values: Vec<Option<f64>> = [2f64, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0].iter().map(|v| Some(*v)).collect();
+        let result = rolled(RollingWindow::new("var", 4, RollingAggregation::Variance), &values);
+
+        for end in 3..values.len() {
+            let window: Vec<f64> = values[end - 3..=end].iter().flatten().copied().collect();
+            let mean = window.iter().sum::<f64>() / 4f64;
+            let expected = window.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / 3f64;
+            assert!((result[end].unwrap() - expected).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn min_periods_and_nulls() {
+        let values = [Some(1f64), None, Some(f64::NAN), Some(4f64)];
+        let window = RollingWindow::new("mean", 3, RollingAggregation::Mean).with_min_periods(1);
+
+        assert_eq!(rolled(window, &values), vec![Some(1.0), Some(1.0), Some(1.0), Some(4.0)]);
+    }
+
+    #[test]
+    fn centred() {
+        let values = [Some(1f64), Some(2f64), Some(3f64), Some(4f64)];
+        let window = RollingWindow::new("mean", 3, RollingAggregation::Mean)
+            .with_min_periods(2)
+            .with_alignment(WindowAlignment::Centred);
+
+        assert_eq!(rolled(window, &values), vec![Some(1.5), Some(2.0), Some(3.0), None]);
+    }
+
+    #[test]
+    fn custom() {
+        let range: RollingFn = Rc::new(|values: &[f64]| {
+            let max = values.iter().copied().fold(f64::MIN, f64::max);
+            let min = values.iter().copied().fold(f64::MAX, f64::min);
+            Some(max - min)
+        });
+        let values = [Some(3f64), Some(1f64), Some(4f64)];
+
+        assert_eq!(rolled(RollingWindow::new("range", 2, RollingAggregation::Custom(range)), &values), vec![None, Some(2.0), Some(3.0)]);
+    }
+
+    #[test]
+    fn duration_window() {
+        let start = Utc::now();
+        let stamps = [0i64, 60, 60, 200, 360, 900];
+        let values = [1f64, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let mut rolling = Rolling::new(RollingWindow::over_duration("sum", Duration::minutes(5), RollingAggregation::Sum));
+        for (stamp, value) in stamps.iter().zip(values.iter()) {
+            rolling.push(Some(*value), Some(start + Duration::seconds(*stamp)));
+        }
+        let sums: Vec<Option<f64>> = (0..rolling.len()).map(|index| rolling.get(index)).collect();
+
+        // rows sharing a timestamp are both in the window, and a row exactly five minutes old has dropped out
+        assert_eq!(sums, vec![Some(1.0), Some(3.0), Some(6.0), Some(10.0), Some(9.0), Some(6.0)]);
+
+        let remaining = [1f64, 2.0, 3.0, 5.0, 6.0];
+        rolling.remove(3, remaining.len(), |position| Some(remaining[position]));
+        let sums: Vec<Option<f64>> = (0..rolling.len()).map(|index| rolling.get(index)).collect();
+
+        assert_eq!(sums, vec![Some(1.0), Some(3.0), Some(6.0), Some(5.0), Some(6.0)]);
+    }
+
+    // a small generator so the windows see repeated values, gaps and NaN
+    fn noisy(len: usize) -> Vec<Option<f64>> {
+        let mut seed = 17u64;
+        (0..len)
+            .map(|_| {
+                seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                match seed >> 60 {
+                    0 => None,
+                    1 => Some(f64::NAN),
+                    _ => Some(((seed >> 33) % 12) as f64),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn order_statistics() {
+        let values = noisy(600);
+        let aggregations = [RollingAggregation::Median, RollingAggregation::Quantile(0.1), RollingAggregation::Quantile(0.75), RollingAggregation::Quantile(1.0)];
+        for (size, aggregation) in [7, 90].into_iter().flat_map(|size| aggregations.clone().map(|aggregation| (size, aggregation))) {
+            let quantile = match aggregation {
+                RollingAggregation::Quantile(quantile) => quantile,
+                _ => 0.5,
+            };
+            let result = rolled(RollingWindow::new("q", size, aggregation).with_min_periods(1), &values);
+            for end in 0..values.len() {
+                let mut window: Vec<f64> = values[(end + 1).saturating_sub(size)..=end].iter().flatten().copied().filter(|value| !value.is_nan()).collect();
+                window.sort_by(f64::total_cmp);
+                let expected = match window.is_empty() {
+                    true => None,
+                    false => {
+                        let position = quantile * (window.len() - 1) as f64;
+                        let (lower, upper) = (position.floor() as usize, position.ceil() as usize);
+                        Some(window[lower] + (window[upper] - window[lower]) * (position - lower as f64))
+                    },
+                };
+                assert_eq!(result[end], expected);
+            }
+        }
+    }
+
+    #[test]
+    fn partial_replay_matches_rebuild() {
+        let start = Utc::now();
+        let values = noisy(60);
+        let stamps: Vec<Option<DateTime<Utc>>> = (0..values.len() as i64)
+            .map(|index| match index % 23 {
+                11 => None,
+                _ => Some(start + Duration::seconds(index * 7 / 3)),
+            })
+            .collect();
+        let windows = [
+            RollingWindow::new("sum", 5, RollingAggregation::Sum),
+            RollingWindow::new("median", 4, RollingAggregation::Median).with_min_periods(2).with_alignment(WindowAlignment::Centred),
+            RollingWindow::over_duration("max", Duration::seconds(9), RollingAggregation::Max),
+            RollingWindow::over_duration("q", Duration::seconds(12), RollingAggregation::Quantile(0.3)),
+        ];
+        let built = |window: &RollingWindow, values: &[Option<f64>], stamps: &[Option<DateTime<Utc>>]| {
+            let mut rolling = Rolling::new(window.clone());
+            rolling.rebuild(values.iter().copied().zip(stamps.iter().copied()));
+            rolling
+        };
+        let results = |rolling: &Rolling| (0..rolling.len()).map(|index| rolling.get(index)).collect::<Vec<Option<f64>>>();
+        for window in windows.iter() {
+            for index in [0, 1, 10, 11, 12, 30, 57, 58, 59] {
+                let mut rolling = built(window, &values, &stamps);
+                let mut changed = values.clone();
+                changed[index] = Some(100.0);
+                rolling.update(index, changed.len(), |position| changed[position]);
+                rolling.push(Some(3.0), Some(start + Duration::seconds(200)));
+                changed.push(Some(3.0));
+                let mut changed_stamps = stamps.clone();
+                changed_stamps.push(Some(start + Duration::seconds(200)));
+                assert_eq!(results(&rolling), results(&built(window, &changed, &changed_stamps)), "{} update {}", window.name, index);
+
+                let mut rolling = built(window, &values, &stamps);
+                let mut remaining = values.clone();
+                let mut remaining_stamps = stamps.clone();
+                remaining.remove(index);
+                remaining_stamps.remove(index);
+                rolling.remove(index, remaining.len(), |position| remaining[position]);
+                rolling.push(Some(3.0), Some(start + Duration::seconds(200)));
+                remaining.push(Some(3.0));
+                remaining_stamps.push(Some(start + Duration::seconds(200)));
+                assert_eq!(results(&rolling), results(&built(window, &remaining, &remaining_stamps)), "{} remove {}", window.name, index);
+            }
+        }
+    }
+
+    #[test]
+    fn restore() {
+        let start = Utc::now();
+        let values = noisy(40);
+        let rows: Vec<(Option<f64>, Option<DateTime<Utc>>)> = values.iter().enumerate().map(|(index, value)| (*value, Some(start + Duration::seconds(index as i64 * 2)))).collect();
+        for window in [RollingWindow::new("median", 5, RollingAggregation::Median).with_alignment(WindowAlignment::Centred), RollingWindow::over_duration("sum", Duration::seconds(7), RollingAggregation::Sum)] {
+            let mut built = Rolling::new(window.clone());
+            built.rebuild(rows.iter().copied());
+            let mut restored = Rolling::new(window);
+            restored.restore(built.results().to_vec(), rows.iter().copied());
+            // the restored window carries on from the last row as the one it was saved from does
+            for rolling in [&mut built, &mut restored] {
+                rolling.push(Some(4.0), Some(start + Duration::seconds(80)));
+                rolling.push(Some(9.0), Some(start + Duration::seconds(81)));
+            }
+            assert_eq!(restored.results(), built.results());
+        }
+    }
+
+    #[test]
+    fn validate() {
+        assert!(RollingWindow::new("empty", 0, RollingAggregation::Mean).validate().is_err());
+        assert!(RollingWindow::new("q", 2, RollingAggregation::Quantile(1.5)).validate().is_err());
+        assert!(RollingWindow::new("mean", 2, RollingAggregation::Mean).with_min_periods(3).validate().is_err());
+        assert!(RollingWindow::new("mean", 2, RollingAggregation::Mean).validate().is_ok());
+        assert!(RollingWindow::over_duration("mean", Duration::zero(), RollingAggregation::Mean).validate().is_err());
+        assert!(RollingWindow::over_duration("mean", Duration::seconds(1), RollingAggregation::Mean).with_alignment(WindowAlignment::Centred).validate().is_err());
+    }
+}
@@ -1,14 +0,0 @@
-pub mod bool;
-pub mod f32;
-pub mod f64;
-pub mod i8;
-pub mod i16;
-pub mod i32;
-pub mod i64;
-pub mod isize;
-pub mod u8;
-pub mod u16;
-pub mod u32;
-pub mod u64;
-pub mod usize;
-pub mod utf8;
\ No newline at end of file
@@ -1,10 +0,0 @@
-use crate::column::{
-    Column
-};
-use crate::cell::{
-    types::datatypes::AnyType,
-};
-
-pub fn mean(_: &Column) -> Option<AnyType> {
-    None
-}
\ No newline at end of file
@@ -0,0 +1,68 @@
+use crate::cell::types::datatypes::DataType;
+use std::error::Error;
+use std::fmt::{
+    Display,
+    Formatter,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DataFrameError {
+    UnknownColumn(String),
+    DuplicateColumn(String),
+    ArityMismatch {
+        expected: usize,
+        found: usize,
+    },
+    TypeMismatch {
+        column: String,
+        expected: DataType,
+        found: DataType,
+    },
+    IndexOutOfRange {
+        index: usize,
+        len: usize,
+    },
+    EmptyColumn(String),
+    UnsupportedDtype {
+        column: String,
+        operation: &'static str,
+        dtype: DataType,
+    },
+    Overflow(String),
+    InvalidArgument(String),
+    Io(String),
+    Parse {
+        line: u64,
+        message: String,
+    },
+    Snapshot(String),
+}
+
+impl Display for DataFrameError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DataFrameError::UnknownColumn(name) => write!(f, "unknown column '{}'", name),
+            DataFrameError::DuplicateColumn(name) => write!(f, "column '{}' already exists", name),
+            DataFrameError::ArityMismatch { expected, found } => write!(f, "expected {} values but found {}", expected, found),
+            DataFrameError::TypeMismatch { column, expected, found } => write!(f, "column '{}' expected {} but found {}", column, expected, found),
+            DataFrameError::IndexOutOfRange { index, len } => write!(f, "index {} is out of range for length {}", index, len),
+            DataFrameError::EmptyColumn(name) => write!(f, "column '{}' is empty", name),
+            DataFrameError::UnsupportedDtype { column, operation, dtype } => write!(f, "column '{}' of type {} does not support {}", column, dtype, operation),
+            DataFrameError::Overflow(name) => write!(f, "result for column '{}' does not fit in its type", name),
+            DataFrameError::InvalidArgument(message) => write!(f, "invalid argument: {}", message),
+            DataFrameError::Io(message) => write!(f, "io error: {}", message),
+            DataFrameError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+            DataFrameError::Snapshot(message) => write!(f, "invalid snapshot: {}", message),
+        }
+    }
+}
+
+impl Error for DataFrameError {}
+
+impl From<std::io::Error> for DataFrameError {
+    fn from(error: std::io::Error) -> Self {
+        DataFrameError::Io(error.to_string())
+    }
+}
+
+pub type Result<T> = std::result::Result<T, DataFrameError>;
@@ -0,0 +1,167 @@
+use crate::cell::types::datatypes::{
+    AnyType,
+    DataType,
+};
+use crate::column::Column;
+use crate::error::{
+    DataFrameError,
+    Result,
+};
+use std::cmp::Ordering;
+use std::ops::Not;
+use std::rc::Rc;
+
+// comparisons never match a null or NaN, and values have to be of the column's type
+#[derive(Debug, Clone)]
+pub enum Predicate {
+    Eq(Rc<str>, AnyType),
+    NotEq(Rc<str>, AnyType),
+    Lt(Rc<str>, AnyType),
+    LtEq(Rc<str>, AnyType),
+    Gt(Rc<str>, AnyType),
+    GtEq(Rc<str>, AnyType),
+    IsNull(Rc<str>),
+    IsNotNull(Rc<str>),
+    In(Rc<str>, Vec<AnyType>),
+    And(Box<Predicate>, Box<Predicate>),
+    Or(Box<Predicate>, Box<Predicate>),
+    Not(Box<Predicate>),
+}
+
+impl Predicate {
+    pub fn eq<S: Into<Rc<str>>, V: Into<AnyType>>(column_name: S, value: V) -> Self {
+        Predicate::Eq(column_name.into(), value.into())
+    }
+
+    pub fn not_eq<S: Into<Rc<str>>, V: Into<AnyType>>(column_name: S, value: V) -> Self {
+        Predicate::NotEq(column_name.into(), value.into())
+    }
+
+    pub fn lt<S: Into<Rc<str>>, V: Into<AnyType>>(column_name: S, value: V) -> Self {
+        Predicate::Lt(column_name.into(), value.into())
+    }
+
+    pub fn lt_eq<S: Into<Rc<str>>, V: Into<AnyType>>(column_name: S, value: V) -> Self {
+        Predicate::LtEq(column_name.into(), value.into())
+    }
+
+    pub fn gt<S: Into<Rc<str>>, V: Into<AnyType>>(column_name: S, value: V) -> Self {
+        Predicate::Gt(column_name.into(), value.into())
+    }
+
+    pub fn gt_eq<S: Into<Rc<str>>, V: Into<AnyType>>(column_name: S, value: V) -> Self {
+        Predicate::GtEq(column_name.into(), value.into())
+    }
+
+    pub fn is_null<S: Into<Rc<str>>>(column_name: S) -> Self {
+        Predicate::IsNull(column_name.into())
+    }
+
+    pub fn is_not_null<S: Into<Rc<str>>>(column_name: S) -> Self {
+        Predicate::IsNotNull(column_name.into())
+    }
+
+    pub fn is_in<S: Into<Rc<str>>>(column_name: S, values: Vec<AnyType>) -> Self {
+        Predicate::In(column_name.into(), values)
+    }
+
+    pub fn and(self, other: Predicate) -> Self {
+        Predicate::And(Box::new(self), Box::new(other))
+    }
+
+    pub fn or(self, other: Predicate) -> Self {
+        Predicate::Or(Box::new(self), Box::new(other))
+    }
+
+    // one flag per row saying whether the row matches
+    pub fn evaluate(&self, columns: &[Column], len: usize) -> Result<Vec<bool>> {
+        let mask = match self {
+            Predicate::Eq(name, value) => {
+                let column = find_column(columns, name, Some(value))?;
+                indexed(column, &without_nulls(std::slice::from_ref(value)), len)
+            },
+            Predicate::In(name, values) => {
+                let column = find_column(columns, name, None)?;
+                for value in values.iter() {
+                    check_dtype(column, value)?;
+                }
+                indexed(column, &without_nulls(values), len)
+            },
+            Predicate::IsNull(name) => indexed(find_column(columns, name, None)?, &[AnyType::Null], len),
+            Predicate::IsNotNull(name) => indexed(find_column(columns, name, None)?, &[AnyType::Null], len).into_iter().map(|matched| !matched).collect(),
+            Predicate::NotEq(name, value) => scan(find_column(columns, name, Some(value))?, value, |ordering| ordering != Ordering::Equal),
+            Predicate::Lt(name, value) => scan(find_column(columns, name, Some(value))?, value, |ordering| ordering == Ordering::Less),
+            Predicate::LtEq(name, value) => scan(find_column(columns, name, Some(value))?, value, |ordering| ordering != Ordering::Greater),
+            Predicate::Gt(name, value) => scan(find_column(columns, name, Some(value))?, value, |ordering| ordering == Ordering::Greater),
+            Predicate::GtEq(name, value) => scan(find_column(columns, name, Some(value))?, value, |ordering| ordering != Ordering::Less),
+            Predicate::And(left, right) => {
+                let right = right.evaluate(columns, len)?;
+                left.evaluate(columns, len)?.into_iter().zip(right).map(|(left, right)| left && right).collect()
+            },
+            Predicate::Or(left, right) => {
+                let right = right.evaluate(columns, len)?;
+                left.evaluate(columns, len)?.into_iter().zip(right).map(|(left, right)| left || right).collect()
+            },
+            Predicate::Not(predicate) => predicate.evaluate(columns, len)?.into_iter().map(|matched| !matched).collect(),
+        };
+        Ok(mask)
+    }
+}
+
+impl Not for Predicate {
+    type Output = Predicate;
+
+    fn not(self) -> Self::Output {
+        Predicate::Not(Box::new(self))
+    }
+}
+
+fn find_column<'a>(columns: &'a [Column], name: &str, value: Option<&AnyType>) -> Result<&'a Column> {
+    let column = columns.iter().find(|c| &*c.name == name)
+        .ok_or_else(|| DataFrameError::UnknownColumn(name.to_string()))?;
+    if let Some(value) = value {
+        check_dtype(column, value)?;
+    }
+    Ok(column)
+}
+
+fn check_dtype(column: &Column, value: &AnyType) -> Result<()> {
+    let dtype = column.dtype();
+    if *value != AnyType::Null && dtype != DataType::Null && dtype != value.dtype() {
+        return Err(DataFrameError::TypeMismatch {
+            column: column.name.to_string(),
+            expected: dtype,
+            found: value.dtype(),
+        });
+    }
+    Ok(())
+}
+
+// equality is answered from the column's index of rows by value rather than by looking at every row
+fn indexed(column: &Column, values: &[AnyType], len: usize) -> Vec<bool> {
+    let mut mask = vec![false; len];
+    for value in values.iter() {
+        if let Some(rows) = column.get_grouped_values(value.clone()) {
+            for row in rows.iter() {
+                mask[*row] = true;
+            }
+        }
+    }
+    mask
+}
+
+// the index groups NaN with NaN, so it is left out along with nulls to keep NaN from ever matching
+fn without_nulls(values: &[AnyType]) -> Vec<AnyType> {
+    values.iter()
+        .filter(|value| **value != AnyType::Null && !value.as_f64().is_some_and(f64::is_nan))
+        .cloned()
+        .collect()
+}
+
+fn scan<F: Fn(Ordering) -> bool>(column: &Column, value: &AnyType, matches: F) -> Vec<bool> {
+    column.iter_values().map(|cell| match cell {
+        AnyType::Null => false,
+        cell if cell.as_f64().is_some_and(f64::is_nan) => false,
+        cell => matches(cell.cmp(value)),
+    }).collect()
+}
@@ -0,0 +1,230 @@
+use crate::cell::types::datatypes::AnyType;
+use crate::column::{
+    Column,
+    Returns,
+    RollingMean,
+};
+use crate::error::Result;
+use crate::frame::DataFrame;
+use std::collections::{
+    HashMap,
+    HashSet,
+};
+use std::rc::Rc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AggFunction {
+    // the number of non-null values
+    Count,
+    Sum,
+    Mean,
+    Min,
+    Max,
+    First,
+    Last,
+    Std,
+    // the number of distinct values, with null counted as one
+    NUnique,
+}
+
+impl AggFunction {
+    fn suffix(&self) -> &'static str {
+        match self {
+            AggFunction::Count => "count",
+            AggFunction::Sum => "sum",
+            AggFunction::Mean => "mean",
+            AggFunction::Min => "min",
+            AggFunction::Max => "max",
+            AggFunction::First => "first",
+            AggFunction::Last => "last",
+            AggFunction::Std => "std",
+            AggFunction::NUnique => "n_unique",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Aggregation {
+    pub column_name: Rc<str>,
+    pub function: AggFunction,
+    pub alias: Rc<str>,
+}
+
+impl Aggregation {
+    // the result column is named after the source column and function, e.g. price_sum
+    pub fn new<S: Into<Rc<str>>>(column_name: S, function: AggFunction) -> Self {
+        let column_name: Rc<str> = column_name.into();
+        let alias = Rc::from(format!("{}_{}", column_name, function.suffix()));
+        Self {
+            column_name,
+            function,
+            alias,
+        }
+    }
+
+    pub fn count<S: Into<Rc<str>>>(column_name: S) -> Self {
+        Self::new(column_name, AggFunction::Count)
+    }
+
+    pub fn sum<S: Into<Rc<str>>>(column_name: S) -> Self {
+        Self::new(column_name, AggFunction::Sum)
+    }
+
+    pub fn mean<S: Into<Rc<str>>>(column_name: S) -> Self {
+        Self::new(column_name, AggFunction::Mean)
+    }
+
+    pub fn min<S: Into<Rc<str>>>(column_name: S) -> Self {
+        Self::new(column_name, AggFunction::Min)
+    }
+
+    pub fn max<S: Into<Rc<str>>>(column_name: S) -> Self {
+        Self::new(column_name, AggFunction::Max)
+    }
+
+    pub fn first<S: Into<Rc<str>>>(column_name: S) -> Self {
+        Self::new(column_name, AggFunction::First)
+    }
+
+    pub fn last<S: Into<Rc<str>>>(column_name: S) -> Self {
+        Self::new(column_name, AggFunction::Last)
+    }
+
+    pub fn std<S: Into<Rc<str>>>(column_name: S) -> Self {
+        Self::new(column_name, AggFunction::Std)
+    }
+
+    pub fn n_unique<S: Into<Rc<str>>>(column_name: S) -> Self {
+        Self::new(column_name, AggFunction::NUnique)
+    }
+
+    pub fn alias<S: Into<Rc<str>>>(mut self, alias: S) -> Self {
+        self.alias = alias.into();
+        self
+    }
+
+    // taken holds the group's values once one numeric aggregation has copied them, for the others over the same column
+    fn apply(&self, column: &Column, rows: &[usize], taken: &mut Option<Column>) -> Result<AnyType> {
+        let value = match self.function {
+            AggFunction::Count => AnyType::USize(rows.iter().filter(|row| column.get_buffer().is_valid(**row)).count()),
+            AggFunction::First => rows.first().and_then(|row| column.get_value(*row)).unwrap_or(AnyType::Null),
+            AggFunction::Last => rows.last().and_then(|row| column.get_value(*row)).unwrap_or(AnyType::Null),
+            AggFunction::NUnique => {
+                let unique: HashSet<AnyType> = rows.iter().filter_map(|row| column.get_value(*row)).collect();
+                AnyType::USize(unique.len())
+            },
+            // the numeric aggregations are taken over a copy of the group's values
+            AggFunction::Sum | AggFunction::Mean | AggFunction::Min | AggFunction::Max | AggFunction::Std => {
+                let values = match taken {
+                    Some(values) => values,
+                    None => taken.insert(column.take(rows)?),
+                };
+                let value = match self.function {
+                    AggFunction::Sum => values.sum()?,
+                    AggFunction::Mean => values.mean()?,
+                    AggFunction::Min => values.min()?,
+                    AggFunction::Max => values.max()?,
+                    _ => values.std()?,
+                };
+                value.unwrap_or(AnyType::Null)
+            },
+        };
+        Ok(value)
+    }
+}
+
+// the rows of a frame split by the values in the key columns, groups are kept in the order they first appear
+#[derive(Debug)]
+pub struct GroupBy<'a> {
+    dataframe: &'a DataFrame,
+    keys: Vec<&'a Column>,
+    groups: Vec<(Vec<AnyType>, Vec<usize>)>,
+}
+
+impl<'a> GroupBy<'a> {
+    pub fn new(dataframe: &'a DataFrame, key_names: &[&str]) -> Result<Self> {
+        let keys = key_names.iter().map(|name| dataframe.get_column_by_name(name)).collect::<Result<Vec<&Column>>>()?;
+        let groups = match keys.as_slice() {
+            // a single key is already bucketed by the column's value index
+            [key] => {
+                let mut groups: Vec<(Vec<AnyType>, Vec<usize>)> = key.iter_values()
+                    .enumerate()
+                    .filter_map(|(row, value)| {
+                        let rows = key.get_grouped_values(value.clone())?;
+                        (rows.first() == Some(&row)).then(|| (vec![value], rows.clone()))
+                    })
+                    .collect();
+                groups.sort_by_key(|(_, rows)| rows[0]);
+                groups
+            },
+            _ => {
+                let mut positions: HashMap<Vec<AnyType>, usize> = HashMap::new();
+                let mut groups: Vec<(Vec<AnyType>, Vec<usize>)> = vec![];
+                for row in 0..dataframe.len() {
+                    let key: Vec<AnyType> = keys.iter().map(|column| column.get_value(row).unwrap_or(AnyType::Null)).collect();
+                    match positions.get(&key) {
+                        Some(position) => groups[*position].1.push(row),
+                        None => {
+                            positions.insert(key.clone(), groups.len());
+                            groups.push((key, vec![row]));
+                        },
+                    }
+                }
+                groups
+            },
+        };
+        Ok(Self {
+            dataframe,
+            keys,
+            groups,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.groups.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    pub fn get_groups(&self) -> &Vec<(Vec<AnyType>, Vec<usize>)> {
+        &self.groups
+    }
+
+    // one row per group holding the key values followed by each aggregation, stamped with the time of the group's first row
+    pub fn agg<I: IntoIterator<Item = Aggregation>>(&self, aggregations: I) -> Result<DataFrame> {
+        let aggregations: Vec<Aggregation> = aggregations.into_iter().collect();
+        let sources = aggregations.iter().map(|aggregation| self.dataframe.get_column_by_name(&aggregation.column_name)).collect::<Result<Vec<&Column>>>()?;
+        // aggregations over the same column share one copy of each group's values
+        let shared: Vec<usize> = aggregations.iter()
+            .map(|aggregation| aggregations.iter().position(|other| other.column_name == aggregation.column_name).unwrap_or(0))
+            .collect();
+
+        let mut datetimes = Vec::with_capacity(self.groups.len());
+        let mut columns: Vec<Column> = self.keys.iter()
+            .map(|key| Column::from_field(key.get_field(), RollingMean::new(false, None), Returns::new(false, None)))
+            .collect();
+        columns.extend(aggregations.iter().map(|aggregation| Column::new(Rc::clone(&aggregation.alias), RollingMean::new(false, None), Returns::new(false, None))));
+        for (key, rows) in self.groups.iter() {
+            datetimes.push(self.dataframe.get_datetime(rows[0])?);
+            for (column, value) in columns.iter_mut().zip(key.iter()) {
+                column.push(value.clone())?;
+            }
+            let mut taken: Vec<Option<Column>> = aggregations.iter().map(|_| None).collect();
+            for (index, (aggregation, source)) in aggregations.iter().zip(sources.iter()).enumerate() {
+                columns[self.keys.len() + index].push(aggregation.apply(source, rows, &mut taken[shared[index]])?)?;
+            }
+        }
+
+        let mut dataframe = DataFrame {
+            datetimes,
+            columns: vec![],
+            clock: Rc::clone(&self.dataframe.clock)
+        };
+        for column in columns.into_iter() {
+            dataframe.add_column(column)?;
+        }
+        Ok(dataframe)
+    }
+}
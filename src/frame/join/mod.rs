@@ -0,0 +1,243 @@
+use crate::cell::types::datatypes::{
+    AnyType,
+    DataType,
+};
+use crate::column::{
+    Column,
+    Returns,
+    RollingMean,
+};
+use crate::error::{
+    DataFrameError,
+    Result,
+};
+use crate::frame::DataFrame;
+use crate::schema::Field;
+use chrono::Duration;
+use std::collections::{
+    HashMap,
+    HashSet,
+};
+use std::rc::Rc;
+
+// the suffix given to a column from the right frame whose name is already taken by the left frame
+pub const DEFAULT_SUFFIX: &str = "_right";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinType {
+    // the rows with a match in both frames
+    Inner,
+    // every left row, with nulls where the right frame has no match
+    Left,
+    // every right row, with nulls where the left frame has no match
+    Right,
+    // every row from both frames
+    Outer,
+    // the left rows with a match, keeping only the left columns
+    Semi,
+    // the left rows without a match, keeping only the left columns
+    Anti,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AsofDirection {
+    // the last right row at or before the left row's time
+    Backward,
+    // the first right row at or after the left row's time
+    Forward,
+    // whichever of the two is closer, backward winning a tie
+    Nearest,
+}
+
+// the row of each frame making up a row of the joined frame
+type RowPairs = Vec<(Option<usize>, Option<usize>)>;
+
+// keys holding a null never match anything, the same as in SQL, but the rows are still kept by outer joins.
+// the key columns appear once, taking their values from whichever frame has the row
+pub fn hash_join(left: &DataFrame, right: &DataFrame, left_on: &[&str], right_on: &[&str], how: JoinType, suffix: &str) -> Result<DataFrame> {
+    if left_on.len() != right_on.len() {
+        return Err(DataFrameError::ArityMismatch { expected: left_on.len(), found: right_on.len() });
+    }
+    if left_on.is_empty() {
+        return Err(DataFrameError::InvalidArgument("a join needs at least one key column".to_string()));
+    }
+    let left_keys = left_on.iter().map(|name| left.get_column_index(name)).collect::<Result<Vec<usize>>>()?;
+    let right_keys = right_on.iter().map(|name| right.get_column_index(name)).collect::<Result<Vec<usize>>>()?;
+    check_key_dtypes(left, right, &left_keys, &right_keys)?;
+
+    let pairs = match_rows(left, right, &left_keys, &right_keys, how);
+    let left_missing = matches!(how, JoinType::Right | JoinType::Outer);
+    let right_missing = matches!(how, JoinType::Left | JoinType::Outer);
+    let right_keys = match how {
+        JoinType::Semi | JoinType::Anti => None,
+        _ => Some(right_keys.as_slice()),
+    };
+    build(left, right, &left_keys, right_keys, &pairs, (left_missing, right_missing), suffix)
+}
+
+// matches each left row with the right row closest in time in the given direction, the joined frame keeps the rows
+// and times of the left frame. right rows can be in any order, with rows at the same time the later row is taken
+// looking backward and the earlier row looking forward
+pub fn asof_join(left: &DataFrame, right: &DataFrame, by: &[&str], direction: AsofDirection, tolerance: Option<Duration>, suffix: &str) -> Result<DataFrame> {
+    if tolerance.is_some_and(|tolerance| tolerance < Duration::zero()) {
+        return Err(DataFrameError::InvalidArgument("the tolerance of an as-of join cannot be negative".to_string()));
+    }
+    let left_keys = by.iter().map(|name| left.get_column_index(name)).collect::<Result<Vec<usize>>>()?;
+    let right_keys = by.iter().map(|name| right.get_column_index(name)).collect::<Result<Vec<usize>>>()?;
+    check_key_dtypes(left, right, &left_keys, &right_keys)?;
+
+    let mut table: HashMap<Vec<AnyType>, Vec<usize>> = HashMap::new();
+    for row in 0..right.len() {
+        if let Some(key) = row_key(right, &right_keys, row) {
+            table.entry(key).or_default().push(row);
+        }
+    }
+    for rows in table.values_mut() {
+        rows.sort_by_key(|row| right.datetimes[*row]);
+    }
+
+    let pairs: RowPairs = (0..left.len())
+        .map(|row| {
+            let datetime = left.datetimes[row];
+            let rows = match row_key(left, &left_keys, row).and_then(|key| table.get(&key)) {
+                Some(rows) => rows,
+                None => return (Some(row), None),
+            };
+            let before = rows[..rows.partition_point(|right_row| right.datetimes[*right_row] <= datetime)].last();
+            let after = rows.get(rows.partition_point(|right_row| right.datetimes[*right_row] < datetime));
+            let closest = match direction {
+                AsofDirection::Backward => before,
+                AsofDirection::Forward => after,
+                AsofDirection::Nearest => match (before, after) {
+                    (Some(before), Some(after)) if right.datetimes[*after] - datetime < datetime - right.datetimes[*before] => Some(after),
+                    (None, after) => after,
+                    (before, _) => before,
+                },
+            };
+            let closest = closest.filter(|right_row| match tolerance {
+                Some(tolerance) => (right.datetimes[**right_row] - datetime).abs() <= tolerance,
+                None => true,
+            });
+            (Some(row), closest.copied())
+        })
+        .collect();
+    build(left, right, &left_keys, Some(&right_keys), &pairs, (false, true), suffix)
+}
+
+fn check_key_dtypes(left: &DataFrame, right: &DataFrame, left_keys: &[usize], right_keys: &[usize]) -> Result<()> {
+    for (left_key, right_key) in left_keys.iter().zip(right_keys.iter()) {
+        let (left_dtype, right_dtype) = (left.columns[*left_key].dtype(), right.columns[*right_key].dtype());
+        if left_dtype != right_dtype && left_dtype != DataType::Null && right_dtype != DataType::Null {
+            return Err(DataFrameError::TypeMismatch {
+                column: right.columns[*right_key].name.to_string(),
+                expected: left_dtype,
+                found: right_dtype,
+            });
+        }
+    }
+    Ok(())
+}
+
+// the left columns followed by the right columns other than its keys, or only the left columns without right keys.
+// missing says whether rows can be missing from the left and right frames so their columns have to allow nulls
+fn build(left: &DataFrame, right: &DataFrame, left_keys: &[usize], right_keys: Option<&[usize]>, pairs: &RowPairs, missing: (bool, bool), suffix: &str) -> Result<DataFrame> {
+    let datetimes = pairs.iter()
+        .map(|pair| match pair {
+            (Some(left_row), _) => left.datetimes[*left_row],
+            (None, Some(right_row)) => right.datetimes[*right_row],
+            (None, None) => unreachable!(),
+        })
+        .collect();
+    let mut dataframe = DataFrame {
+        datetimes,
+        columns: vec![],
+        clock: Rc::clone(&left.clock)
+    };
+
+    let (left_missing, right_missing) = missing;
+    for (index, column) in left.columns.iter().enumerate() {
+        let key = left_keys.iter().position(|left_key| *left_key == index);
+        let mut field = column.get_field();
+        // rows only in the right frame take their key from the right key column, nulls and all
+        field.nullable |= left_missing && match (key, right_keys) {
+            (Some(key), Some(right_keys)) => right.columns[right_keys[key]].is_nullable(),
+            _ => true,
+        };
+        let mut joined = Column::from_field(field, RollingMean::new(false, None), Returns::new(false, None));
+        for pair in pairs.iter() {
+            let value = match (pair, key, right_keys) {
+                ((Some(left_row), _), _, _) => column.get_value(*left_row),
+                ((None, Some(right_row)), Some(key), Some(right_keys)) => right.columns[right_keys[key]].get_value(*right_row),
+                _ => None,
+            };
+            joined.push(value.unwrap_or(AnyType::Null))?;
+        }
+        dataframe.add_column(joined)?;
+    }
+    let right_keys = match right_keys {
+        Some(right_keys) => right_keys,
+        None => return Ok(dataframe),
+    };
+
+    let left_names: HashSet<&str> = left.columns.iter().map(|column| &*column.name).collect();
+    for (index, column) in right.columns.iter().enumerate() {
+        if right_keys.contains(&index) {
+            continue;
+        }
+        let name: Rc<str> = match left_names.contains(&*column.name) {
+            true => Rc::from(format!("{}{}", column.name, suffix)),
+            false => Rc::clone(&column.name),
+        };
+        let field = Field::new(name, column.dtype(), column.is_nullable() || right_missing);
+        let mut joined = Column::from_field(field, RollingMean::new(false, None), Returns::new(false, None));
+        for (_, right_row) in pairs.iter() {
+            joined.push(right_row.and_then(|right_row| column.get_value(right_row)).unwrap_or(AnyType::Null))?;
+        }
+        dataframe.add_column(joined)?;
+    }
+    Ok(dataframe)
+}
+
+// rows follow the left frame, with a left row's matches in right frame order, except for right joins which follow
+// the right frame. outer joins finish with the right rows that matched nothing
+fn match_rows(left: &DataFrame, right: &DataFrame, left_keys: &[usize], right_keys: &[usize], how: JoinType) -> RowPairs {
+    let (build, build_keys, probe, probe_keys) = match how {
+        JoinType::Right => (left, left_keys, right, right_keys),
+        _ => (right, right_keys, left, left_keys),
+    };
+    let mut table: HashMap<Vec<AnyType>, Vec<usize>> = HashMap::new();
+    for row in 0..build.len() {
+        if let Some(key) = row_key(build, build_keys, row) {
+            table.entry(key).or_default().push(row);
+        }
+    }
+
+    let mut pairs = vec![];
+    let mut matched = vec![false; build.len()];
+    for row in 0..probe.len() {
+        let rows = row_key(probe, probe_keys, row).and_then(|key| table.get(&key));
+        match (how, rows) {
+            (JoinType::Semi, Some(_)) | (JoinType::Anti, None) => pairs.push((Some(row), None)),
+            (JoinType::Semi, None) | (JoinType::Anti, Some(_)) | (JoinType::Inner, None) => {},
+            (JoinType::Left, None) | (JoinType::Outer, None) => pairs.push((Some(row), None)),
+            (JoinType::Right, None) => pairs.push((None, Some(row))),
+            (JoinType::Right, Some(rows)) => pairs.extend(rows.iter().map(|left_row| (Some(*left_row), Some(row)))),
+            (_, Some(rows)) => {
+                for right_row in rows.iter() {
+                    matched[*right_row] = true;
+                    pairs.push((Some(row), Some(*right_row)));
+                }
+            },
+        }
+    }
+    if how == JoinType::Outer {
+        pairs.extend(matched.into_iter().enumerate().filter_map(|(right_row, matched)| (!matched).then_some((None, Some(right_row)))));
+    }
+    pairs
+}
+
+fn row_key(dataframe: &DataFrame, keys: &[usize], row: usize) -> Option<Vec<AnyType>> {
+    keys.iter()
+        .map(|key| dataframe.columns[*key].get_value(row).filter(|value| *value != AnyType::Null))
+        .collect()
+}
@@ -1,197 +1,637 @@
+pub mod filter;
+pub mod group_by;
+pub mod join;
+
 use crate::row::{
     Row,
-    RcRow
 };
 use crate::column::{
     Column,
     RollingMean,
     Returns,
+    ReturnsKind,
+    RateOfChange,
+    rolling::RollingWindow,
 };
 use crate::cell::{
-    types::datatypes::AnyType,
-    Cell,
+    types::datatypes::{
+        AnyType,
+        DataType,
+    },
+};
+use crate::error::{
+    DataFrameError,
+    Result,
+};
+use crate::clock::{
+    Clock,
+    SystemClock,
+};
+use crate::schema::{
+    Schema,
+};
+use crate::io::csv::{
+    CsvReadOptions,
+    CsvWriteOptions,
 };
-use std::cell::{
-    RefCell,
-    Ref,
-    RefMut,
+use crate::io::json::{
+    JsonReadOptions,
+    JsonWriteOptions,
 };
+use filter::Predicate;
+use group_by::GroupBy;
+use join::{
+    AsofDirection,
+    JoinType,
+};
+use std::cmp::Ordering;
+use std::rc::Rc;
 use chrono::{
     DateTime,
+    Duration,
     Utc
 };
 
+type ColumnUpdates = Vec<(usize, AnyType)>;
+
 #[derive(Debug)]
 pub struct DataFrame {
-    rows: RefCell<Vec<RcRow>>,
-    columns: Vec<Column>
+    datetimes: Vec<DateTime<Utc>>,
+    columns: Vec<Column>,
+    clock: Rc<dyn Clock>
 }
 
 impl DataFrame {
-    pub fn new(column_names: Vec<&'static str>) -> Self {
-        let mut columns = vec![];
-        for column_name in column_names.iter() {
-            let column = Column::new(*column_name, RollingMean::new(false, None), Returns::new(false, None));
-            columns.push(column);
+    pub fn new<S: Into<Rc<str>>>(column_names: Vec<S>) -> Result<Self> {
+        Self::with_clock(column_names, Rc::new(SystemClock::default()))
+    }
+
+    // rows added without an explicit timestamp are stamped with the time given by the clock
+    pub fn with_clock<S: Into<Rc<str>>>(column_names: Vec<S>, clock: Rc<dyn Clock>) -> Result<Self> {
+        let mut dataframe = Self {
+            datetimes: vec![],
+            columns: vec![],
+            clock
+        };
+        for column_name in column_names.into_iter() {
+            let column = Column::new(column_name, RollingMean::new(false, None), Returns::new(false, None));
+            dataframe.add_column(column)?;
+        }
+        Ok(dataframe)
+    }
+
+    // a frame of columns already holding a value for each of the timestamps
+    pub fn from_columns(datetimes: Vec<DateTime<Utc>>, columns: Vec<Column>) -> Result<Self> {
+        let mut dataframe = Self {
+            datetimes,
+            columns: vec![],
+            clock: Rc::new(SystemClock::default())
+        };
+        for column in columns.into_iter() {
+            dataframe.add_column(column)?;
         }
+        Ok(dataframe)
+    }
 
-        Self {
-            rows: RefCell::new(vec![]),
-            columns
+    // every column is created with the type given in the schema and rejects values of any other type
+    pub fn with_schema(schema: Schema) -> Result<Self> {
+        let mut dataframe = Self::new(Vec::<Rc<str>>::new())?;
+        for field in schema.get_fields().iter() {
+            let column = Column::from_field(field.clone(), RollingMean::new(false, None), Returns::new(false, None));
+            dataframe.add_column(column)?;
         }
+        Ok(dataframe)
+    }
+
+    pub fn read_csv<P: AsRef<std::path::Path>>(path: P, options: &CsvReadOptions) -> Result<Self> {
+        crate::io::csv::read_path(path, options)
+    }
+
+    pub fn read_csv_from_reader<R: std::io::Read>(reader: R, options: &CsvReadOptions) -> Result<Self> {
+        crate::io::csv::read(reader, options)
+    }
+
+    pub fn write_csv<W: std::io::Write>(&self, writer: W, options: &CsvWriteOptions) -> Result<()> {
+        crate::io::csv::write(writer, self, options)
+    }
+
+    pub fn read_json<R: std::io::Read>(reader: R, options: &JsonReadOptions) -> Result<Self> {
+        crate::io::json::read(reader, options)
+    }
+
+    pub fn read_ndjson<R: std::io::Read>(reader: R, options: &JsonReadOptions) -> Result<Self> {
+        crate::io::json::read_ndjson(reader, options)
+    }
+
+    pub fn write_json<W: std::io::Write>(&self, writer: W, options: &JsonWriteOptions) -> Result<()> {
+        crate::io::json::write(writer, self, options)
+    }
+
+    pub fn write_ndjson<W: std::io::Write>(&self, writer: W, options: &JsonWriteOptions) -> Result<()> {
+        crate::io::json::write_ndjson(writer, self, options)
+    }
+
+    // the time of each row is written to the timestamp column when one is named
+    #[cfg(feature = "arrow")]
+    pub fn to_record_batch(&self, timestamp_column: Option<&str>) -> Result<arrow::record_batch::RecordBatch> {
+        crate::io::arrow::to_record_batch(self, timestamp_column)
+    }
+
+    #[cfg(feature = "arrow")]
+    pub fn from_record_batch(batch: &arrow::record_batch::RecordBatch, timestamp_column: Option<&str>) -> Result<Self> {
+        crate::io::arrow::from_record_batch(batch, timestamp_column)
+    }
+
+    #[cfg(feature = "arrow")]
+    pub fn read_ipc_file<R: std::io::Read + std::io::Seek>(reader: R, timestamp_column: Option<&str>) -> Result<Self> {
+        crate::io::arrow::read_ipc_file(reader, timestamp_column)
+    }
+
+    #[cfg(feature = "arrow")]
+    pub fn read_ipc_stream<R: std::io::Read>(reader: R, timestamp_column: Option<&str>) -> Result<Self> {
+        crate::io::arrow::read_ipc_stream(reader, timestamp_column)
+    }
+
+    #[cfg(feature = "arrow")]
+    pub fn write_ipc_file<W: std::io::Write>(&self, writer: W, timestamp_column: Option<&str>) -> Result<()> {
+        crate::io::arrow::write_ipc_file(writer, self, timestamp_column)
+    }
+
+    #[cfg(feature = "arrow")]
+    pub fn write_ipc_stream<W: std::io::Write>(&self, writer: W, timestamp_column: Option<&str>) -> Result<()> {
+        crate::io::arrow::write_ipc_stream(writer, self, timestamp_column)
     }
 
-    pub fn get_rows(&self) -> Ref<Vec<RcRow>> {
-        self.rows.borrow()
+    #[cfg(feature = "parquet")]
+    pub fn read_parquet<P: AsRef<std::path::Path>>(path: P, options: &crate::io::parquet::ParquetReadOptions) -> Result<Self> {
+        crate::io::parquet::read(std::fs::File::open(path)?, options)
+    }
+
+    #[cfg(feature = "parquet")]
+    pub fn write_parquet<W: std::io::Write + Send>(&self, writer: W, options: &crate::io::parquet::ParquetWriteOptions) -> Result<()> {
+        crate::io::parquet::write(writer, self, options)
+    }
+
+    pub fn save_snapshot<P: AsRef<std::path::Path>>(&self, path: P) -> Result<()> {
+        crate::io::snapshot::save(path, self)
+    }
+
+    pub fn load_snapshot<P: AsRef<std::path::Path>>(path: P) -> Result<Self> {
+        crate::io::snapshot::load(path)
+    }
+
+    pub fn schema(&self) -> Schema {
+        Schema::new(self.columns.iter().map(|c| c.get_field()).collect())
+    }
+
+    pub fn len(&self) -> usize {
+        self.datetimes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.datetimes.is_empty()
+    }
+
+    pub fn get_row(&self, row_index: usize) -> Result<Row> {
+        let datetime = self.get_datetime(row_index)?;
+        let cells = self.columns.iter().filter_map(|c| c.get_cell(row_index)).collect();
+        Ok(Row::new(row_index, datetime, cells))
+    }
+
+    pub fn get_rows(&self) -> Vec<Row> {
+        (0..self.len()).filter_map(|row_index| self.get_row(row_index).ok()).collect()
+    }
+
+    pub fn get_datetime(&self, row_index: usize) -> Result<DateTime<Utc>> {
+        self.datetimes.get(row_index).copied()
+            .ok_or(DataFrameError::IndexOutOfRange { index: row_index, len: self.len() })
+    }
+
+    pub fn get_datetimes(&self) -> &Vec<DateTime<Utc>> {
+        &self.datetimes
     }
 
     pub fn get_columns(&self) -> &Vec<Column> {
         &self.columns
     }
 
-    pub fn add_row(&mut self, cell_values: Vec<AnyType>) -> usize {
-        let total_rows = self.rows.borrow().len();
-        let row = Row::new(total_rows);
-        let row_index = row.borrow().index;
-        for (index, cell_value) in cell_values.iter().enumerate() {
-            let column: &mut Column = &mut self.columns[index];
-            let cell = Cell::new(*cell_value, &row, &column.name.clone());
-            row.borrow_mut().add_cell(&cell);
-            column.add_cell(&cell);
+    // a new frame of the given rows in that order, derived columns are copied as plain values
+    pub fn take(&self, row_indices: &[usize]) -> Result<DataFrame> {
+        let mut datetimes = Vec::with_capacity(row_indices.len());
+        for row_index in row_indices.iter() {
+            datetimes.push(self.get_datetime(*row_index)?);
+        }
+        let columns = self.columns.iter().map(|column| column.take(row_indices)).collect::<Result<Vec<Column>>>()?;
+        Ok(Self {
+            datetimes,
+            columns,
+            clock: Rc::clone(&self.clock)
+        })
+    }
+
+    // a new frame with the rows ordered by the values in the given columns, earlier columns taking precedence,
+    // descending is given once for every column or once per column and rows with equal keys keep their order
+    pub fn sort_by(&self, column_names: &[&str], descending: &[bool], nulls_last: bool) -> Result<DataFrame> {
+        if descending.len() != 1 && descending.len() != column_names.len() {
+            return Err(DataFrameError::ArityMismatch { expected: column_names.len(), found: descending.len() });
+        }
+        let keys: Vec<(&Column, bool)> = column_names.iter().enumerate()
+            .map(|(index, name)| Ok((self.get_column_by_name(name)?, descending[index.min(descending.len() - 1)])))
+            .collect::<Result<Vec<(&Column, bool)>>>()?;
+        let mut row_indices: Vec<usize> = (0..self.len()).collect();
+        row_indices.sort_by(|left, right| {
+            keys.iter().fold(Ordering::Equal, |ordering, (column, descending)| ordering.then_with(|| {
+                let left = column.get_value(*left).unwrap_or(AnyType::Null);
+                let right = column.get_value(*right).unwrap_or(AnyType::Null);
+                match (left == AnyType::Null, right == AnyType::Null) {
+                    (true, true) => Ordering::Equal,
+                    (true, false) if nulls_last => Ordering::Greater,
+                    (true, false) => Ordering::Less,
+                    (false, true) if nulls_last => Ordering::Less,
+                    (false, true) => Ordering::Greater,
+                    (false, false) if *descending => right.cmp(&left),
+                    (false, false) => left.cmp(&right),
+                }
+            }))
+        });
+        self.reorder(&row_indices)
+    }
+
+    // the same frame with its rows in the given order, with every rolling window and returns column
+    // calculated again so each row's derived values follow the new order
+    pub fn reorder(&self, row_indices: &[usize]) -> Result<DataFrame> {
+        let mut datetimes = Vec::with_capacity(row_indices.len());
+        for row_index in row_indices.iter() {
+            datetimes.push(self.get_datetime(*row_index)?);
+        }
+        let columns = self.columns.iter().map(|column| column.reorder(row_indices, &datetimes)).collect::<Result<Vec<Column>>>()?;
+        let mut dataframe = Self {
+            datetimes,
+            columns,
+            clock: Rc::clone(&self.clock)
+        };
+        let every_row: Vec<usize> = (0..dataframe.len()).collect();
+        let changed = dataframe.columns.iter().map(|column| if dataframe.is_returns_column(&column.name) { vec![] } else { every_row.clone() }).collect();
+        dataframe.recalculate_returns(changed)?;
+        Ok(dataframe)
+    }
+
+    // a new frame of the rows matching the predicate, in their original order
+    pub fn filter(&self, predicate: &Predicate) -> Result<DataFrame> {
+        let row_indices: Vec<usize> = predicate.evaluate(&self.columns, self.len())?
+            .into_iter()
+            .enumerate()
+            .filter_map(|(row_index, matched)| matched.then_some(row_index))
+            .collect();
+        self.take(&row_indices)
+    }
+
+    // the rows split into groups by the values in the given columns, a null key is a group of its own
+    pub fn group_by(&self, column_names: &[&str]) -> Result<GroupBy<'_>> {
+        GroupBy::new(self, column_names)
+    }
+
+    // a new frame combining the rows of both frames whose values in the key columns are equal, clashing column
+    // names from the other frame are suffixed with _right
+    pub fn join(&self, other: &DataFrame, left_on: &[&str], right_on: &[&str], how: JoinType) -> Result<DataFrame> {
+        join::hash_join(self, other, left_on, right_on, how, join::DEFAULT_SUFFIX)
+    }
+
+    pub fn join_with_suffix(&self, other: &DataFrame, left_on: &[&str], right_on: &[&str], how: JoinType, suffix: &str) -> Result<DataFrame> {
+        join::hash_join(self, other, left_on, right_on, how, suffix)
+    }
+
+    // a new frame of these rows, each with the values of the other frame's row closest in time in the given direction,
+    // or nulls where there is none within the tolerance
+    pub fn join_asof(&self, other: &DataFrame, direction: AsofDirection, tolerance: Option<Duration>) -> Result<DataFrame> {
+        join::asof_join(self, other, &[], direction, tolerance, join::DEFAULT_SUFFIX)
+    }
+
+    // an as-of join where rows are only matched with rows holding the same values in the by columns
+    pub fn join_asof_by(&self, other: &DataFrame, by: &[&str], direction: AsofDirection, tolerance: Option<Duration>) -> Result<DataFrame> {
+        join::asof_join(self, other, by, direction, tolerance, join::DEFAULT_SUFFIX)
+    }
+
+    pub fn add_row(&mut self, cell_values: Vec<AnyType>) -> Result<usize> {
+        let datetime = self.clock.now();
+        self.add_row_at(datetime, cell_values)
+    }
+
+    pub fn add_row_at(&mut self, datetime: DateTime<Utc>, cell_values: Vec<AnyType>) -> Result<usize> {
+        // returns columns are populated from their source column so are not supplied by the caller
+        let value_columns: Vec<usize> = (0..self.columns.len()).filter(|index| !self.is_returns_column(&self.columns[*index].name)).collect();
+        if cell_values.len() != value_columns.len() {
+            return Err(DataFrameError::ArityMismatch { expected: value_columns.len(), found: cell_values.len() });
+        }
+        // check every value before storing any so a bad row leaves the frame untouched
+        for (index, cell_value) in value_columns.iter().zip(cell_values.iter()) {
+            self.columns[*index].check_value(cell_value)?;
         }
-        // this does not update the returns for columns
-        self.add_returns_for_cells(row_index, &row);
-        self.rows.borrow_mut().push(row);
-        row_index
+        for column in self.columns.iter() {
+            column.check_datetime(datetime)?;
+        }
+
+        let row_index = self.len();
+        let pushed = value_columns.into_iter().zip(cell_values).try_for_each(|(index, cell_value)| self.columns[index].push_at(cell_value, datetime));
+        self.datetimes.push(datetime);
+        // returns are only known once the row is in, so a returns column refusing its value takes the whole row back out
+        if let Err(error) = pushed.and_then(|_| self.add_returns_for_cells(row_index)) {
+            for column in self.columns.iter_mut().filter(|column| column.len() > row_index) {
+                column.remove(row_index)?;
+            }
+            self.datetimes.truncate(row_index);
+            return Err(error);
+        }
+        Ok(row_index)
     }
 
-    pub fn add_column_from_values(&mut self, column_name: &'static str, cell_values: Vec<AnyType>, rolling_mean: RollingMean) {
-        let mut column = Column::new(column_name, rolling_mean, Returns::new(false, None));
-        for (index, cell_value) in cell_values.iter().enumerate() {
-            let row = &self.rows.borrow()[index];
-            let cell = Cell::new(*cell_value, row, column_name.clone());
-            row.borrow_mut().add_cell(&cell);
-            column.add_cell(&cell);
+    pub fn add_column_from_values<S: Into<Rc<str>>>(&mut self, column_name: S, cell_values: Vec<AnyType>, rolling_mean: RollingMean) -> Result<()> {
+        let column_name: Rc<str> = column_name.into();
+        self.check_column_name_available(&column_name)?;
+        if cell_values.len() != self.len() {
+            return Err(DataFrameError::ArityMismatch { expected: self.len(), found: cell_values.len() });
+        }
+
+        // the rolling mean is added once every value is in, as the rows need not be in time order
+        let mut column = Column::new(column_name, RollingMean::new(false, None), Returns::new(false, None));
+        for cell_value in cell_values.into_iter() {
+            column.push(cell_value)?;
         }
+        column.update_rolling_mean(rolling_mean, &self.datetimes);
         self.columns.push(column);
+        Ok(())
     }
 
-    pub fn add_column(&mut self, column: Column) {
+    pub fn add_column(&mut self, mut column: Column) -> Result<()> {
+        self.check_column_name_available(&column.name)?;
+        if column.len() != self.len() {
+            return Err(DataFrameError::ArityMismatch { expected: self.len(), found: column.len() });
+        }
+        // the column's windows are rebuilt against the frame's timestamps
+        column.rebuild_rolling_windows(&self.datetimes);
         self.columns.push(column);
+        Ok(())
     }
 
-    // create a test that generates a weak pointer
-    // run this method and check the weak reference returns None
-    // when upgrading weak pointer
-    // https://doc.rust-lang.org/std/rc/struct.Weak.html#method.upgrade
-    pub fn drop_row(&mut self, row_index: usize) {
-        let mut rows = self.rows.borrow_mut();
-        let row: Vec<RcRow> = rows.drain(row_index..row_index+1).collect();
-        self.update_row_index(&rows);
-        for (column_index, cell) in row[0].borrow().get_cells().iter().enumerate() {
-            let column = &mut self.columns[column_index];
-            column.drop_cell(cell.upgrade().unwrap());
+    pub fn drop_row(&mut self, row_index: usize) -> Result<()> {
+        if row_index >= self.len() {
+            return Err(DataFrameError::IndexOutOfRange { index: row_index, len: self.len() });
+        }
+
+        for column in self.columns.iter_mut() {
+            column.remove(row_index)?;
         }
-        drop(row);
+        self.datetimes.remove(row_index);
+        // the rows up to the lag after the dropped one now look back at a different row, and each
+        // returns column taken from another returns column has that many more rows to correct than its source
+        let len = self.len();
+        let mut last_changed: Vec<Option<usize>> = vec![None; self.columns.len()];
+        for (source, target) in self.returns_pairs() {
+            let lag = self.columns[source].returns.kind.lag();
+            let end = last_changed[source].map_or((row_index + lag).saturating_sub(1), |last| last + lag);
+            for index in (row_index..=end).take_while(|index| *index < len) {
+                let value = self.columns[source].get_returns(index);
+                self.columns[target].set(index, value)?;
+            }
+            last_changed[target] = Some(end);
+        }
+        Ok(())
+    }
+
+    // replaces a single value, returning the one it held
+    pub fn set_value(&mut self, row_index: usize, column_name: &str, value: AnyType) -> Result<AnyType> {
+        let mut previous = self.set_values(vec![(row_index, column_name, value)])?;
+        Ok(previous.remove(0))
     }
 
-    fn update_row_index(&self, rows: &RefMut<Vec<RcRow>>) {
-        for (index, row) in rows.iter().enumerate() {
-            row.borrow_mut().update_index(index);
+    // applies every update or none of them, then brings rolling windows and returns columns up to date,
+    // the values replaced are returned in the order the updates were given
+    pub fn set_values(&mut self, updates: Vec<(usize, &str, AnyType)>) -> Result<Vec<AnyType>> {
+        // each column touched, with the position of its updates in the request and the updates themselves
+        let mut by_column: Vec<(usize, Vec<usize>, ColumnUpdates)> = vec![];
+        for (position, (row_index, column_name, value)) in updates.into_iter().enumerate() {
+            let index = self.get_column_index(column_name)?;
+            if self.is_returns_column(column_name) {
+                return Err(DataFrameError::InvalidArgument(format!("column '{}' is calculated from another column so cannot be set", column_name)));
+            }
+            if row_index >= self.len() {
+                return Err(DataFrameError::IndexOutOfRange { index: row_index, len: self.len() });
+            }
+            match by_column.iter_mut().find(|(column, _, _)| *column == index) {
+                Some((_, positions, column_updates)) => {
+                    positions.push(position);
+                    column_updates.push((row_index, value));
+                },
+                None => by_column.push((index, vec![position], vec![(row_index, value)])),
+            }
+        }
+        // check every column's updates before applying any so a bad value leaves the frame untouched
+        for (index, _, column_updates) in by_column.iter() {
+            self.columns[*index].check_updates(column_updates)?;
         }
+
+        let mut changed: Vec<Vec<usize>> = vec![vec![]; self.columns.len()];
+        let mut previous_values: Vec<(usize, AnyType)> = vec![];
+        for (index, positions, column_updates) in by_column.into_iter() {
+            changed[index] = column_updates.iter().map(|(row_index, _)| *row_index).collect();
+            let previous = self.columns[index].set_many(column_updates)?;
+            previous_values.extend(positions.into_iter().zip(previous));
+        }
+        self.recalculate_returns(changed)?;
+        previous_values.sort_by_key(|(position, _)| *position);
+        Ok(previous_values.into_iter().map(|(_, value)| value).collect())
     }
 
-    pub fn drop_column(&mut self, column_index: usize) {
-        let column: Vec<Column> = self.columns.drain(column_index..column_index+1).collect();
-        for cell in column[0].get_cells().iter() {
-            let row = cell.borrow().clone_row();
-            row.borrow_mut().drop_cell(cell);
+    // recalculates the returns rows which look at any of the changed rows, following returns columns
+    // that are themselves the source of other returns
+    fn recalculate_returns(&mut self, mut changed: Vec<Vec<usize>>) -> Result<()> {
+        let len = self.len();
+        for (source, target) in self.returns_pairs() {
+            let lag = self.columns[source].returns.kind.lag();
+            let mut rows: Vec<usize> = changed[source].iter()
+                .flat_map(|row| [*row, row + lag])
+                .filter(|row| *row < len)
+                .collect();
+            rows.sort_unstable();
+            rows.dedup();
+            let updates: Vec<(usize, AnyType)> = rows.iter().map(|row| (*row, self.columns[source].get_returns(*row))).collect();
+            if !updates.is_empty() {
+                self.columns[target].set_many(updates)?;
+            }
+            changed[target] = rows;
         }
-        drop(column);
+        Ok(())
+    }
+
+    pub fn drop_column(&mut self, column_index: usize) -> Result<()> {
+        if column_index >= self.columns.len() {
+            return Err(DataFrameError::IndexOutOfRange { index: column_index, len: self.columns.len() });
+        }
+
+        let column = self.columns.remove(column_index);
+        // stop the source column populating a returns column which no longer exists
+        for source in self.columns.iter_mut().filter(|c| c.returns.column_name.as_ref() == Some(&column.name)) {
+            source.returns = Returns::new(false, None);
+        }
+        Ok(())
+    }
+
+    pub fn drop_column_by_name(&mut self, column_name: &str) -> Result<()> {
+        let index = self.get_column_index(column_name)?;
+        self.drop_column(index)
+    }
+
+    pub fn update_column_rolling_mean(&mut self, column_name: &str, rolling_mean: RollingMean) -> Result<()> {
+        let index = self.get_column_index(column_name)?;
+        self.columns[index].update_rolling_mean(rolling_mean, &self.datetimes);
+        Ok(())
+    }
+
+    pub fn add_rolling_window(&mut self, column_name: &str, window: RollingWindow) -> Result<()> {
+        let index = self.get_column_index(column_name)?;
+        self.columns[index].add_rolling_window(window, &self.datetimes)
+    }
+
+    pub fn remove_rolling_window(&mut self, column_name: &str, window_name: &str) -> Result<RollingWindow> {
+        let column = self.get_mut_column_by_name(column_name)?;
+        column.remove_rolling_window(window_name)
+            .ok_or_else(|| DataFrameError::InvalidArgument(format!("column '{}' has no rolling window named '{}'", column_name, window_name)))
+    }
+
+    pub fn create_returns_for_column<S: Into<Rc<str>>>(&mut self, column_name: &str, new_column_name: S, kind: ReturnsKind, rolling_mean: RollingMean) -> Result<()> {
+        let new_column_name: Rc<str> = new_column_name.into();
+        self.check_column_name_available(&new_column_name)?;
+        if kind.lag() == 0 {
+            return Err(DataFrameError::InvalidArgument(format!("returns for column '{}' must look back at least one row", column_name)));
+        }
+        let column = self.get_mut_column_by_name(column_name)?;
+        // a column feeds a single returns column, which has to be dropped before it can be replaced
+        if let Some(existing) = column.returns.column_name.as_ref().filter(|_| column.returns.should_calculate) {
+            return Err(DataFrameError::InvalidArgument(format!("column '{}' already has returns in '{}'", column_name, existing)));
+        }
+        let dtype = column.dtype();
+        // ratios only make sense for plain numbers, differences also work for temporal values
+        let supported = match kind {
+            ReturnsKind::Difference(_) => dtype.is_numeric() || dtype.is_temporal(),
+            ReturnsKind::Percentage(_) | ReturnsKind::Log(_) => dtype.is_numeric(),
+        };
+        if dtype != DataType::Null && !supported {
+            return Err(DataFrameError::TypeMismatch {
+                column: column_name.to_string(),
+                expected: DataType::Float64,
+                found: dtype,
+            });
+        }
+        let values: Option<Vec<AnyType>> = column.update_returns(Returns::new(true, Some(&new_column_name)).with_kind(kind));
+        self.add_column_from_values(new_column_name, values.unwrap_or_default(), rolling_mean)
+    }
+
+    pub fn get_column_by_name(&self, column_name: &str) -> Result<&Column> {
+        self.columns.iter().find(|c| &*c.name == column_name)
+            .ok_or_else(|| DataFrameError::UnknownColumn(column_name.to_string()))
     }
 
-    pub fn drop_column_by_name(&mut self, column_name: &str) {
-        let index = self.columns.iter().position(|c| c.name == column_name).unwrap();
-        self.drop_column(index);
+    fn get_column_index(&self, column_name: &str) -> Result<usize> {
+        self.columns.iter().position(|c| &*c.name == column_name)
+            .ok_or_else(|| DataFrameError::UnknownColumn(column_name.to_string()))
     }
 
-    pub fn update_column_rolling_mean(&mut self, column_name: &'static str, rolling_mean: RollingMean) {
-        let column = self.get_mut_column_by_name(column_name);
-        column.update_rolling_mean(rolling_mean);
+    pub(crate) fn get_mut_column_by_name(&mut self, column_name: &str) -> Result<&mut Column> {
+        self.columns.iter_mut().find(|c| &*c.name == column_name)
+            .ok_or_else(|| DataFrameError::UnknownColumn(column_name.to_string()))
     }
 
-    pub fn create_returns_for_column(&mut self, column_name: &'static str, new_column_name: &'static str, rolling_mean: RollingMean) {
-        let column = self.get_mut_column_by_name(column_name);
-        let values: Option<Vec<AnyType>> = column.update_returns(Returns::new(true, Some(new_column_name)));
-        self.add_column_from_values(new_column_name, values.unwrap(), rolling_mean);
+    fn check_column_name_available(&self, column_name: &str) -> Result<()> {
+        if self.columns.iter().any(|c| &*c.name == column_name) {
+            return Err(DataFrameError::DuplicateColumn(column_name.to_string()));
+        }
+        Ok(())
     }
 
-    fn get_mut_column_by_name(&mut self, column_name: &'static str) -> &mut Column {
-        self.columns.iter_mut().find(|c| c.name == column_name).unwrap()
+    fn is_returns_column(&self, column_name: &str) -> bool {
+        self.columns.iter().any(|c| c.returns.should_calculate && c.returns.column_name.as_deref() == Some(column_name))
     }
 
-    fn get_column_by_name(&self, column_name: &'static str) -> &Column {
-        self.columns.iter().find(|c| c.name == column_name).unwrap()
+    pub fn add_returns_for_cells(&mut self, row_index: usize) -> Result<()> {
+        let datetime = self.get_datetime(row_index)?;
+        for (source, target) in self.returns_pairs() {
+            let value = self.columns[source].get_returns(row_index);
+            self.columns[target].push_at(value, datetime)?;
+        }
+        Ok(())
     }
 
-    pub fn add_returns_for_cells(&mut self, row_index: usize, row: &RcRow) {
-        let mut iterator =  self.columns.iter_mut();
-        for column in iterator.find(|c| c.returns.should_calculate) {
-            let value = column.get_difference_to_last(row_index);
-            let returns_column = iterator.find(|c| Some(c.name) == column.returns.column_name).unwrap();
-            let cell = Cell::new(value, row, returns_column.name.clone());
-            returns_column.add_cell(&cell);
-            row.borrow_mut().add_cell(&cell);
+    // indices of each source column and its returns column, ordered so that a returns column
+    // which is itself the source of another is filled in before its own returns are taken
+    fn returns_pairs(&self) -> Vec<(usize, usize)> {
+        let mut pairs: Vec<(usize, usize)> = self.columns.iter().enumerate()
+            .filter(|(_, column)| column.returns.should_calculate)
+            .filter_map(|(source, column)| {
+                let target = self.columns.iter().position(|c| Some(&c.name) == column.returns.column_name.as_ref())?;
+                Some((source, target))
+            })
+            .collect();
+        let mut ordered = Vec::with_capacity(pairs.len());
+        while !pairs.is_empty() {
+            let next = pairs.iter().position(|(source, _)| !pairs.iter().any(|(_, target)| target == source)).unwrap_or(0);
+            ordered.push(pairs.remove(next));
         }
+        ordered
     }
 
-    pub fn get_column_values_with_datetime<T>(&self, column_name: &'static str) -> Vec<(DateTime<Utc>, T)>
+    pub fn get_column_values_with_datetime<T>(&self, column_name: &str) -> Result<Vec<(DateTime<Utc>, T)>>
     where Option<T>: From<AnyType> {
-        let column = self.get_column_by_name(column_name);
-        column.get_values_as_vec_with_datetime()
+        let column = self.get_column_by_name(column_name)?;
+        Ok(column.get_values_as_vec_with_datetime(&self.datetimes))
     }
 
-    pub fn get_column_values_with_unix_datetime<T>(&self, column_name: &'static str) -> Vec<(i64, T)>
+    pub fn get_column_values_with_unix_datetime<T>(&self, column_name: &str) -> Result<Vec<(i64, T)>>
     where Option<T>: From<AnyType> {
-        let column = self.get_column_by_name(column_name);
-        column.get_values_as_vec_with_unix_datetime()
+        let column = self.get_column_by_name(column_name)?;
+        Ok(column.get_values_as_vec_with_unix_datetime(&self.datetimes))
     }
 
-    pub fn get_rolling_means_as_vec_with_unix_datetime_diff<T>(&self, column_name: &'static str, rate_of_change_over: usize) -> (Vec<(f64, f64)>, Vec<(f64, f64)>)
+    pub fn get_rolling_means_as_vec_with_unix_datetime_diff<T>(&self, column_name: &str, rate_of_change_over: usize) -> Result<RateOfChange>
     where Option<T>: From<AnyType>,
     T: Into<f64> {
-        let column = self.get_column_by_name(column_name);
-        column.rate_of_change_rolling_means_over_x_elements(rate_of_change_over)
+        let column = self.get_column_by_name(column_name)?;
+        Ok(column.rate_of_change_rolling_means_over_x_elements::<T>(&self.datetimes, rate_of_change_over))
     }
 }
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::rc::{ Rc };
+    use crate::column::rolling::RollingAggregation;
+    use filter::Predicate;
+    use group_by::Aggregation;
+    use join::{
+        AsofDirection,
+        JoinType,
+    };
+
     #[test]
     fn drop_row() {
         let columns = vec![
             "rando",
             "second"
         ];
-        let mut dataframe = DataFrame::new(columns);
+        let mut dataframe = DataFrame::new(columns).unwrap();
         let cell_values: Vec<AnyType> = vec![
             6.into(),
             "whoop".into()
         ];
-        dataframe.add_row(cell_values);
+        dataframe.add_row(cell_values).unwrap();
         let cell_values2: Vec<AnyType> = vec![
             7.into(),
             "whoop".into()
         ];
-        dataframe.add_row(cell_values2);
-        let cell_ref = Rc::downgrade(&dataframe.get_columns()[0].get_cells()[0]);
-        dataframe.drop_row(0);
+        dataframe.add_row(cell_values2).unwrap();
+        dataframe.drop_row(0).unwrap();
 
-        assert!(cell_ref.upgrade().is_none());
-        assert_eq!(dataframe.get_rows().len(), 1);
-        assert_eq!(dataframe.get_rows()[0].borrow().index, 0);
-        assert_eq!(dataframe.get_columns()[0].get_cells().len(), 1);
+        assert!(dataframe.get_columns()[0].get_value(0) == Some(7.into()));
+        assert_eq!(dataframe.len(), 1);
+        assert_eq!(dataframe.get_row(0).unwrap().index, 0);
+        assert_eq!(dataframe.get_columns()[0].len(), 1);
     }
 
     #[test]
@@ -200,24 +640,21 @@ mod tests {
             "rando",
             "second"
         ];
-        let mut dataframe = DataFrame::new(columns);
+        let mut dataframe = DataFrame::new(columns).unwrap();
         let cell_values: Vec<AnyType> = vec![
             6.into(),
             "whoop".into()
         ];
-        dataframe.add_row(cell_values);
+        dataframe.add_row(cell_values).unwrap();
         let cell_values2: Vec<AnyType> = vec![
             7.into(),
             "whoop".into()
         ];
-        dataframe.add_row(cell_values2);
-        let cell_ref = Rc::downgrade(&dataframe.get_columns()[1].get_cells()[0]);
-        let cell_ref2 = Rc::downgrade(&dataframe.get_columns()[1].get_cells()[1]);
-        dataframe.drop_column(1);
+        dataframe.add_row(cell_values2).unwrap();
+        dataframe.drop_column(1).unwrap();
 
-        assert!(cell_ref.upgrade().is_none());
-        assert!(cell_ref2.upgrade().is_none());
-        assert_eq!(dataframe.get_rows().len(), 2);
+        assert_eq!(dataframe.get_row(0).unwrap().get_cells().len(), 1);
+        assert_eq!(dataframe.len(), 2);
         assert_eq!(dataframe.get_columns().len(), 1);
     }
 
@@ -227,19 +664,19 @@ mod tests {
             "rando",
             "second"
         ];
-        let mut dataframe = DataFrame::new(columns);
-        dataframe.create_returns_for_column("rando", "rando_returns", RollingMean::new(true, Some(2)));
+        let mut dataframe = DataFrame::new(columns).unwrap();
+        dataframe.create_returns_for_column("rando", "rando_returns", ReturnsKind::Difference(1), RollingMean::new(true, Some(2))).unwrap();
         let cell_values: Vec<AnyType> = vec![
             6u8.into(),
             "whoop".into()
         ];
-        dataframe.add_row(cell_values);
+        dataframe.add_row(cell_values).unwrap();
 
         assert_eq!(dataframe.get_columns().len(), 3);
-        assert_eq!(dataframe.get_columns()[0].returns.should_calculate, true);
-        assert_eq!(dataframe.get_columns()[0].returns.column_name, Some("rando_returns"));
-        assert_eq!(dataframe.get_rows()[0].borrow().get_cells().len(), 3);
-        assert_eq!(dataframe.get_rows()[0].borrow().get_cells()[2].upgrade().unwrap().borrow().get_value(), &AnyType::Null);
+        assert!(dataframe.get_columns()[0].returns.should_calculate);
+        assert_eq!(dataframe.get_columns()[0].returns.column_name.as_deref(), Some("rando_returns"));
+        assert_eq!(dataframe.get_row(0).unwrap().get_cells().len(), 3);
+        assert_eq!(dataframe.get_row(0).unwrap().get_cells()[2].get_value(), &AnyType::Null);
     }
 
     #[test]
@@ -248,39 +685,425 @@ mod tests {
             "rando",
             "second"
         ];
-        let mut dataframe = DataFrame::new(columns);
-        dataframe.create_returns_for_column("rando", "rando_returns", RollingMean::new(true, Some(2)));
+        let mut dataframe = DataFrame::new(columns).unwrap();
+        dataframe.create_returns_for_column("rando", "rando_returns", ReturnsKind::Difference(1), RollingMean::new(true, Some(2))).unwrap();
         let cell_values: Vec<AnyType> = vec![
             6.into(),
             "whoop".into()
         ];
-        dataframe.add_row(cell_values);
+        dataframe.add_row(cell_values).unwrap();
         let cell_values2: Vec<AnyType> = vec![
             7.into(),
             "whoop".into()
         ];
-        dataframe.add_row(cell_values2);
+        dataframe.add_row(cell_values2).unwrap();
         let cell_values3: Vec<AnyType> = vec![
             8.into(),
             "whoop".into()
         ];
-        dataframe.add_row(cell_values3);
+        dataframe.add_row(cell_values3).unwrap();
         let cell_values4: Vec<AnyType> = vec![
             11.into(),
             "whoop".into()
         ];
-        dataframe.add_row(cell_values4);
+        dataframe.add_row(cell_values4).unwrap();
         let cell_values5: Vec<AnyType> = vec![
             1.into(),
             "whoop".into()
         ];
-        dataframe.add_row(cell_values5);
+        dataframe.add_row(cell_values5).unwrap();
         assert!(dataframe.get_columns().len() == 3);
-        assert!(dataframe.get_rows().len() == 5);
-        assert!(dataframe.get_rows()[0].borrow().get_cells().len() == 3);
-        assert_eq!(dataframe.get_rows()[2].borrow().get_cells()[2].upgrade().unwrap().borrow().get_value(), &1isize.into());
-        assert_eq!(dataframe.get_rows()[3].borrow().get_cells()[2].upgrade().unwrap().borrow().get_value(), &3isize.into());
-        assert_eq!(dataframe.get_rows()[4].borrow().get_cells()[2].upgrade().unwrap().borrow().get_value(), &(-10isize).into());
+        assert!(dataframe.len() == 5);
+        assert!(dataframe.get_row(0).unwrap().get_cells().len() == 3);
+        assert_eq!(dataframe.get_row(2).unwrap().get_cells()[2].get_value(), &1isize.into());
+        assert_eq!(dataframe.get_row(3).unwrap().get_cells()[2].get_value(), &3isize.into());
+        assert_eq!(dataframe.get_row(4).unwrap().get_cells()[2].get_value(), &(-10isize).into());
+    }
+
+    #[test]
+    fn multiple_returns_columns() {
+        let mut dataframe = DataFrame::new(vec!["first", "second"]).unwrap();
+        dataframe.create_returns_for_column("second", "second_returns", ReturnsKind::Difference(1), RollingMean::new(false, None)).unwrap();
+        dataframe.create_returns_for_column("first", "first_returns", ReturnsKind::Difference(1), RollingMean::new(false, None)).unwrap();
+        dataframe.create_returns_for_column("second_returns", "second_acceleration", ReturnsKind::Difference(1), RollingMean::new(false, None)).unwrap();
+        for (first, second) in [(1i64, 10i64), (3, 20), (6, 40), (10, 50)] {
+            dataframe.add_row(vec![first.into(), second.into()]).unwrap();
+        }
+        let values = |dataframe: &DataFrame, name: &str| dataframe.get_column_by_name(name).unwrap().iter_values().collect::<Vec<AnyType>>();
+
+        assert_eq!(values(&dataframe, "first_returns"), vec![AnyType::Null, 2isize.into(), 3isize.into(), 4isize.into()]);
+        assert_eq!(values(&dataframe, "second_returns"), vec![AnyType::Null, 10isize.into(), 20isize.into(), 10isize.into()]);
+        assert_eq!(values(&dataframe, "second_acceleration"), vec![AnyType::Null, AnyType::Null, 10isize.into(), (-10isize).into()]);
+
+        dataframe.drop_row(2).unwrap();
+
+        assert_eq!(values(&dataframe, "first_returns"), vec![AnyType::Null, 2isize.into(), 7isize.into()]);
+        assert_eq!(values(&dataframe, "second_returns"), vec![AnyType::Null, 10isize.into(), 30isize.into()]);
+        assert_eq!(values(&dataframe, "second_acceleration"), vec![AnyType::Null, AnyType::Null, 20isize.into()]);
+
+        dataframe.drop_row(0).unwrap();
+
+        assert_eq!(values(&dataframe, "first_returns"), vec![AnyType::Null, 7isize.into()]);
+        assert_eq!(values(&dataframe, "second_acceleration"), vec![AnyType::Null, AnyType::Null]);
+
+        dataframe.drop_row(1).unwrap();
+        dataframe.drop_row(0).unwrap();
+
+        assert!(dataframe.is_empty());
+        assert!(values(&dataframe, "second_acceleration").is_empty());
+    }
+
+    #[test]
+    fn returns_kinds() {
+        let mut dataframe = DataFrame::new(vec!["first", "second", "third"]).unwrap();
+        dataframe.create_returns_for_column("first", "percentage", ReturnsKind::Percentage(1), RollingMean::new(false, None)).unwrap();
+        dataframe.create_returns_for_column("second", "log", ReturnsKind::Log(1), RollingMean::new(false, None)).unwrap();
+        dataframe.create_returns_for_column("third", "lagged", ReturnsKind::Difference(2), RollingMean::new(false, None)).unwrap();
+        for price in [100u32, 110, 99, 0, 50] {
+            dataframe.add_row(vec![price.into(), price.into(), price.into()]).unwrap();
+        }
+        let values = |dataframe: &DataFrame, name: &str| dataframe.get_column_by_name(name).unwrap().iter_values().collect::<Vec<AnyType>>();
+
+        assert_eq!(values(&dataframe, "percentage")[1], AnyType::Float64(110f64 / 100f64 - 1f64));
+        assert_eq!(values(&dataframe, "percentage")[2], AnyType::Float64(99f64 / 110f64 - 1f64));
+        // nothing to compare against once the previous price is zero
+        assert_eq!(values(&dataframe, "percentage")[4], AnyType::Null);
+        assert_eq!(values(&dataframe, "log")[1], AnyType::Float64(1.1f64.ln()));
+        assert_eq!(values(&dataframe, "log")[3], AnyType::Null);
+        assert_eq!(values(&dataframe, "lagged"), vec![AnyType::Null, AnyType::Null, (-1isize).into(), (-110isize).into(), (-49isize).into()]);
+
+        dataframe.drop_row(1).unwrap();
+
+        assert_eq!(values(&dataframe, "lagged"), vec![AnyType::Null, AnyType::Null, (-100isize).into(), (-49isize).into()]);
+        assert_eq!(values(&dataframe, "percentage")[1], AnyType::Float64(99f64 / 100f64 - 1f64));
+        assert_eq!(
+            dataframe.create_returns_for_column("first", "again", ReturnsKind::Log(1), RollingMean::new(false, None)),
+            Err(DataFrameError::InvalidArgument("column 'first' already has returns in 'percentage'".to_string()))
+        );
+    }
+
+    #[test]
+    fn returns_overflow() {
+        let mut dataframe = DataFrame::new(vec!["signed", "unsigned"]).unwrap();
+        dataframe.create_returns_for_column("signed", "signed_returns", ReturnsKind::Difference(1), RollingMean::new(false, None)).unwrap();
+        dataframe.create_returns_for_column("unsigned", "unsigned_returns", ReturnsKind::Difference(1), RollingMean::new(false, None)).unwrap();
+        for (signed, unsigned) in [(i64::MAX, 0u64), (i64::MIN, u64::MAX), (i64::MIN + 1, u64::MAX - 1)] {
+            dataframe.add_row(vec![signed.into(), unsigned.into()]).unwrap();
+        }
+        let values = |dataframe: &DataFrame, name: &str| dataframe.get_column_by_name(name).unwrap().iter_values().collect::<Vec<AnyType>>();
+
+        // differences that do not fit in an isize are null rather than wrapping or panicking
+        assert_eq!(values(&dataframe, "signed_returns"), vec![AnyType::Null, AnyType::Null, 1isize.into()]);
+        assert_eq!(values(&dataframe, "unsigned_returns"), vec![AnyType::Null, AnyType::Null, (-1isize).into()]);
+    }
+
+    #[test]
+    fn returns_kind_needs_numbers() {
+        use chrono::TimeZone;
+        let mut dataframe = DataFrame::new(vec!["observed"]).unwrap();
+        dataframe.add_row(vec![Utc.with_ymd_and_hms(2021, 6, 1, 12, 0, 0).unwrap().into()]).unwrap();
+
+        assert_eq!(
+            dataframe.create_returns_for_column("observed", "observed_returns", ReturnsKind::Log(1), RollingMean::new(false, None)),
+            Err(DataFrameError::TypeMismatch { column: "observed".to_string(), expected: DataType::Float64, found: DataType::Datetime })
+        );
+        assert!(dataframe.create_returns_for_column("observed", "observed_returns", ReturnsKind::Difference(0), RollingMean::new(false, None)).is_err());
+        assert!(!dataframe.get_columns()[0].returns.should_calculate);
+    }
+
+    #[test]
+    fn set_value() {
+        let mut dataframe = DataFrame::new(vec!["price", "venue"]).unwrap();
+        dataframe.update_column_rolling_mean("price", RollingMean::new(true, Some(2))).unwrap();
+        dataframe.create_returns_for_column("price", "price_returns", ReturnsKind::Difference(1), RollingMean::new(false, None)).unwrap();
+        dataframe.create_returns_for_column("price_returns", "price_acceleration", ReturnsKind::Difference(1), RollingMean::new(false, None)).unwrap();
+        for (price, venue) in [(10i64, "lse"), (12, "lse"), (15, "nyse"), (11, "nyse")] {
+            dataframe.add_row(vec![price.into(), venue.into()]).unwrap();
+        }
+        let values = |dataframe: &DataFrame, name: &str| dataframe.get_column_by_name(name).unwrap().iter_values().collect::<Vec<AnyType>>();
+
+        assert_eq!(dataframe.set_value(1, "price", 20i64.into()), Ok(12i64.into()));
+        assert_eq!(dataframe.set_value(2, "venue", "lse".into()), Ok("nyse".into()));
+
+        let price = dataframe.get_column_by_name("price").unwrap();
+        assert_eq!(price.get_rolling_mean(1), Some(15i64.into()));
+        assert_eq!(price.get_rolling_mean(2), Some(17i64.into()));
+        assert_eq!(price.get_rolling_mean(3), Some(13i64.into()));
+        assert_eq!(dataframe.get_column_by_name("venue").unwrap().get_grouped_values("lse".into()), Some(&vec![0, 1, 2]));
+        assert_eq!(values(&dataframe, "price_returns"), vec![AnyType::Null, 10isize.into(), (-5isize).into(), (-4isize).into()]);
+        assert_eq!(values(&dataframe, "price_acceleration"), vec![AnyType::Null, AnyType::Null, (-15isize).into(), 1isize.into()]);
+    }
+
+    #[test]
+    fn set_values_is_all_or_nothing() {
+        let mut dataframe = DataFrame::new(vec!["price", "venue"]).unwrap();
+        dataframe.create_returns_for_column("price", "price_returns", ReturnsKind::Difference(1), RollingMean::new(false, None)).unwrap();
+        dataframe.add_row(vec![10i64.into(), "lse".into()]).unwrap();
+        dataframe.add_row(vec![12i64.into(), "lse".into()]).unwrap();
+
+        assert_eq!(
+            dataframe.set_values(vec![(0, "price", 11i64.into()), (1, "venue", 5i64.into())]),
+            Err(DataFrameError::TypeMismatch { column: "venue".to_string(), expected: DataType::Utf8, found: DataType::Int64 })
+        );
+        assert_eq!(dataframe.get_column_by_name("price").unwrap().get_value(0), Some(10i64.into()));
+        assert_eq!(dataframe.set_value(5, "price", 11i64.into()), Err(DataFrameError::IndexOutOfRange { index: 5, len: 2 }));
+        assert!(dataframe.set_value(1, "price_returns", 1isize.into()).is_err());
+
+        assert_eq!(
+            dataframe.set_values(vec![(1, "price", 15i64.into()), (0, "venue", "nyse".into()), (0, "price", 11i64.into())]),
+            Ok(vec![12i64.into(), "lse".into(), 10i64.into()])
+        );
+        assert_eq!(dataframe.get_column_by_name("price_returns").unwrap().get_value(1), Some(4isize.into()));
+    }
+
+    #[test]
+    fn filter() {
+        let mut dataframe = DataFrame::new(vec!["price", "venue"]).unwrap();
+        dataframe.create_returns_for_column("price", "price_returns", ReturnsKind::Difference(1), RollingMean::new(false, None)).unwrap();
+        for (price, venue) in [(10i64, Some("lse")), (12, Some("nyse")), (15, None), (11, Some("lse")), (20, Some("cboe"))] {
+            dataframe.add_row(vec![price.into(), venue.into()]).unwrap();
+        }
+        let prices = |dataframe: &DataFrame| dataframe.get_column_by_name("price").unwrap().iter_values().collect::<Vec<AnyType>>();
+
+        let lse = dataframe.filter(&Predicate::eq("venue", "lse")).unwrap();
+        assert_eq!(prices(&lse), vec![10i64.into(), 11i64.into()]);
+        assert_eq!(lse.get_datetimes(), &vec![dataframe.get_datetimes()[0], dataframe.get_datetimes()[3]]);
+        // the returns are those of the original rows rather than between the rows kept
+        assert_eq!(lse.get_column_by_name("price_returns").unwrap().get_value(1), Some((-4isize).into()));
+
+        let combined = Predicate::gt("price", 10i64).and(Predicate::is_in("venue", vec!["lse".into(), "cboe".into()]).or(Predicate::is_null("venue")));
+        assert_eq!(prices(&dataframe.filter(&combined).unwrap()), vec![15i64.into(), 11i64.into(), 20i64.into()]);
+        assert_eq!(prices(&dataframe.filter(&!Predicate::lt_eq("price", 12i64)).unwrap()), vec![15i64.into(), 20i64.into()]);
+        assert_eq!(prices(&dataframe.filter(&Predicate::not_eq("venue", "lse")).unwrap()), vec![12i64.into(), 20i64.into()]);
+        assert_eq!(prices(&dataframe.filter(&Predicate::is_not_null("venue").and(Predicate::gt_eq("price", 12i64))).unwrap()), vec![12i64.into(), 20i64.into()]);
+        assert!(dataframe.filter(&Predicate::eq("venue", "asx")).unwrap().is_empty());
+
+        assert_eq!(
+            dataframe.filter(&Predicate::lt("price", 10i32)).unwrap_err(),
+            DataFrameError::TypeMismatch { column: "price".to_string(), expected: DataType::Int64, found: DataType::Int32 }
+        );
+        assert_eq!(dataframe.filter(&Predicate::is_null("missing")).unwrap_err(), DataFrameError::UnknownColumn("missing".to_string()));
+
+        // NaN never matches, whether looked up by equality or compared
+        let mut readings = DataFrame::new(vec!["reading"]).unwrap();
+        for reading in [1.5f64, f64::NAN, 2.0] {
+            readings.add_row(vec![reading.into()]).unwrap();
+        }
+        assert!(readings.filter(&Predicate::eq("reading", f64::NAN)).unwrap().is_empty());
+        assert_eq!(readings.filter(&Predicate::is_in("reading", vec![f64::NAN.into(), 2f64.into()])).unwrap().len(), 1);
+        assert_eq!(readings.filter(&Predicate::gt_eq("reading", 1.5f64)).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn group_by() {
+        let mut dataframe = DataFrame::new(vec!["venue", "side", "price"]).unwrap();
+        let rows = [(Some("lse"), "buy", Some(10i64)), (None, "sell", Some(7)), (Some("cboe"), "buy", Some(4)), (Some("lse"), "sell", None), (Some("lse"), "buy", Some(14)), (None, "sell", Some(7))];
+        for (venue, side, price) in rows {
+            dataframe.add_row(vec![venue.into(), side.into(), price.into()]).unwrap();
+        }
+        let values = |dataframe: &DataFrame, name: &str| dataframe.get_column_by_name(name).unwrap().iter_values().collect::<Vec<AnyType>>();
+
+        let grouped = dataframe.group_by(&["venue"]).unwrap().agg([
+            Aggregation::count("price"),
+            Aggregation::sum("price"),
+            Aggregation::mean("price").alias("average"),
+            Aggregation::min("price"),
+            Aggregation::max("price"),
+            Aggregation::first("price"),
+            Aggregation::last("price"),
+            Aggregation::std("price"),
+            Aggregation::n_unique("price"),
+        ]).unwrap();
+        // groups come out in the order they first appear with the null key as a group of its own
+        assert_eq!(values(&grouped, "venue"), vec!["lse".into(), AnyType::Null, "cboe".into()]);
+        assert_eq!(grouped.get_datetimes(), &vec![dataframe.get_datetimes()[0], dataframe.get_datetimes()[1], dataframe.get_datetimes()[2]]);
+        assert_eq!(values(&grouped, "price_count"), vec![AnyType::USize(2), AnyType::USize(2), AnyType::USize(1)]);
+        assert_eq!(values(&grouped, "price_sum"), vec![24i64.into(), 14i64.into(), 4i64.into()]);
+        assert_eq!(values(&grouped, "average"), vec![12f64.into(), 7f64.into(), 4f64.into()]);
+        assert_eq!(values(&grouped, "price_min"), vec![10i64.into(), 7i64.into(), 4i64.into()]);
+        assert_eq!(values(&grouped, "price_max"), vec![14i64.into(), 7i64.into(), 4i64.into()]);
+        assert_eq!(values(&grouped, "price_first"), vec![10i64.into(), 7i64.into(), 4i64.into()]);
+        assert_eq!(values(&grouped, "price_last"), vec![14i64.into(), 7i64.into(), 4i64.into()]);
+        assert_eq!(values(&grouped, "price_std"), vec![8f64.sqrt().into(), 0f64.into(), AnyType::Null]);
+        assert_eq!(values(&grouped, "price_n_unique"), vec![AnyType::USize(3), AnyType::USize(1), AnyType::USize(1)]);
+
+        let grouped = dataframe.group_by(&["venue", "side"]).unwrap().agg([Aggregation::count("price"), Aggregation::n_unique("side"), Aggregation::max("price")]).unwrap();
+        assert_eq!(values(&grouped, "venue"), vec!["lse".into(), AnyType::Null, "cboe".into(), "lse".into()]);
+        assert_eq!(values(&grouped, "side"), vec!["buy".into(), "sell".into(), "buy".into(), "sell".into()]);
+        assert_eq!(values(&grouped, "price_count"), vec![AnyType::USize(2), AnyType::USize(2), AnyType::USize(1), AnyType::USize(0)]);
+        assert_eq!(values(&grouped, "side_n_unique"), vec![AnyType::USize(1), AnyType::USize(1), AnyType::USize(1), AnyType::USize(1)]);
+        assert_eq!(values(&grouped, "price_max"), vec![14i64.into(), 7i64.into(), 4i64.into(), AnyType::Null]);
+
+        assert_eq!(dataframe.group_by(&["missing"]).unwrap_err(), DataFrameError::UnknownColumn("missing".to_string()));
+        assert_eq!(
+            dataframe.group_by(&["venue"]).unwrap().agg([Aggregation::sum("side")]).unwrap_err(),
+            DataFrameError::UnsupportedDtype { column: "side".to_string(), operation: "sum", dtype: DataType::Utf8 }
+        );
+        assert_eq!(
+            dataframe.group_by(&["venue"]).unwrap().agg([Aggregation::sum("price"), Aggregation::max("price").alias("price_sum")]).unwrap_err(),
+            DataFrameError::DuplicateColumn("price_sum".to_string())
+        );
+    }
+
+    #[test]
+    fn join() {
+        let mut prices = DataFrame::new(vec!["ticker", "venue", "price"]).unwrap();
+        for (ticker, venue, price) in [(Some("vod"), "lse", 70i64), (Some("aapl"), "nyse", 180), (None, "lse", 5), (Some("msft"), "nyse", 410), (Some("vod"), "nyse", 71)] {
+            prices.add_row(vec![ticker.into(), venue.into(), price.into()]).unwrap();
+        }
+        let mut reference = DataFrame::new(vec!["symbol", "venue", "sector"]).unwrap();
+        for (symbol, venue, sector) in [(Some("aapl"), "nyse", "tech"), (Some("vod"), "lse", "telecoms"), (None, "lse", "unknown"), (Some("ibm"), "nyse", "tech"), (Some("vod"), "lse", "mobile")] {
+            reference.add_row(vec![symbol.into(), venue.into(), sector.into()]).unwrap();
+        }
+        let values = |dataframe: &DataFrame, name: &str| dataframe.get_column_by_name(name).unwrap().iter_values().collect::<Vec<AnyType>>();
+        let tickers = |dataframe: &DataFrame| values(dataframe, "ticker");
+
+        let inner = prices.join(&reference, &["ticker"], &["symbol"], JoinType::Inner).unwrap();
+        // null keys never match and a left row is repeated for each of its matches
+        assert_eq!(tickers(&inner), vec!["vod".into(), "vod".into(), "aapl".into(), "vod".into(), "vod".into()]);
+        assert_eq!(values(&inner, "sector"), vec!["telecoms".into(), "mobile".into(), "tech".into(), "telecoms".into(), "mobile".into()]);
+        assert_eq!(values(&inner, "venue_right"), vec!["lse".into(), "lse".into(), "nyse".into(), "lse".into(), "lse".into()]);
+        assert!(inner.get_column_by_name("symbol").is_err());
+        assert_eq!(inner.get_datetimes()[2], prices.get_datetimes()[1]);
+
+        let multi = prices.join(&reference, &["ticker", "venue"], &["symbol", "venue"], JoinType::Inner).unwrap();
+        assert_eq!(tickers(&multi), vec!["vod".into(), "vod".into(), "aapl".into()]);
+        assert_eq!(multi.get_columns().iter().map(|column| column.name.to_string()).collect::<Vec<String>>(), vec!["ticker", "venue", "price", "sector"]);
+
+        let left = prices.join(&reference, &["ticker", "venue"], &["symbol", "venue"], JoinType::Left).unwrap();
+        assert_eq!(tickers(&left), vec!["vod".into(), "vod".into(), "aapl".into(), AnyType::Null, "msft".into(), "vod".into()]);
+        assert_eq!(values(&left, "sector"), vec!["telecoms".into(), "mobile".into(), "tech".into(), AnyType::Null, AnyType::Null, AnyType::Null]);
+
+        let right = prices.join(&reference, &["ticker"], &["symbol"], JoinType::Right).unwrap();
+        // unmatched right rows take their key from the right frame and their time from it too
+        assert_eq!(tickers(&right), vec!["aapl".into(), "vod".into(), "vod".into(), AnyType::Null, "ibm".into(), "vod".into(), "vod".into()]);
+        assert_eq!(values(&right, "price"), vec![180i64.into(), 70i64.into(), 71i64.into(), AnyType::Null, AnyType::Null, 70i64.into(), 71i64.into()]);
+        assert_eq!(right.get_datetimes()[4], reference.get_datetimes()[3]);
+
+        let outer = prices.join_with_suffix(&reference, &["ticker", "venue"], &["symbol", "venue"], JoinType::Outer, "_ref").unwrap();
+        assert_eq!(tickers(&outer), vec!["vod".into(), "vod".into(), "aapl".into(), AnyType::Null, "msft".into(), "vod".into(), AnyType::Null, "ibm".into()]);
+        assert_eq!(values(&outer, "venue"), vec!["lse".into(), "lse".into(), "nyse".into(), "lse".into(), "nyse".into(), "nyse".into(), "lse".into(), "nyse".into()]);
+        assert_eq!(values(&outer, "sector"), vec!["telecoms".into(), "mobile".into(), "tech".into(), AnyType::Null, AnyType::Null, AnyType::Null, "unknown".into(), "tech".into()]);
+
+        let semi = prices.join(&reference, &["ticker"], &["symbol"], JoinType::Semi).unwrap();
+        assert_eq!(tickers(&semi), vec!["vod".into(), "aapl".into(), "vod".into()]);
+        assert_eq!(semi.get_columns().len(), 3);
+        let anti = prices.join(&reference, &["ticker"], &["symbol"], JoinType::Anti).unwrap();
+        assert_eq!(tickers(&anti), vec![AnyType::Null, "msft".into()]);
+
+        // a left key that does not allow nulls still takes the nulls of unmatched right keys
+        use crate::schema::Field;
+        let mut listed = DataFrame::with_schema(Schema::new(vec![Field::new("ticker", DataType::Utf8, false), Field::new("price", DataType::Int64, true)])).unwrap();
+        listed.add_row(vec!["vod".into(), 70i64.into()]).unwrap();
+        for how in [JoinType::Right, JoinType::Outer] {
+            let joined = listed.join(&reference, &["ticker"], &["symbol"], how).unwrap();
+            assert!(joined.get_column_by_name("ticker").unwrap().is_nullable());
+            assert!(tickers(&joined).contains(&AnyType::Null));
+        }
+        let inner = listed.join(&reference, &["ticker"], &["symbol"], JoinType::Inner).unwrap();
+        assert!(!inner.get_column_by_name("ticker").unwrap().is_nullable());
+
+        assert_eq!(prices.join(&reference, &["ticker"], &[], JoinType::Inner).unwrap_err(), DataFrameError::ArityMismatch { expected: 1, found: 0 });
+        assert_eq!(prices.join(&reference, &["ticker"], &["missing"], JoinType::Inner).unwrap_err(), DataFrameError::UnknownColumn("missing".to_string()));
+        assert_eq!(
+            prices.join(&reference, &["price"], &["symbol"], JoinType::Inner).unwrap_err(),
+            DataFrameError::TypeMismatch { column: "symbol".to_string(), expected: DataType::Int64, found: DataType::Utf8 }
+        );
+    }
+
+    #[test]
+    fn join_asof() {
+        let start = DateTime::parse_from_rfc3339("2024-01-02T08:00:00Z").unwrap().with_timezone(&Utc);
+        let at = |seconds: i64| start + Duration::seconds(seconds);
+        let mut trades = DataFrame::new(vec!["ticker", "price"]).unwrap();
+        for (seconds, ticker, price) in [(10, "vod", 70i64), (20, "aapl", 180), (30, "vod", 71), (50, "vod", 72)] {
+            trades.add_row_at(at(seconds), vec![ticker.into(), price.into()]).unwrap();
+        }
+        // quotes arrive out of order and one shares a time with another
+        let mut quotes = DataFrame::new(vec!["ticker", "bid"]).unwrap();
+        for (seconds, ticker, bid) in [(25, "vod", 70.5f64), (5, "vod", 69.5), (18, "aapl", 179.5), (25, "vod", 70.75), (45, "aapl", 181.0)] {
+            quotes.add_row_at(at(seconds), vec![ticker.into(), bid.into()]).unwrap();
+        }
+        let bids = |dataframe: &DataFrame| dataframe.get_column_by_name("bid").unwrap().iter_values().collect::<Vec<AnyType>>();
+
+        let backward = trades.join_asof(&quotes, AsofDirection::Backward, None).unwrap();
+        assert_eq!(bids(&backward), vec![69.5f64.into(), 179.5f64.into(), 70.75f64.into(), 181.0f64.into()]);
+        assert_eq!(backward.get_datetimes(), trades.get_datetimes());
+        assert_eq!(backward.get_column_by_name("ticker_right").unwrap().get_value(3), Some("aapl".into()));
+
+        let forward = trades.join_asof(&quotes, AsofDirection::Forward, None).unwrap();
+        assert_eq!(bids(&forward), vec![179.5f64.into(), 70.5f64.into(), 181.0f64.into(), AnyType::Null]);
+        let nearest = trades.join_asof(&quotes, AsofDirection::Nearest, None).unwrap();
+        assert_eq!(bids(&nearest), vec![69.5f64.into(), 179.5f64.into(), 70.75f64.into(), 181.0f64.into()]);
+
+        let by = trades.join_asof_by(&quotes, &["ticker"], AsofDirection::Backward, Some(Duration::seconds(10))).unwrap();
+        assert_eq!(bids(&by), vec![69.5f64.into(), 179.5f64.into(), 70.75f64.into(), AnyType::Null]);
+        assert!(by.get_column_by_name("ticker_right").is_err());
+        let by = trades.join_asof_by(&quotes, &["ticker"], AsofDirection::Nearest, None).unwrap();
+        assert_eq!(bids(&by), vec![69.5f64.into(), 179.5f64.into(), 70.75f64.into(), 70.75f64.into()]);
+
+        assert_eq!(
+            trades.join_asof(&quotes, AsofDirection::Backward, Some(Duration::seconds(-1))).unwrap_err(),
+            DataFrameError::InvalidArgument("the tolerance of an as-of join cannot be negative".to_string())
+        );
+        assert_eq!(trades.join_asof_by(&quotes, &["price"], AsofDirection::Backward, None).unwrap_err(), DataFrameError::UnknownColumn("price".to_string()));
+    }
+
+    #[test]
+    fn sort_by() {
+        let mut dataframe = DataFrame::new(vec!["venue", "price"]).unwrap();
+        dataframe.update_column_rolling_mean("price", RollingMean::new(true, Some(2))).unwrap();
+        dataframe.create_returns_for_column("price", "price_returns", ReturnsKind::Difference(1), RollingMean::new(false, None)).unwrap();
+        for (venue, price) in [(Some("lse"), 12i64), (None, 7), (Some("cboe"), 10), (Some("lse"), 9), (Some("cboe"), 10)] {
+            dataframe.add_row(vec![venue.into(), price.into()]).unwrap();
+        }
+        let values = |dataframe: &DataFrame, name: &str| dataframe.get_column_by_name(name).unwrap().iter_values().collect::<Vec<AnyType>>();
+
+        let sorted = dataframe.sort_by(&["venue", "price"], &[false, true], true).unwrap();
+        assert_eq!(values(&sorted, "venue"), vec!["cboe".into(), "cboe".into(), "lse".into(), "lse".into(), AnyType::Null]);
+        assert_eq!(values(&sorted, "price"), vec![10i64.into(), 10i64.into(), 12i64.into(), 9i64.into(), 7i64.into()]);
+        // equal keys keep the order they were added in
+        assert_eq!(sorted.get_datetimes()[0], dataframe.get_datetimes()[2]);
+        assert_eq!(sorted.get_datetimes()[1], dataframe.get_datetimes()[4]);
+        assert_eq!(values(&sorted, "price_returns"), vec![AnyType::Null, 0isize.into(), 2isize.into(), (-3isize).into(), (-2isize).into()]);
+        assert_eq!(sorted.get_column_by_name("price").unwrap().get_rolling_mean(2), Some(11i64.into()));
+        assert_eq!(sorted.get_column_by_name("venue").unwrap().get_grouped_values("lse".into()), Some(&vec![2, 3]));
+        assert_eq!(sorted.get_row(4).unwrap().index, 4);
+
+        let sorted = dataframe.sort_by(&["venue"], &[true], false).unwrap();
+        assert_eq!(values(&sorted, "venue"), vec![AnyType::Null, "lse".into(), "lse".into(), "cboe".into(), "cboe".into()]);
+
+        assert_eq!(dataframe.sort_by(&["venue", "price"], &[true, false, true], false).unwrap_err(), DataFrameError::ArityMismatch { expected: 2, found: 3 });
+        assert_eq!(dataframe.sort_by(&["missing"], &[true], false).unwrap_err(), DataFrameError::UnknownColumn("missing".to_string()));
+    }
+
+    #[test]
+    fn returns_column_before_source() {
+        let mut dataframe = DataFrame::new(vec!["price_returns"]).unwrap();
+        dataframe.add_column(Column::new("price", RollingMean::new(false, None), Returns::new(true, Some("price_returns")))).unwrap();
+        dataframe.add_row(vec![5f64.into()]).unwrap();
+        dataframe.add_row(vec![7.5f64.into()]).unwrap();
+
+        assert_eq!(dataframe.get_column_by_name("price_returns").unwrap().get_value(1), Some(2.5f64.into()));
+    }
+
+    #[test]
+    fn returns_refused() {
+        use crate::schema::Field;
+        let mut dataframe = DataFrame::with_schema(Schema::new(vec![Field::new("price_returns", DataType::Utf8, true)])).unwrap();
+        dataframe.add_column(Column::new("price", RollingMean::new(true, Some(2)), Returns::new(true, Some("price_returns")))).unwrap();
+        dataframe.add_row(vec![5f64.into()]).unwrap();
+
+        // the returns column cannot hold a float, so the row is not added anywhere
+        let error = dataframe.add_row(vec![7.5f64.into()]).unwrap_err();
+        assert_eq!(error, DataFrameError::TypeMismatch { column: "price_returns".to_string(), expected: DataType::Utf8, found: DataType::Float64 });
+        assert_eq!(dataframe.len(), 1);
+        assert_eq!(dataframe.get_datetimes().len(), 1);
+        let price = dataframe.get_column_by_name("price").unwrap();
+        assert_eq!(price.len(), 1);
+        assert_eq!(price.get_grouped_values(7.5f64.into()), None);
+        assert_eq!(price.get_rolling_mean(0), None);
+        assert_eq!(dataframe.get_column_by_name("price_returns").unwrap().len(), 1);
     }
 
     #[test]
@@ -289,39 +1112,66 @@ mod tests {
             "rando",
             "second"
         ];
-        let mut dataframe = DataFrame::new(columns);
-        dataframe.update_column_rolling_mean("rando", RollingMean::new(true, Some(2)));
+        let mut dataframe = DataFrame::new(columns).unwrap();
+        dataframe.update_column_rolling_mean("rando", RollingMean::new(true, Some(2))).unwrap();
         let cell_values: Vec<AnyType> = vec![
             6usize.into(),
             "whoop".into()
         ];
-        dataframe.add_row(cell_values);
+        dataframe.add_row(cell_values).unwrap();
         let cell_values2: Vec<AnyType> = vec![
             7usize.into(),
             "whoop".into()
         ];
-        dataframe.add_row(cell_values2);
+        dataframe.add_row(cell_values2).unwrap();
         let cell_values3: Vec<AnyType> = vec![
             7usize.into(),
             "whoop".into()
         ];
-        dataframe.add_row(cell_values3);
+        dataframe.add_row(cell_values3).unwrap();
         let cell_values4: Vec<AnyType> = vec![
             9usize.into(),
             "whoop".into()
         ];
-        dataframe.add_row(cell_values4);
+        dataframe.add_row(cell_values4).unwrap();
         let cell_values5: Vec<AnyType> = vec![
             1usize.into(),
             "whoop".into()
         ];
-        dataframe.add_row(cell_values5);
+        dataframe.add_row(cell_values5).unwrap();
         assert!(dataframe.get_columns().len() == 2);
-        assert!(dataframe.get_rows().len() == 5);
-        assert!(dataframe.get_rows()[0].borrow().get_cells().len() == 2);
-        assert_eq!(dataframe.get_rows()[2].borrow().get_cells()[0].upgrade().unwrap().borrow().get_rolling_mean(), Some(7usize.into()));
-        assert_eq!(dataframe.get_rows()[3].borrow().get_cells()[0].upgrade().unwrap().borrow().get_rolling_mean(), Some(8usize.into()));
-        assert_eq!(dataframe.get_rows()[4].borrow().get_cells()[0].upgrade().unwrap().borrow().get_rolling_mean(), Some(5usize.into()));
+        assert!(dataframe.len() == 5);
+        assert!(dataframe.get_row(0).unwrap().get_cells().len() == 2);
+        assert_eq!(dataframe.get_row(2).unwrap().get_cells()[0].get_rolling_mean(), Some(7usize.into()));
+        assert_eq!(dataframe.get_row(3).unwrap().get_cells()[0].get_rolling_mean(), Some(8usize.into()));
+        assert_eq!(dataframe.get_row(4).unwrap().get_cells()[0].get_rolling_mean(), Some(5usize.into()));
+    }
+
+    #[test]
+    fn rolling_windows() {
+        let mut dataframe = DataFrame::new(vec!["price"]).unwrap();
+        dataframe.add_rolling_window("price", RollingWindow::new("sum", 2, RollingAggregation::Sum)).unwrap();
+        dataframe.add_rolling_window("price", RollingWindow::new("max", 3, RollingAggregation::Max).with_min_periods(1)).unwrap();
+        for value in [4u8, 250, 250, 1] {
+            dataframe.add_row(vec![value.into()]).unwrap();
+        }
+
+        let row = dataframe.get_row(2).unwrap();
+        assert_eq!(row.get_cells()[0].get_rolling_value("sum"), Some(&AnyType::Float64(500.0)));
+        assert_eq!(row.get_cells()[0].get_rolling_value("max"), Some(&AnyType::Float64(250.0)));
+        assert_eq!(dataframe.get_row(0).unwrap().get_cells()[0].get_rolling_value("sum"), None);
+
+        dataframe.drop_row(1).unwrap();
+        let column = dataframe.get_column_by_name("price").unwrap();
+        assert_eq!(column.get_rolling_value("sum", 1), Some(AnyType::Float64(254.0)));
+        assert_eq!(column.get_rolling_value("max", 2), Some(AnyType::Float64(250.0)));
+
+        assert_eq!(
+            dataframe.add_rolling_window("price", RollingWindow::new("sum", 2, RollingAggregation::Sum)),
+            Err(DataFrameError::InvalidArgument("column 'price' already has a rolling window named 'sum'".to_string()))
+        );
+        assert!(dataframe.remove_rolling_window("price", "sum").is_ok());
+        assert!(dataframe.remove_rolling_window("price", "sum").is_err());
     }
 
     #[test]
@@ -330,72 +1180,327 @@ mod tests {
             "rando",
             "second"
         ];
-        let mut dataframe = DataFrame::new(columns);
+        let mut dataframe = DataFrame::new(columns).unwrap();
         let cell_values: Vec<AnyType> = vec![
             6usize.into(),
             "whoop".into()
         ];
-        dataframe.add_row(cell_values);
+        dataframe.add_row(cell_values).unwrap();
         let cell_values2: Vec<AnyType> = vec![
             7usize.into(),
             "whoop".into()
         ];
-        dataframe.add_row(cell_values2);
+        dataframe.add_row(cell_values2).unwrap();
         let cell_values3: Vec<AnyType> = vec![
             7usize.into(),
             "whoop".into()
         ];
-        dataframe.add_row(cell_values3);
+        dataframe.add_row(cell_values3).unwrap();
         let cell_values4: Vec<AnyType> = vec![
             9usize.into(),
             "whoop".into()
         ];
-        dataframe.add_row(cell_values4);
+        dataframe.add_row(cell_values4).unwrap();
         let cell_values5: Vec<AnyType> = vec![
             1usize.into(),
             "whoop".into()
         ];
-        dataframe.add_row(cell_values5);
+        dataframe.add_row(cell_values5).unwrap();
         
-        let column_values: Vec<(DateTime<Utc>, usize)> = dataframe.get_column_values_with_datetime::<usize>("rando");
+        let column_values: Vec<(DateTime<Utc>, usize)> = dataframe.get_column_values_with_datetime::<usize>("rando").unwrap();
 
         assert_eq!(column_values[0].1, 6usize);
     }
 
+    #[test]
     fn get_column_values_with_unix_datetime() {
         let columns = vec![
             "rando",
             "second"
         ];
-        let mut dataframe = DataFrame::new(columns);
+        let mut dataframe = DataFrame::new(columns).unwrap();
         let cell_values: Vec<AnyType> = vec![
             6usize.into(),
             "whoop".into()
         ];
-        dataframe.add_row(cell_values);
+        dataframe.add_row(cell_values).unwrap();
         let cell_values2: Vec<AnyType> = vec![
             7usize.into(),
             "whoop".into()
         ];
-        dataframe.add_row(cell_values2);
+        dataframe.add_row(cell_values2).unwrap();
         let cell_values3: Vec<AnyType> = vec![
             7usize.into(),
             "whoop".into()
         ];
-        dataframe.add_row(cell_values3);
+        dataframe.add_row(cell_values3).unwrap();
         let cell_values4: Vec<AnyType> = vec![
             9usize.into(),
             "whoop".into()
         ];
-        dataframe.add_row(cell_values4);
+        dataframe.add_row(cell_values4).unwrap();
         let cell_values5: Vec<AnyType> = vec![
             1usize.into(),
             "whoop".into()
         ];
-        dataframe.add_row(cell_values5);
+        dataframe.add_row(cell_values5).unwrap();
         
-        let column_values: Vec<(i64, usize)> = dataframe.get_column_values_with_unix_datetime::<usize>("rando");
+        let column_values: Vec<(i64, usize)> = dataframe.get_column_values_with_unix_datetime::<usize>("rando").unwrap();
+
+        assert_eq!(column_values[0].1, 6usize);
+    }
+
+    #[test]
+    fn add_row_arity_mismatch() {
+        let columns = vec![
+            "rando",
+            "second"
+        ];
+        let mut dataframe = DataFrame::new(columns).unwrap();
+        let cell_values: Vec<AnyType> = vec![
+            6.into(),
+            "whoop".into(),
+            7.into()
+        ];
+
+        assert_eq!(dataframe.add_row(cell_values), Err(DataFrameError::ArityMismatch { expected: 2, found: 3 }));
+        assert_eq!(dataframe.len(), 0);
+        assert_eq!(dataframe.get_columns()[0].len(), 0);
+    }
 
+    #[test]
+    fn unknown_column() {
+        let columns = vec![
+            "rando",
+            "second"
+        ];
+        let mut dataframe = DataFrame::new(columns).unwrap();
+
+        assert_eq!(dataframe.drop_column_by_name("missing"), Err(DataFrameError::UnknownColumn("missing".to_string())));
+        assert_eq!(dataframe.update_column_rolling_mean("missing", RollingMean::new(true, Some(2))), Err(DataFrameError::UnknownColumn("missing".to_string())));
+        assert!(dataframe.get_column_values_with_datetime::<usize>("missing").is_err());
+    }
+
+    #[test]
+    fn drop_out_of_range() {
+        let columns = vec![
+            "rando",
+            "second"
+        ];
+        let mut dataframe = DataFrame::new(columns).unwrap();
+        dataframe.add_row(vec![6.into(), "whoop".into()]).unwrap();
+
+        assert_eq!(dataframe.drop_row(1), Err(DataFrameError::IndexOutOfRange { index: 1, len: 1 }));
+        assert_eq!(dataframe.drop_column(2), Err(DataFrameError::IndexOutOfRange { index: 2, len: 2 }));
+    }
+
+    #[test]
+    fn drop_last_row_with_rolling_mean() {
+        let columns = vec![
+            "rando",
+            "second"
+        ];
+        let mut dataframe = DataFrame::new(columns).unwrap();
+        dataframe.update_column_rolling_mean("rando", RollingMean::new(true, Some(3))).unwrap();
+        dataframe.add_row(vec![6usize.into(), "whoop".into()]).unwrap();
+        dataframe.add_row(vec![7usize.into(), "whoop".into()]).unwrap();
+        dataframe.drop_row(1).unwrap();
+
+        assert_eq!(dataframe.len(), 1);
+    }
+
+    #[test]
+    fn create_returns_for_text_column() {
+        let columns = vec![
+            "rando",
+            "second"
+        ];
+        let mut dataframe = DataFrame::new(columns).unwrap();
+        dataframe.add_row(vec![6u8.into(), "whoop".into()]).unwrap();
+
+        assert_eq!(
+            dataframe.create_returns_for_column("second", "second_returns", ReturnsKind::Difference(1), RollingMean::new(false, None)),
+            Err(DataFrameError::TypeMismatch { column: "second".to_string(), expected: DataType::Float64, found: DataType::Utf8 })
+        );
+    }
+
+    #[test]
+    fn drop_returns_column() {
+        let columns = vec![
+            "rando",
+            "second"
+        ];
+        let mut dataframe = DataFrame::new(columns).unwrap();
+        dataframe.create_returns_for_column("rando", "rando_returns", ReturnsKind::Difference(1), RollingMean::new(false, None)).unwrap();
+        dataframe.add_row(vec![6.into(), "whoop".into()]).unwrap();
+        dataframe.drop_column_by_name("rando_returns").unwrap();
+        dataframe.add_row(vec![7.into(), "whoop".into()]).unwrap();
+
+        assert!(!dataframe.get_columns()[0].returns.should_calculate);
+        assert_eq!(dataframe.get_row(1).unwrap().get_cells().len(), 2);
+    }
+
+    #[test]
+    fn runtime_column_names() {
+        let header = String::from("rando,second");
+        let columns: Vec<String> = header.split(',').map(String::from).collect();
+        let mut dataframe = DataFrame::new(columns).unwrap();
+        dataframe.add_row(vec![6usize.into(), "whoop".into()]).unwrap();
+        dataframe.create_returns_for_column("rando", format!("{}_returns", "rando"), ReturnsKind::Difference(1), RollingMean::new(false, None)).unwrap();
+
+        let column_values: Vec<(DateTime<Utc>, usize)> = dataframe.get_column_values_with_datetime::<usize>(&String::from("rando")).unwrap();
         assert_eq!(column_values[0].1, 6usize);
+        assert_eq!(&*dataframe.get_columns()[2].name, "rando_returns");
+        assert_eq!(dataframe.get_row(0).unwrap().get_cells()[1].get_column_name(), "second");
+    }
+
+    #[test]
+    fn duplicate_column_names() {
+        assert_eq!(DataFrame::new(vec!["rando", "rando"]).unwrap_err(), DataFrameError::DuplicateColumn("rando".to_string()));
+
+        let mut dataframe = DataFrame::new(vec!["rando", "second"]).unwrap();
+        assert_eq!(
+            dataframe.create_returns_for_column("rando", "second", ReturnsKind::Difference(1), RollingMean::new(false, None)),
+            Err(DataFrameError::DuplicateColumn("second".to_string()))
+        );
+        assert_eq!(
+            dataframe.add_column(Column::new("rando", RollingMean::new(false, None), Returns::new(false, None))),
+            Err(DataFrameError::DuplicateColumn("rando".to_string()))
+        );
+        assert!(!dataframe.get_columns()[0].returns.should_calculate);
+    }
+
+    #[test]
+    fn datetime_returns_column() {
+        use chrono::TimeZone;
+        let mut dataframe = DataFrame::new(vec!["observed", "second"]).unwrap();
+        dataframe.create_returns_for_column("observed", "observed_returns", ReturnsKind::Difference(1), RollingMean::new(false, None)).unwrap();
+        dataframe.add_row(vec![Utc.with_ymd_and_hms(2021, 6, 1, 12, 0, 0).unwrap().into(), "whoop".into()]).unwrap();
+        dataframe.add_row(vec![Utc.with_ymd_and_hms(2021, 6, 1, 12, 5, 0).unwrap().into(), "whoop".into()]).unwrap();
+
+        let observed = dataframe.get_columns()[0].get_grouped_values(Utc.with_ymd_and_hms(2021, 6, 1, 12, 5, 0).unwrap().into());
+        assert_eq!(observed.unwrap().len(), 1);
+        assert_eq!(dataframe.get_row(1).unwrap().get_cells()[2].get_value(), &chrono::Duration::minutes(5).into());
+    }
+
+    #[test]
+    fn add_row_at() {
+        use chrono::TimeZone;
+        let mut dataframe = DataFrame::new(vec!["rando", "second"]).unwrap();
+        dataframe.add_row_at(Utc.with_ymd_and_hms(2019, 1, 1, 0, 0, 0).unwrap(), vec![6usize.into(), "whoop".into()]).unwrap();
+        dataframe.add_row_at(Utc.with_ymd_and_hms(2019, 1, 1, 0, 0, 30).unwrap(), vec![7usize.into(), "whoop".into()]).unwrap();
+
+        let column_values: Vec<(DateTime<Utc>, usize)> = dataframe.get_column_values_with_datetime::<usize>("rando").unwrap();
+        assert_eq!(column_values[1], (Utc.with_ymd_and_hms(2019, 1, 1, 0, 0, 30).unwrap(), 7usize));
+        let unix_values: Vec<(i64, usize)> = dataframe.get_column_values_with_unix_datetime::<usize>("rando").unwrap();
+        assert_eq!(unix_values[0].0, 1546300800i64);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn rolling_mean_over_duration() {
+        use chrono::TimeZone;
+        let start = Utc.with_ymd_and_hms(2021, 6, 1, 12, 0, 0).unwrap();
+        let mut dataframe = DataFrame::new(vec!["price"]).unwrap();
+        dataframe.update_column_rolling_mean("price", RollingMean::over_duration(chrono::Duration::minutes(5))).unwrap();
+        dataframe.add_row_at(start, vec![10f64.into()]).unwrap();
+        dataframe.add_row_at(start + chrono::Duration::minutes(1), vec![20f64.into()]).unwrap();
+        dataframe.add_row_at(start + chrono::Duration::minutes(1), vec![30f64.into()]).unwrap();
+        dataframe.add_row_at(start + chrono::Duration::minutes(6), vec![40f64.into()]).unwrap();
+        let column = dataframe.get_column_by_name("price").unwrap();
+
+        assert_eq!(column.get_rolling_mean(0), Some(10f64.into()));
+        assert_eq!(column.get_rolling_mean(2), Some(20f64.into()));
+        // the rows stamped at minute one are exactly five minutes old so have dropped out
+        assert_eq!(column.get_rolling_mean(3), Some(40f64.into()));
+
+        dataframe.drop_row(2).unwrap();
+        dataframe.add_row_at(start + chrono::Duration::minutes(7), vec![60f64.into()]).unwrap();
+        let column = dataframe.get_column_by_name("price").unwrap();
+
+        assert_eq!(column.get_rolling_mean(1), Some(15f64.into()));
+        assert_eq!(column.get_rolling_mean(3), Some(50f64.into()));
+
+        // a row stamped before the latest one is turned away before anything is stored
+        let error = dataframe.add_row_at(start + chrono::Duration::minutes(3), vec![70f64.into()]).unwrap_err();
+        assert!(matches!(error, DataFrameError::InvalidArgument(_)));
+        assert_eq!(dataframe.len(), 4);
+
+        // sorted rows still take their window from the rows stamped before them
+        let mut sorted = dataframe.sort_by(&["price"], &[true], false).unwrap();
+        let means = |dataframe: &DataFrame| (0..dataframe.len()).map(|index| dataframe.get_column_by_name("price").unwrap().get_rolling_mean(index)).collect::<Vec<Option<AnyType>>>();
+        assert_eq!(means(&sorted), vec![Some(50f64.into()), Some(40f64.into()), Some(15f64.into()), Some(10f64.into())]);
+
+        sorted.drop_row(1).unwrap();
+        sorted.add_row_at(start + chrono::Duration::minutes(8), vec![80f64.into()]).unwrap();
+        assert_eq!(means(&sorted), vec![Some(60f64.into()), Some(15f64.into()), Some(10f64.into()), Some(70f64.into())]);
+        assert!(sorted.add_row_at(start + chrono::Duration::minutes(2), vec![80f64.into()]).is_err());
+    }
+
+    #[test]
+    fn with_clock() {
+        use crate::clock::ManualClock;
+        use chrono::TimeZone;
+        let clock = Rc::new(ManualClock::new(Utc.with_ymd_and_hms(2021, 6, 1, 12, 0, 0).unwrap()));
+        let mut dataframe = DataFrame::with_clock(vec!["rando", "second"], clock.clone()).unwrap();
+        dataframe.add_row(vec![6u32.into(), "whoop".into()]).unwrap();
+        clock.advance(chrono::Duration::seconds(5));
+        dataframe.add_row(vec![7u32.into(), "whoop".into()]).unwrap();
+        clock.advance(chrono::Duration::seconds(3));
+        dataframe.add_row(vec![AnyType::Null, "whoop".into()]).unwrap();
+        clock.advance(chrono::Duration::seconds(4));
+        dataframe.add_row(vec![9u32.into(), "whoop".into()]).unwrap();
+
+        assert_eq!(dataframe.get_datetime(1).unwrap(), Utc.with_ymd_and_hms(2021, 6, 1, 12, 0, 5).unwrap());
+        let column = &dataframe.get_columns()[0];
+        let diffs: Vec<(i64, u32)> = column.get_values_as_vec_with_unix_datetime_diff::<u32>(dataframe.get_datetimes());
+        assert_eq!(diffs, vec![(0, 6u32), (5, 7u32), (7, 9u32)]);
+    }
+
+    #[test]
+    fn with_schema() {
+        use crate::schema::Field;
+        let schema = Schema::new(vec![
+            Field::new("price", DataType::Float64, false),
+            Field::new("venue", DataType::Utf8, true),
+        ]);
+        let mut dataframe = DataFrame::with_schema(schema.clone()).unwrap();
+        dataframe.add_row(vec![6.5f64.into(), AnyType::Null]).unwrap();
+
+        assert_eq!(dataframe.schema(), schema);
+        assert_eq!(
+            dataframe.add_row(vec!["whoop".into(), "whoop".into()]),
+            Err(DataFrameError::TypeMismatch { column: "price".to_string(), expected: DataType::Float64, found: DataType::Utf8 })
+        );
+        assert_eq!(
+            dataframe.add_row(vec![AnyType::Null, "whoop".into()]),
+            Err(DataFrameError::TypeMismatch { column: "price".to_string(), expected: DataType::Float64, found: DataType::Null })
+        );
+        assert_eq!(
+            dataframe.add_row(vec![7.5f64.into(), 3u8.into()]),
+            Err(DataFrameError::TypeMismatch { column: "venue".to_string(), expected: DataType::Utf8, found: DataType::UInt8 })
+        );
+        assert_eq!(dataframe.len(), 1);
+        assert_eq!(dataframe.get_columns()[0].len(), 1);
+    }
+
+    #[test]
+    fn schema_inferred_from_values() {
+        let mut dataframe = DataFrame::new(vec!["rando", "second"]).unwrap();
+        assert_eq!(dataframe.schema().get_dtype("rando"), Some(DataType::Null));
+
+        dataframe.add_row(vec![6u32.into(), "whoop".into()]).unwrap();
+        dataframe.create_returns_for_column("rando", "rando_returns", ReturnsKind::Difference(1), RollingMean::new(false, None)).unwrap();
+        dataframe.add_row(vec![8u32.into(), "whoop".into()]).unwrap();
+
+        let schema = dataframe.schema();
+        assert_eq!(schema.get_dtype("rando"), Some(DataType::UInt32));
+        assert_eq!(schema.get_dtype("second"), Some(DataType::Utf8));
+        assert_eq!(schema.get_dtype("rando_returns"), Some(DataType::ISize));
+        assert!(dataframe.add_row(vec!["whoop".into(), "whoop".into()]).is_err());
+        assert_eq!(
+            dataframe.add_column_from_values("mixed", vec![1u8.into(), 1i8.into()], RollingMean::new(false, None)),
+            Err(DataFrameError::TypeMismatch { column: "mixed".to_string(), expected: DataType::UInt8, found: DataType::Int8 })
+        );
+        assert_eq!(dataframe.get_columns().len(), 3);
+    }
+}
@@ -0,0 +1,478 @@
+use crate::cell::types::datatypes::{
+    AnyType,
+    DataType,
+};
+use crate::clock::{
+    Clock,
+    SystemClock,
+};
+use crate::column::{
+    Column,
+    Returns,
+    RollingMean,
+};
+use crate::error::{
+    DataFrameError,
+    Result,
+};
+use crate::frame::DataFrame;
+use crate::schema::{
+    Field,
+    Schema,
+};
+use ::arrow::array::{
+    Array,
+    ArrayRef,
+    AsArray,
+    BooleanArray,
+    Date32Array,
+    DurationNanosecondArray,
+    Float32Array,
+    Float64Array,
+    Int16Array,
+    Int32Array,
+    Int64Array,
+    Int8Array,
+    NullArray,
+    StringArray,
+    Time64NanosecondArray,
+    TimestampNanosecondArray,
+    UInt16Array,
+    UInt32Array,
+    UInt64Array,
+    UInt8Array,
+};
+use ::arrow::compute::{
+    cast,
+    concat_batches,
+};
+use ::arrow::datatypes::{
+    DataType as ArrowDataType,
+    Date32Type,
+    DurationNanosecondType,
+    Field as ArrowField,
+    Float32Type,
+    Float64Type,
+    Int16Type,
+    Int32Type,
+    Int64Type,
+    Int8Type,
+    Schema as ArrowSchema,
+    SchemaRef,
+    Time64NanosecondType,
+    TimeUnit,
+    TimestampNanosecondType,
+    UInt16Type,
+    UInt32Type,
+    UInt64Type,
+    UInt8Type,
+};
+use ::arrow::error::ArrowError;
+use ::arrow::ipc::reader::{
+    FileReader,
+    StreamReader,
+};
+use ::arrow::ipc::writer::{
+    FileWriter,
+    StreamWriter,
+};
+use ::arrow::record_batch::RecordBatch;
+use chrono::{
+    DateTime,
+    NaiveDate,
+    NaiveTime,
+    Timelike,
+    Utc,
+};
+use std::collections::HashMap;
+use std::io::{
+    Read,
+    Seek,
+    Write,
+};
+use std::sync::Arc;
+
+// usize and isize have no arrow type of their own so are stored as u64 and i64 with this metadata naming the type
+pub const DTYPE_METADATA: &str = "dataframe.dtype";
+
+fn utc() -> Option<Arc<str>> {
+    Some(Arc::from("UTC"))
+}
+
+// temporal values are held to the nanosecond, with timestamps in utc
+pub fn to_arrow_dtype(dtype: DataType) -> ArrowDataType {
+    match dtype {
+        DataType::Null => ArrowDataType::Null,
+        DataType::Boolean => ArrowDataType::Boolean,
+        DataType::Utf8 => ArrowDataType::Utf8,
+        DataType::UInt8 => ArrowDataType::UInt8,
+        DataType::UInt16 => ArrowDataType::UInt16,
+        DataType::UInt32 => ArrowDataType::UInt32,
+        DataType::UInt64 | DataType::USize => ArrowDataType::UInt64,
+        DataType::Int8 => ArrowDataType::Int8,
+        DataType::Int16 => ArrowDataType::Int16,
+        DataType::Int32 => ArrowDataType::Int32,
+        DataType::Int64 | DataType::ISize => ArrowDataType::Int64,
+        DataType::Float32 => ArrowDataType::Float32,
+        DataType::Float64 => ArrowDataType::Float64,
+        DataType::Date => ArrowDataType::Date32,
+        DataType::Datetime => ArrowDataType::Timestamp(TimeUnit::Nanosecond, utc()),
+        DataType::Duration => ArrowDataType::Duration(TimeUnit::Nanosecond),
+        DataType::Time => ArrowDataType::Time64(TimeUnit::Nanosecond),
+    }
+}
+
+// the type an arrow type is read as and the arrow type its arrays are cast to first, None if it cannot be read
+fn from_arrow_dtype(arrow_dtype: &ArrowDataType) -> Option<(DataType, ArrowDataType)> {
+    let dtype = match arrow_dtype {
+        ArrowDataType::Null => DataType::Null,
+        ArrowDataType::Boolean => DataType::Boolean,
+        ArrowDataType::Utf8 | ArrowDataType::LargeUtf8 | ArrowDataType::Utf8View => DataType::Utf8,
+        ArrowDataType::UInt8 => DataType::UInt8,
+        ArrowDataType::UInt16 => DataType::UInt16,
+        ArrowDataType::UInt32 => DataType::UInt32,
+        ArrowDataType::UInt64 => DataType::UInt64,
+        ArrowDataType::Int8 => DataType::Int8,
+        ArrowDataType::Int16 => DataType::Int16,
+        ArrowDataType::Int32 => DataType::Int32,
+        ArrowDataType::Int64 => DataType::Int64,
+        ArrowDataType::Float16 | ArrowDataType::Float32 => DataType::Float32,
+        ArrowDataType::Float64 => DataType::Float64,
+        ArrowDataType::Date32 | ArrowDataType::Date64 => DataType::Date,
+        ArrowDataType::Timestamp(_, _) => DataType::Datetime,
+        ArrowDataType::Duration(_) => DataType::Duration,
+        ArrowDataType::Time32(_) | ArrowDataType::Time64(_) => DataType::Time,
+        _ => return None,
+    };
+    match arrow_dtype {
+        // timestamps are stored since the epoch in utc whatever their zone so only the unit needs changing, and
+        // those without a zone are taken to be in utc
+        ArrowDataType::Timestamp(_, timezone) => Some((dtype, ArrowDataType::Timestamp(TimeUnit::Nanosecond, timezone.clone()))),
+        _ => Some((dtype, to_arrow_dtype(dtype))),
+    }
+}
+
+pub fn to_arrow_field(field: &Field) -> ArrowField {
+    let arrow_field = ArrowField::new(field.name.to_string(), to_arrow_dtype(field.dtype), field.nullable);
+    match field.dtype {
+        DataType::USize | DataType::ISize => arrow_field.with_metadata(HashMap::from([(DTYPE_METADATA.to_string(), field.dtype.to_string())])),
+        _ => arrow_field,
+    }
+}
+
+pub fn from_arrow_field(arrow_field: &ArrowField) -> Result<Field> {
+    let (dtype, _) = from_arrow_dtype(arrow_field.data_type()).ok_or_else(|| unsupported(arrow_field))?;
+    let dtype = match (dtype, arrow_field.metadata().get(DTYPE_METADATA).map(String::as_str)) {
+        (DataType::UInt64, Some("usize")) => DataType::USize,
+        (DataType::Int64, Some("isize")) => DataType::ISize,
+        (dtype, _) => dtype,
+    };
+    Ok(Field::new(arrow_field.name().as_str(), dtype, arrow_field.is_nullable()))
+}
+
+pub fn to_arrow_schema(schema: &Schema) -> ArrowSchema {
+    ArrowSchema::new(schema.get_fields().iter().map(to_arrow_field).collect::<Vec<ArrowField>>())
+}
+
+pub fn from_arrow_schema(arrow_schema: &ArrowSchema) -> Result<Schema> {
+    let fields = arrow_schema.fields().iter().map(|arrow_field| from_arrow_field(arrow_field)).collect::<Result<Vec<Field>>>()?;
+    Ok(Schema::new(fields))
+}
+
+// the timestamp column, when named, comes first and holds the time of each row
+pub fn to_record_batch(dataframe: &DataFrame, timestamp_column: Option<&str>) -> Result<RecordBatch> {
+    let mut fields: Vec<ArrowField> = vec![];
+    let mut arrays: Vec<ArrayRef> = vec![];
+    if let Some(name) = timestamp_column {
+        if dataframe.get_columns().iter().any(|column| &*column.name == name) {
+            return Err(DataFrameError::DuplicateColumn(name.to_string()));
+        }
+        let datetimes = dataframe.get_datetimes().iter()
+            .map(|datetime| datetime.timestamp_nanos_opt().ok_or_else(|| DataFrameError::Overflow(name.to_string())))
+            .collect::<Result<Vec<i64>>>()?;
+        fields.push(ArrowField::new(name, to_arrow_dtype(DataType::Datetime), false));
+        arrays.push(Arc::new(TimestampNanosecondArray::from(datetimes).with_timezone_opt(utc())));
+    }
+    for column in dataframe.get_columns().iter() {
+        fields.push(to_arrow_field(&column.get_field()));
+        arrays.push(to_array(column)?);
+    }
+    RecordBatch::try_new(Arc::new(ArrowSchema::new(fields)), arrays).map_err(from_arrow_error)
+}
+
+// rows are stamped by the system clock as they are read, as added rows are, unless the batch has a timestamp column
+pub fn from_record_batch(batch: &RecordBatch, timestamp_column: Option<&str>) -> Result<DataFrame> {
+    let mut datetimes = None;
+    let mut columns = vec![];
+    for (arrow_field, array) in batch.schema().fields().iter().zip(batch.columns().iter()) {
+        if Some(arrow_field.name().as_str()) == timestamp_column {
+            let arrow_dtype = match from_arrow_dtype(arrow_field.data_type()) {
+                Some((DataType::Datetime, arrow_dtype)) => arrow_dtype,
+                _ => return Err(unsupported(arrow_field)),
+            };
+            let array = cast(array, &arrow_dtype).map_err(from_arrow_error)?;
+            let array = array.as_primitive::<TimestampNanosecondType>();
+            datetimes = Some((0..array.len())
+                .map(|index| match array.is_valid(index) {
+                    true => Ok(DateTime::from_timestamp_nanos(array.value(index))),
+                    false => Err(DataFrameError::InvalidArgument(format!("timestamp column '{}' holds a null", arrow_field.name()))),
+                })
+                .collect::<Result<Vec<DateTime<Utc>>>>()?);
+            continue;
+        }
+        let field = from_arrow_field(arrow_field)?;
+        let (_, arrow_dtype) = from_arrow_dtype(arrow_field.data_type()).ok_or_else(|| unsupported(arrow_field))?;
+        let array = cast(array, &arrow_dtype).map_err(from_arrow_error)?;
+        let mut column = Column::from_field(field.clone(), RollingMean::new(false, None), Returns::new(false, None));
+        for index in 0..array.len() {
+            column.push(from_array(&array, field.dtype, index))?;
+        }
+        columns.push(column);
+    }
+    if let Some(name) = timestamp_column {
+        if batch.schema().column_with_name(name).is_none() {
+            return Err(DataFrameError::UnknownColumn(name.to_string()));
+        }
+    }
+    let datetimes = datetimes.unwrap_or_else(|| {
+        let clock = SystemClock::default();
+        (0..batch.num_rows()).map(|_| clock.now()).collect()
+    });
+    DataFrame::from_columns(datetimes, columns)
+}
+
+pub fn write_ipc_file<W: Write>(writer: W, dataframe: &DataFrame, timestamp_column: Option<&str>) -> Result<()> {
+    let batch = to_record_batch(dataframe, timestamp_column)?;
+    let mut writer = FileWriter::try_new(writer, &batch.schema()).map_err(from_arrow_error)?;
+    writer.write(&batch).map_err(from_arrow_error)?;
+    writer.finish().map_err(from_arrow_error)
+}
+
+pub fn write_ipc_stream<W: Write>(writer: W, dataframe: &DataFrame, timestamp_column: Option<&str>) -> Result<()> {
+    let batch = to_record_batch(dataframe, timestamp_column)?;
+    let mut writer = StreamWriter::try_new(writer, &batch.schema()).map_err(from_arrow_error)?;
+    writer.write(&batch).map_err(from_arrow_error)?;
+    writer.finish().map_err(from_arrow_error)
+}
+
+// every batch in the file is read into the one frame
+pub fn read_ipc_file<R: Read + Seek>(reader: R, timestamp_column: Option<&str>) -> Result<DataFrame> {
+    let reader = FileReader::try_new(reader, None).map_err(from_arrow_error)?;
+    let schema = reader.schema();
+    let batches = reader.collect::<std::result::Result<Vec<RecordBatch>, ArrowError>>().map_err(from_arrow_error)?;
+    from_batches(&schema, &batches, timestamp_column)
+}
+
+pub fn read_ipc_stream<R: Read>(reader: R, timestamp_column: Option<&str>) -> Result<DataFrame> {
+    let reader = StreamReader::try_new(reader, None).map_err(from_arrow_error)?;
+    let schema = reader.schema();
+    let batches = reader.collect::<std::result::Result<Vec<RecordBatch>, ArrowError>>().map_err(from_arrow_error)?;
+    from_batches(&schema, &batches, timestamp_column)
+}
+
+fn from_batches(schema: &SchemaRef, batches: &[RecordBatch], timestamp_column: Option<&str>) -> Result<DataFrame> {
+    let batch = concat_batches(schema, batches).map_err(from_arrow_error)?;
+    from_record_batch(&batch, timestamp_column)
+}
+
+fn to_array(column: &Column) -> Result<ArrayRef> {
+    let values = column.iter_values();
+    let array: ArrayRef = match column.dtype() {
+        DataType::Null => Arc::new(NullArray::new(column.len())),
+        DataType::Boolean => Arc::new(values.map(|value| match value { AnyType::Boolean(value) => Some(value), _ => None }).collect::<BooleanArray>()),
+        DataType::Utf8 => Arc::new(values.map(|value| match value { AnyType::Utf8(value) => Some(value), _ => None }).collect::<Vec<Option<_>>>().iter().map(|value| value.as_deref()).collect::<StringArray>()),
+        DataType::UInt8 => Arc::new(values.map(|value| match value { AnyType::UInt8(value) => Some(value), _ => None }).collect::<UInt8Array>()),
+        DataType::UInt16 => Arc::new(values.map(|value| match value { AnyType::UInt16(value) => Some(value), _ => None }).collect::<UInt16Array>()),
+        DataType::UInt32 => Arc::new(values.map(|value| match value { AnyType::UInt32(value) => Some(value), _ => None }).collect::<UInt32Array>()),
+        DataType::UInt64 => Arc::new(values.map(|value| match value { AnyType::UInt64(value) => Some(value), _ => None }).collect::<UInt64Array>()),
+        DataType::USize => Arc::new(values.map(|value| match value { AnyType::USize(value) => Some(value as u64), _ => None }).collect::<UInt64Array>()),
+        DataType::Int8 => Arc::new(values.map(|value| match value { AnyType::Int8(value) => Some(value), _ => None }).collect::<Int8Array>()),
+        DataType::Int16 => Arc::new(values.map(|value| match value { AnyType::Int16(value) => Some(value), _ => None }).collect::<Int16Array>()),
+        DataType::Int32 => Arc::new(values.map(|value| match value { AnyType::Int32(value) => Some(value), _ => None }).collect::<Int32Array>()),
+        DataType::Int64 => Arc::new(values.map(|value| match value { AnyType::Int64(value) => Some(value), _ => None }).collect::<Int64Array>()),
+        DataType::ISize => Arc::new(values.map(|value| match value { AnyType::ISize(value) => Some(value as i64), _ => None }).collect::<Int64Array>()),
+        DataType::Float32 => Arc::new(values.map(|value| match value { AnyType::Float32(value) => Some(value), _ => None }).collect::<Float32Array>()),
+        DataType::Float64 => Arc::new(values.map(|value| match value { AnyType::Float64(value) => Some(value), _ => None }).collect::<Float64Array>()),
+        DataType::Date => Arc::new(values.map(|value| match value { AnyType::Date(value) => Some((value - epoch()).num_days() as i32), _ => None }).collect::<Date32Array>()),
+        DataType::Datetime => {
+            let values = values
+                .map(|value| match value {
+                    AnyType::Datetime(value) => value.timestamp_nanos_opt().map(Some).ok_or_else(|| DataFrameError::Overflow(column.name.to_string())),
+                    _ => Ok(None),
+                })
+                .collect::<Result<Vec<Option<i64>>>>()?;
+            Arc::new(TimestampNanosecondArray::from(values).with_timezone_opt(utc()))
+        },
+        DataType::Duration => {
+            let values = values
+                .map(|value| match value {
+                    AnyType::Duration(value) => value.num_nanoseconds().map(Some).ok_or_else(|| DataFrameError::Overflow(column.name.to_string())),
+                    _ => Ok(None),
+                })
+                .collect::<Result<Vec<Option<i64>>>>()?;
+            Arc::new(DurationNanosecondArray::from(values))
+        },
+        DataType::Time => Arc::new(values.map(|value| match value {
+            AnyType::Time(value) => Some(value.num_seconds_from_midnight() as i64 * 1_000_000_000 + value.nanosecond() as i64),
+            _ => None,
+        }).collect::<Time64NanosecondArray>()),
+    };
+    Ok(array)
+}
+
+// the array has already been cast to the arrow type of the dtype
+fn from_array(array: &ArrayRef, dtype: DataType, index: usize) -> AnyType {
+    if array.is_null(index) {
+        return AnyType::Null;
+    }
+    match dtype {
+        DataType::Null => AnyType::Null,
+        DataType::Boolean => AnyType::Boolean(array.as_boolean().value(index)),
+        DataType::Utf8 => AnyType::Utf8(array.as_string::<i32>().value(index).into()),
+        DataType::UInt8 => AnyType::UInt8(array.as_primitive::<UInt8Type>().value(index)),
+        DataType::UInt16 => AnyType::UInt16(array.as_primitive::<UInt16Type>().value(index)),
+        DataType::UInt32 => AnyType::UInt32(array.as_primitive::<UInt32Type>().value(index)),
+        DataType::UInt64 => AnyType::UInt64(array.as_primitive::<UInt64Type>().value(index)),
+        DataType::USize => AnyType::USize(array.as_primitive::<UInt64Type>().value(index) as usize),
+        DataType::Int8 => AnyType::Int8(array.as_primitive::<Int8Type>().value(index)),
+        DataType::Int16 => AnyType::Int16(array.as_primitive::<Int16Type>().value(index)),
+        DataType::Int32 => AnyType::Int32(array.as_primitive::<Int32Type>().value(index)),
+        DataType::Int64 => AnyType::Int64(array.as_primitive::<Int64Type>().value(index)),
+        DataType::ISize => AnyType::ISize(array.as_primitive::<Int64Type>().value(index) as isize),
+        DataType::Float32 => AnyType::Float32(array.as_primitive::<Float32Type>().value(index)),
+        DataType::Float64 => AnyType::Float64(array.as_primitive::<Float64Type>().value(index)),
+        // date32 reaches further than NaiveDate so dates beyond it are read as nulls
+        DataType::Date => epoch()
+            .checked_add_signed(chrono::Duration::days(array.as_primitive::<Date32Type>().value(index) as i64))
+            .map(AnyType::Date)
+            .unwrap_or(AnyType::Null),
+        DataType::Datetime => AnyType::Datetime(DateTime::from_timestamp_nanos(array.as_primitive::<TimestampNanosecondType>().value(index))),
+        DataType::Duration => AnyType::Duration(chrono::Duration::nanoseconds(array.as_primitive::<DurationNanosecondType>().value(index))),
+        DataType::Time => {
+            let nanoseconds = array.as_primitive::<Time64NanosecondType>().value(index);
+            NaiveTime::from_num_seconds_from_midnight_opt((nanoseconds / 1_000_000_000) as u32, (nanoseconds % 1_000_000_000) as u32)
+                .map(AnyType::Time)
+                .unwrap_or(AnyType::Null)
+        },
+    }
+}
+
+fn epoch() -> NaiveDate {
+    NaiveDate::from_ymd_opt(1970, 1, 1).unwrap()
+}
+
+fn unsupported(arrow_field: &ArrowField) -> DataFrameError {
+    DataFrameError::InvalidArgument(format!("column '{}' has arrow type {} which is not supported", arrow_field.name(), arrow_field.data_type()))
+}
+
+pub fn from_arrow_error(error: ArrowError) -> DataFrameError {
+    match error {
+        ArrowError::IoError(message, _) => DataFrameError::Io(message),
+        error => DataFrameError::InvalidArgument(error.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::test_helpers::{
+        start,
+        values,
+    };
+    use std::io::Cursor;
+
+    fn every_type() -> DataFrame {
+        let start = start() + chrono::Duration::nanoseconds(123);
+        let rows: Vec<Vec<AnyType>> = vec![
+            vec![
+                AnyType::Null, true.into(), "vod".into(), AnyType::UInt8(1), AnyType::UInt16(2), AnyType::UInt32(3), AnyType::UInt64(4), AnyType::USize(5),
+                AnyType::Int8(-1), AnyType::Int16(-2), AnyType::Int32(-3), AnyType::Int64(-4), AnyType::ISize(-5), AnyType::Float32(1.5), AnyType::Float64(2.5),
+                AnyType::Date(NaiveDate::from_ymd_opt(1969, 12, 31).unwrap()), AnyType::Datetime(start), AnyType::Duration(chrono::Duration::nanoseconds(-7)),
+                AnyType::Time(NaiveTime::from_hms_nano_opt(8, 1, 2, 3).unwrap()),
+            ],
+            vec![AnyType::Null; 19],
+        ];
+        let mut dataframe = DataFrame::new(vec![
+            "null", "bool", "str", "u8", "u16", "u32", "u64", "usize", "i8", "i16", "i32", "i64", "isize", "f32", "f64", "date", "datetime", "duration", "time",
+        ]).unwrap();
+        for (offset, row) in rows.into_iter().enumerate() {
+            dataframe.add_row_at(start + chrono::Duration::seconds(offset as i64), row).unwrap();
+        }
+        dataframe
+    }
+
+    #[test]
+    fn record_batch() {
+        let dataframe = every_type();
+        let batch = to_record_batch(&dataframe, Some("time_of_row")).unwrap();
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(batch.schema().field(0).data_type(), &ArrowDataType::Timestamp(TimeUnit::Nanosecond, utc()));
+        assert_eq!(batch.schema().field(8).data_type(), &ArrowDataType::UInt64);
+        assert_eq!(batch.column(3).null_count(), 1);
+
+        let read_back = from_record_batch(&batch, Some("time_of_row")).unwrap();
+        assert_eq!(read_back.schema(), dataframe.schema());
+        assert_eq!(read_back.get_datetimes(), dataframe.get_datetimes());
+        for column in dataframe.get_columns().iter() {
+            assert_eq!(values(&read_back, &column.name), column.iter_values().collect::<Vec<AnyType>>());
+        }
+        assert_eq!(from_arrow_schema(&to_arrow_schema(&dataframe.schema())).unwrap(), dataframe.schema());
+
+        // without a timestamp column the batch holds only the columns and rows are stamped as they are read
+        let batch = to_record_batch(&dataframe, None).unwrap();
+        assert_eq!(batch.num_columns(), 19);
+        let before = Utc::now();
+        let read_back = from_record_batch(&batch, None).unwrap();
+        assert_eq!(read_back.len(), 2);
+        assert!(read_back.get_datetimes().iter().all(|datetime| *datetime >= before && *datetime <= Utc::now()));
+        assert!(read_back.get_datetimes().is_sorted());
+        assert_eq!(from_record_batch(&batch, Some("missing")).unwrap_err(), DataFrameError::UnknownColumn("missing".to_string()));
+        assert_eq!(to_record_batch(&dataframe, Some("time")).unwrap_err(), DataFrameError::DuplicateColumn("time".to_string()));
+    }
+
+    #[test]
+    fn foreign_types() {
+        let schema = ArrowSchema::new(vec![
+            ArrowField::new("at", ArrowDataType::Timestamp(TimeUnit::Millisecond, None), false),
+            ArrowField::new("name", ArrowDataType::LargeUtf8, true),
+            ArrowField::new("list", ArrowDataType::new_list(ArrowDataType::Int32, true), true),
+        ]);
+        let batch = RecordBatch::try_new(Arc::new(schema.clone()), vec![
+            Arc::new(::arrow::array::TimestampMillisecondArray::from(vec![1_704_182_400_000])),
+            Arc::new(::arrow::array::LargeStringArray::from(vec![Some("vod")])),
+            Arc::new(::arrow::array::ListArray::new_null(Arc::new(ArrowField::new_list_field(ArrowDataType::Int32, true)), 1)),
+        ]).unwrap();
+        match from_record_batch(&batch, Some("at")).unwrap_err() {
+            DataFrameError::InvalidArgument(message) => assert!(message.starts_with("column 'list' has arrow type List(")),
+            error => panic!("unexpected error {:?}", error),
+        }
+
+        let batch = batch.project(&[0, 1]).unwrap();
+        let dataframe = from_record_batch(&batch, Some("at")).unwrap();
+        assert_eq!(dataframe.get_datetimes()[0], start());
+        assert_eq!(values(&dataframe, "name"), vec!["vod".into()]);
+
+        let schema = ArrowSchema::new(vec![ArrowField::new("day", ArrowDataType::Date32, true)]);
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(Date32Array::from(vec![i32::MIN, 1, i32::MAX]))]).unwrap();
+        let dataframe = from_record_batch(&batch, None).unwrap();
+        assert_eq!(values(&dataframe, "day"), vec![AnyType::Null, AnyType::Date(NaiveDate::from_ymd_opt(1970, 1, 2).unwrap()), AnyType::Null]);
+    }
+
+    #[test]
+    fn ipc() {
+        let dataframe = every_type();
+        let mut file = Cursor::new(vec![]);
+        write_ipc_file(&mut file, &dataframe, Some("time_of_row")).unwrap();
+        file.set_position(0);
+        let read_back = read_ipc_file(file, Some("time_of_row")).unwrap();
+        assert_eq!(read_back.schema(), dataframe.schema());
+        assert_eq!(read_back.get_datetimes(), dataframe.get_datetimes());
+        assert_eq!(values(&read_back, "time"), values(&dataframe, "time"));
+
+        let mut stream = vec![];
+        write_ipc_stream(&mut stream, &dataframe, None).unwrap();
+        let read_back = read_ipc_stream(stream.as_slice(), None).unwrap();
+        assert_eq!(values(&read_back, "datetime"), values(&dataframe, "datetime"));
+        assert!(read_ipc_stream("not arrow".as_bytes(), None).is_err());
+    }
+}
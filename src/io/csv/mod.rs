@@ -0,0 +1,410 @@
+use crate::cell::types::datatypes::{
+    AnyType,
+    DataType,
+};
+use crate::error::{
+    DataFrameError,
+    Result,
+};
+use crate::column::Column;
+use crate::frame::DataFrame;
+use crate::io::{
+    build_frame,
+    infer_dtype,
+    RawValue,
+};
+use crate::schema::Schema;
+use std::fs::File;
+use std::io::{
+    Read,
+    Write,
+};
+use std::path::Path;
+
+#[derive(Debug, Clone)]
+pub struct CsvReadOptions {
+    // None looks at the first line, which is taken as the header when every field in it is text
+    pub has_header: Option<bool>,
+    pub delimiter: u8,
+    pub quote: u8,
+    // None means quotes inside a quoted field are escaped by doubling them
+    pub escape: Option<u8>,
+    // fields holding any of these are read as nulls
+    pub null_values: Vec<String>,
+    // types for the named columns in place of inferring them, the other columns are still inferred
+    pub schema: Option<Schema>,
+    // a column of timestamps used as the time of each row rather than being read as a column
+    pub timestamp_column: Option<String>,
+}
+
+impl Default for CsvReadOptions {
+    fn default() -> Self {
+        Self {
+            has_header: None,
+            delimiter: b',',
+            quote: b'"',
+            escape: None,
+            null_values: vec!["".to_string(), "null".to_string()],
+            schema: None,
+            timestamp_column: None,
+        }
+    }
+}
+
+impl CsvReadOptions {
+    pub fn with_header(mut self, has_header: bool) -> Self {
+        self.has_header = Some(has_header);
+        self
+    }
+
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    pub fn with_quote(mut self, quote: u8) -> Self {
+        self.quote = quote;
+        self
+    }
+
+    pub fn with_escape(mut self, escape: u8) -> Self {
+        self.escape = Some(escape);
+        self
+    }
+
+    pub fn with_null_values(mut self, null_values: Vec<String>) -> Self {
+        self.null_values = null_values;
+        self
+    }
+
+    pub fn with_schema(mut self, schema: Schema) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    pub fn with_timestamp_column<S: Into<String>>(mut self, column_name: S) -> Self {
+        self.timestamp_column = Some(column_name.into());
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuoteStyle {
+    // only fields holding the delimiter, a quote or a line break
+    Necessary,
+    Always,
+    // every field that is not a number
+    NonNumeric,
+    Never,
+}
+
+#[derive(Debug, Clone)]
+pub struct CsvWriteOptions {
+    pub has_header: bool,
+    pub delimiter: u8,
+    pub quote: u8,
+    pub quote_style: QuoteStyle,
+    pub null_value: String,
+    // the number of decimal places floats are written with, None writes them in full
+    pub float_precision: Option<usize>,
+    // writes the time of each row as the first column, named timestamp
+    pub include_timestamp: bool,
+    // writes the rolling mean of each column calculating one after it, named with a _rolling_mean suffix
+    pub include_rolling_means: bool,
+}
+
+impl Default for CsvWriteOptions {
+    fn default() -> Self {
+        Self {
+            has_header: true,
+            delimiter: b',',
+            quote: b'"',
+            quote_style: QuoteStyle::Necessary,
+            null_value: "".to_string(),
+            float_precision: None,
+            include_timestamp: false,
+            include_rolling_means: false,
+        }
+    }
+}
+
+impl CsvWriteOptions {
+    pub fn with_header(mut self, has_header: bool) -> Self {
+        self.has_header = has_header;
+        self
+    }
+
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    pub fn with_quote(mut self, quote: u8) -> Self {
+        self.quote = quote;
+        self
+    }
+
+    pub fn with_quote_style(mut self, quote_style: QuoteStyle) -> Self {
+        self.quote_style = quote_style;
+        self
+    }
+
+    pub fn with_null_value<S: Into<String>>(mut self, null_value: S) -> Self {
+        self.null_value = null_value.into();
+        self
+    }
+
+    pub fn with_float_precision(mut self, float_precision: usize) -> Self {
+        self.float_precision = Some(float_precision);
+        self
+    }
+
+    pub fn with_timestamp(mut self) -> Self {
+        self.include_timestamp = true;
+        self
+    }
+
+    pub fn with_rolling_means(mut self) -> Self {
+        self.include_rolling_means = true;
+        self
+    }
+
+    // durations are written as a whole number of nanoseconds so they read back as they were
+    fn format(&self, value: &AnyType, column_name: &str) -> Result<String> {
+        let text = match (value, self.float_precision) {
+            (AnyType::Null, _) => self.null_value.clone(),
+            (AnyType::Float32(value), Some(precision)) => format!("{:.*}", precision, value),
+            (AnyType::Float64(value), Some(precision)) => format!("{:.*}", precision, value),
+            (AnyType::Duration(value), _) => value.num_nanoseconds().ok_or_else(|| DataFrameError::Overflow(column_name.to_string()))?.to_string(),
+            (value, _) => value.to_string(),
+        };
+        Ok(text)
+    }
+}
+
+pub fn read_path<P: AsRef<Path>>(path: P, options: &CsvReadOptions) -> Result<DataFrame> {
+    read(File::open(path)?, options)
+}
+
+// columns without a type in the schema are read as the narrowest of bool, i64, f64 and str holding every value
+pub fn read<R: Read>(reader: R, options: &CsvReadOptions) -> Result<DataFrame> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .delimiter(options.delimiter)
+        .quote(options.quote)
+        .escape(options.escape)
+        .double_quote(options.escape.is_none())
+        .from_reader(reader);
+    let mut records = vec![];
+    for record in reader.records() {
+        let record = record.map_err(from_csv_error)?;
+        let line = record.position().map(|position| position.line()).unwrap_or_default();
+        records.push((line, record.iter().map(str::to_string).collect::<Vec<String>>()));
+    }
+
+    let is_header = |fields: &Vec<String>| fields.iter().all(|field| !options.null_values.contains(field) && infer_dtype(field) == DataType::Utf8);
+    let names: Vec<String> = match records.first() {
+        Some((_, fields)) if options.has_header.unwrap_or_else(|| is_header(fields)) => records.remove(0).1,
+        Some((_, fields)) => (1..=fields.len()).map(|index| format!("column_{}", index)).collect(),
+        None => vec![],
+    };
+    let is_null = |text: &str| options.null_values.iter().any(|null_value| null_value == text);
+    let records: Vec<(u64, Vec<RawValue>)> = records.into_iter()
+        .map(|(line, texts)| {
+            let values = texts.into_iter()
+                .map(|text| match is_null(&text) {
+                    true => None,
+                    false => {
+                        let dtype = infer_dtype(&text);
+                        Some((text, dtype))
+                    },
+                })
+                .collect();
+            (line, values)
+        })
+        .collect();
+    build_frame(&names, &records, options.schema.as_ref(), options.timestamp_column.as_deref())
+}
+
+// values are written with their Display other than durations, timestamps in rfc 3339
+pub fn write<W: Write>(writer: W, dataframe: &DataFrame, options: &CsvWriteOptions) -> Result<()> {
+    let quote_style = match options.quote_style {
+        QuoteStyle::Necessary => csv::QuoteStyle::Necessary,
+        QuoteStyle::Always => csv::QuoteStyle::Always,
+        QuoteStyle::NonNumeric => csv::QuoteStyle::NonNumeric,
+        QuoteStyle::Never => csv::QuoteStyle::Never,
+    };
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(options.delimiter)
+        .quote(options.quote)
+        .quote_style(quote_style)
+        .from_writer(writer);
+    let columns = dataframe.get_columns();
+    let with_rolling_mean = |column: &Column| options.include_rolling_means && column.has_rolling_mean();
+
+    if options.has_header {
+        let mut header: Vec<String> = vec![];
+        if options.include_timestamp {
+            header.push("timestamp".to_string());
+        }
+        for column in columns.iter() {
+            header.push(column.name.to_string());
+            if with_rolling_mean(column) {
+                header.push(format!("{}_rolling_mean", column.name));
+            }
+        }
+        writer.write_record(&header).map_err(from_csv_error)?;
+    }
+    for (row_index, datetime) in dataframe.get_datetimes().iter().enumerate() {
+        let mut record: Vec<String> = vec![];
+        if options.include_timestamp {
+            record.push(datetime.to_rfc3339());
+        }
+        for column in columns.iter() {
+            record.push(options.format(&column.get_value(row_index).unwrap_or(AnyType::Null), &column.name)?);
+            if with_rolling_mean(column) {
+                record.push(options.format(&column.get_rolling_mean(row_index).unwrap_or(AnyType::Null), &column.name)?);
+            }
+        }
+        writer.write_record(&record).map_err(from_csv_error)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn from_csv_error(error: csv::Error) -> DataFrameError {
+    match error.position() {
+        Some(position) => DataFrameError::Parse { line: position.line(), message: error.to_string() },
+        None => DataFrameError::Io(error.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::test_helpers::{
+        start,
+        values,
+    };
+    use crate::schema::Field;
+    use chrono::{
+        NaiveDate,
+        NaiveTime,
+    };
+
+    #[test]
+    fn infers_types() {
+        let text = "ticker,price,size,active,note,empty\nvod,70.5,100,true,,\naapl,180,200,false,\"big, \"\"block\"\"\",\n";
+        let dataframe = read(text.as_bytes(), &CsvReadOptions::default()).unwrap();
+        let dtypes: Vec<DataType> = dataframe.get_columns().iter().map(|column| column.dtype()).collect();
+        assert_eq!(dtypes, vec![DataType::Utf8, DataType::Float64, DataType::Int64, DataType::Boolean, DataType::Utf8, DataType::Null]);
+        assert_eq!(values(&dataframe, "price"), vec![70.5f64.into(), 180f64.into()]);
+        assert_eq!(values(&dataframe, "note"), vec![AnyType::Null, "big, \"block\"".into()]);
+        assert_eq!(values(&dataframe, "empty"), vec![AnyType::Null, AnyType::Null]);
+    }
+
+    #[test]
+    fn options() {
+        // the first line holds a number so is not taken as a header
+        let text = "vod;1\n'a;b';NA\n";
+        let dataframe = read(text.as_bytes(), &CsvReadOptions::default().with_delimiter(b';').with_quote(b'\'').with_null_values(vec!["NA".to_string()])).unwrap();
+        assert_eq!(values(&dataframe, "column_1"), vec!["vod".into(), "a;b".into()]);
+        assert_eq!(values(&dataframe, "column_2"), vec![1i64.into(), AnyType::Null]);
+
+        let text = "name\n\"say \\\"hi\\\"\"\n";
+        let dataframe = read(text.as_bytes(), &CsvReadOptions::default().with_escape(b'\\')).unwrap();
+        assert_eq!(values(&dataframe, "name"), vec!["say \"hi\"".into()]);
+        let dataframe = read(text.as_bytes(), &CsvReadOptions::default().with_header(false)).unwrap();
+        assert_eq!(dataframe.len(), 2);
+    }
+
+    #[test]
+    fn schema_and_timestamps() {
+        let text = "time,ticker,size\n2024-01-02T08:00:00Z,vod,100\n2024-01-02 08:00:05,aapl,\n";
+        let schema = Schema::new(vec![Field::new("size", DataType::UInt32, true)]);
+        let dataframe = read(text.as_bytes(), &CsvReadOptions::default().with_schema(schema).with_timestamp_column("time")).unwrap();
+        assert!(dataframe.get_column_by_name("time").is_err());
+        assert_eq!(values(&dataframe, "size"), vec![AnyType::UInt32(100), AnyType::Null]);
+        let start = start();
+        assert_eq!(dataframe.get_datetimes(), &vec![start, start + chrono::Duration::seconds(5)]);
+
+        let schema = Schema::new(vec![Field::new("size", DataType::UInt8, false)]);
+        assert_eq!(
+            read(text.as_bytes(), &CsvReadOptions::default().with_schema(schema)).unwrap_err(),
+            DataFrameError::Parse { line: 3, message: "column 'size' does not allow nulls".to_string() }
+        );
+        let schema = Schema::new(vec![Field::new("ticker", DataType::Int64, true)]);
+        assert_eq!(
+            read(text.as_bytes(), &CsvReadOptions::default().with_schema(schema)).unwrap_err(),
+            DataFrameError::Parse { line: 2, message: "'vod' in column 'ticker' is not a i64".to_string() }
+        );
+        assert_eq!(
+            read(text.as_bytes(), &CsvReadOptions::default().with_timestamp_column("ticker")).unwrap_err(),
+            DataFrameError::Parse { line: 2, message: "'vod' is not a timestamp".to_string() }
+        );
+        assert_eq!(read(text.as_bytes(), &CsvReadOptions::default().with_timestamp_column("when")).unwrap_err(), DataFrameError::UnknownColumn("when".to_string()));
+    }
+
+    #[test]
+    fn write_csv() {
+        let start = start();
+        let mut dataframe = DataFrame::new(vec!["ticker", "price"]).unwrap();
+        dataframe.update_column_rolling_mean("price", crate::column::RollingMean::new(true, Some(2))).unwrap();
+        for (seconds, ticker, price) in [(0, Some("vod"), Some(70.125f64)), (5, None, Some(71.0)), (10, Some("a,b"), None)] {
+            dataframe.add_row_at(start + chrono::Duration::seconds(seconds), vec![ticker.into(), price.into()]).unwrap();
+        }
+
+        let mut written = vec![];
+        write(&mut written, &dataframe, &CsvWriteOptions::default()).unwrap();
+        assert_eq!(String::from_utf8(written).unwrap(), "ticker,price\nvod,70.125\n,71\n\"a,b\",\n");
+
+        let options = CsvWriteOptions::default()
+            .with_delimiter(b';')
+            .with_null_value("NA")
+            .with_float_precision(2)
+            .with_quote_style(QuoteStyle::NonNumeric)
+            .with_timestamp()
+            .with_rolling_means();
+        let mut written = vec![];
+        write(&mut written, &dataframe, &options).unwrap();
+        assert_eq!(
+            String::from_utf8(written).unwrap(),
+            "\"timestamp\";\"ticker\";\"price\";\"price_rolling_mean\"\n\"2024-01-02T08:00:00+00:00\";\"vod\";70.12;\"NA\"\n\"2024-01-02T08:00:05+00:00\";\"NA\";71.00;70.56\n\"2024-01-02T08:00:10+00:00\";\"a,b\";\"NA\";\"NA\"\n"
+        );
+
+        // what is written reads back as the same values
+        let mut written = vec![];
+        write(&mut written, &dataframe, &CsvWriteOptions::default().with_timestamp()).unwrap();
+        let read_back = read(written.as_slice(), &CsvReadOptions::default().with_timestamp_column("timestamp")).unwrap();
+        assert_eq!(values(&read_back, "ticker"), values(&dataframe, "ticker"));
+        assert_eq!(values(&read_back, "price"), values(&dataframe, "price"));
+        assert_eq!(read_back.get_datetimes(), dataframe.get_datetimes());
+
+        let mut dataframe = DataFrame::new(vec!["latency", "day", "at", "seen"]).unwrap();
+        for index in 0..3i64 {
+            dataframe.add_row(vec![
+                AnyType::Duration(chrono::Duration::nanoseconds(-index * 1_500_000_001)),
+                AnyType::Date(NaiveDate::from_ymd_opt(2024, 1, 2).unwrap() + chrono::Duration::days(index)),
+                AnyType::Time(NaiveTime::from_hms_nano_opt(8, 0, index as u32, 5).unwrap()),
+                AnyType::Datetime(start + chrono::Duration::milliseconds(index)),
+            ]).unwrap();
+        }
+        let mut written = vec![];
+        write(&mut written, &dataframe, &CsvWriteOptions::default()).unwrap();
+        assert!(String::from_utf8(written.clone()).unwrap().contains("\n-1500000001,"));
+        let read_back = read(written.as_slice(), &CsvReadOptions::default().with_schema(dataframe.schema())).unwrap();
+        for column in dataframe.get_columns().iter() {
+            assert_eq!(values(&read_back, &column.name), values(&dataframe, &column.name));
+        }
+    }
+
+    #[test]
+    fn malformed_lines() {
+        let text = "ticker,price\nvod,70\naapl,180,extra\n";
+        match read(text.as_bytes(), &CsvReadOptions::default()).unwrap_err() {
+            DataFrameError::Parse { line, .. } => assert_eq!(line, 3),
+            error => panic!("unexpected error {:?}", error),
+        }
+        assert!(matches!(read_path("missing.csv", &CsvReadOptions::default()), Err(DataFrameError::Io(_))));
+    }
+}
@@ -0,0 +1,293 @@
+use crate::cell::types::datatypes::{
+    AnyType,
+    DataType,
+};
+use crate::error::{
+    DataFrameError,
+    Result,
+};
+use crate::frame::DataFrame;
+use crate::io::{
+    build_frame,
+    RawValue,
+};
+use crate::schema::Schema;
+use serde_json::{
+    Map,
+    Value,
+};
+use std::io::{
+    BufRead,
+    BufReader,
+    Read,
+    Write,
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct JsonReadOptions {
+    // types for the named fields in place of inferring them, the other fields are still inferred
+    pub schema: Option<Schema>,
+    // a field of timestamps used as the time of each row rather than being read as a column
+    pub timestamp_field: Option<String>,
+}
+
+impl JsonReadOptions {
+    pub fn with_schema(mut self, schema: Schema) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    pub fn with_timestamp_field<S: Into<String>>(mut self, field_name: S) -> Self {
+        self.timestamp_field = Some(field_name.into());
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JsonOrientation {
+    // an array holding an object per row
+    #[default]
+    Records,
+    // an object holding an array of values per column
+    Columns,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct JsonWriteOptions {
+    // only used by write, ndjson always holds a record per line
+    pub orientation: JsonOrientation,
+    // writes the time of each row in rfc 3339 under this name
+    pub timestamp_field: Option<String>,
+}
+
+impl JsonWriteOptions {
+    pub fn with_orientation(mut self, orientation: JsonOrientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    pub fn with_timestamp_field<S: Into<String>>(mut self, field_name: S) -> Self {
+        self.timestamp_field = Some(field_name.into());
+        self
+    }
+}
+
+// an array of records, errors in a record give its position in the array as the line as the document may be on one
+pub fn read<R: Read>(reader: R, options: &JsonReadOptions) -> Result<DataFrame> {
+    let document: Value = serde_json::from_reader(reader).map_err(from_json_error)?;
+    let records = match document {
+        Value::Array(records) => records,
+        _ => return Err(DataFrameError::Parse { line: 1, message: "expected an array of records".to_string() }),
+    };
+    let records = records.into_iter()
+        .enumerate()
+        .map(|(index, record)| (index as u64 + 1, record))
+        .collect();
+    build(records, options)
+}
+
+// a record per line, blank lines are skipped
+pub fn read_ndjson<R: Read>(reader: R, options: &JsonReadOptions) -> Result<DataFrame> {
+    let mut records = vec![];
+    for (index, line) in BufReader::new(reader).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record = serde_json::from_str(&line).map_err(|error| DataFrameError::Parse { line: index as u64 + 1, message: error.to_string() })?;
+        records.push((index as u64 + 1, record));
+    }
+    build(records, options)
+}
+
+// json types map onto bool, i64, u64, f64 and str, with arrays and objects kept as their json text
+fn build(records: Vec<(u64, Value)>, options: &JsonReadOptions) -> Result<DataFrame> {
+    let mut names: Vec<String> = vec![];
+    let mut objects: Vec<(u64, Map<String, Value>)> = vec![];
+    for (line, record) in records.into_iter() {
+        let object = match record {
+            Value::Object(object) => object,
+            _ => return Err(DataFrameError::Parse { line, message: "expected a record".to_string() }),
+        };
+        for name in object.keys() {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+        objects.push((line, object));
+    }
+
+    let records: Vec<(u64, Vec<RawValue>)> = objects.into_iter()
+        .map(|(line, mut object)| {
+            let values = names.iter().map(|name| raw_value(object.remove(name).unwrap_or(Value::Null))).collect();
+            (line, values)
+        })
+        .collect();
+    build_frame(&names, &records, options.schema.as_ref(), options.timestamp_field.as_deref())
+}
+
+fn raw_value(value: Value) -> RawValue {
+    match value {
+        Value::Null => None,
+        Value::Bool(value) => Some((value.to_string(), DataType::Boolean)),
+        Value::Number(number) if number.is_i64() => Some((number.to_string(), DataType::Int64)),
+        Value::Number(number) if number.is_u64() => Some((number.to_string(), DataType::UInt64)),
+        Value::Number(number) => Some((number.to_string(), DataType::Float64)),
+        Value::String(value) => Some((value, DataType::Utf8)),
+        value => Some((value.to_string(), DataType::Utf8)),
+    }
+}
+
+pub fn write<W: Write>(mut writer: W, dataframe: &DataFrame, options: &JsonWriteOptions) -> Result<()> {
+    let document = match options.orientation {
+        JsonOrientation::Records => Value::Array((0..dataframe.len()).map(|row_index| Value::Object(record(dataframe, row_index, options))).collect()),
+        JsonOrientation::Columns => {
+            let mut columns = Map::new();
+            if let Some(name) = &options.timestamp_field {
+                columns.insert(name.clone(), dataframe.get_datetimes().iter().map(|datetime| Value::String(datetime.to_rfc3339())).collect());
+            }
+            for column in dataframe.get_columns().iter() {
+                columns.insert(column.name.to_string(), column.iter_values().map(|value| to_json(&value)).collect());
+            }
+            Value::Object(columns)
+        },
+    };
+    serde_json::to_writer(&mut writer, &document).map_err(from_json_error)?;
+    writer.flush()?;
+    Ok(())
+}
+
+// writes each row as it goes rather than building the whole document first
+pub fn write_ndjson<W: Write>(mut writer: W, dataframe: &DataFrame, options: &JsonWriteOptions) -> Result<()> {
+    for row_index in 0..dataframe.len() {
+        serde_json::to_writer(&mut writer, &record(dataframe, row_index, options)).map_err(from_json_error)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn record(dataframe: &DataFrame, row_index: usize, options: &JsonWriteOptions) -> Map<String, Value> {
+    let mut record = Map::new();
+    if let Some(name) = &options.timestamp_field {
+        record.insert(name.clone(), Value::String(dataframe.get_datetimes()[row_index].to_rfc3339()));
+    }
+    for column in dataframe.get_columns().iter() {
+        record.insert(column.name.to_string(), to_json(&column.get_value(row_index).unwrap_or(AnyType::Null)));
+    }
+    record
+}
+
+// NaN and infinite floats have no json number so are written as nulls, durations as a number of nanoseconds
+fn to_json(value: &AnyType) -> Value {
+    match value {
+        AnyType::Null => Value::Null,
+        AnyType::Boolean(value) => Value::from(*value),
+        AnyType::Utf8(value) => Value::from(&**value),
+        AnyType::UInt8(value) => Value::from(*value),
+        AnyType::UInt16(value) => Value::from(*value),
+        AnyType::UInt32(value) => Value::from(*value),
+        AnyType::UInt64(value) => Value::from(*value),
+        AnyType::USize(value) => Value::from(*value),
+        AnyType::Int8(value) => Value::from(*value),
+        AnyType::Int16(value) => Value::from(*value),
+        AnyType::Int32(value) => Value::from(*value),
+        AnyType::Int64(value) => Value::from(*value),
+        AnyType::ISize(value) => Value::from(*value),
+        AnyType::Float32(value) => serde_json::Number::from_f64(*value as f64).map(Value::Number).unwrap_or(Value::Null),
+        AnyType::Float64(value) => serde_json::Number::from_f64(*value).map(Value::Number).unwrap_or(Value::Null),
+        AnyType::Date(value) => Value::String(value.to_string()),
+        AnyType::Datetime(value) => Value::String(value.to_rfc3339()),
+        AnyType::Duration(value) => value.num_nanoseconds().map(Value::from).unwrap_or(Value::Null),
+        AnyType::Time(value) => Value::String(value.to_string()),
+    }
+}
+
+fn from_json_error(error: serde_json::Error) -> DataFrameError {
+    match error.is_io() {
+        true => DataFrameError::Io(error.to_string()),
+        false => DataFrameError::Parse { line: error.line() as u64, message: error.to_string() },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::test_helpers::{
+        start,
+        values,
+    };
+    use crate::schema::Field;
+
+    #[test]
+    fn read_records() {
+        let text = r#"[
+            {"ticker": "vod", "price": 70, "size": 100, "active": true, "tags": ["a"]},
+            {"ticker": null, "price": 70.5, "active": false, "venue": "lse"},
+            {"price": 18446744073709551615, "size": -5, "tags": {"b": 1}}
+        ]"#;
+        let dataframe = read(text.as_bytes(), &JsonReadOptions::default()).unwrap();
+        let names: Vec<String> = dataframe.get_columns().iter().map(|column| column.name.to_string()).collect();
+        assert_eq!(names, vec!["ticker", "price", "size", "active", "tags", "venue"]);
+        let dtypes: Vec<DataType> = dataframe.get_columns().iter().map(|column| column.dtype()).collect();
+        assert_eq!(dtypes, vec![DataType::Utf8, DataType::Float64, DataType::Int64, DataType::Boolean, DataType::Utf8, DataType::Utf8]);
+        assert_eq!(values(&dataframe, "ticker"), vec!["vod".into(), AnyType::Null, AnyType::Null]);
+        assert_eq!(values(&dataframe, "price"), vec![70f64.into(), 70.5f64.into(), 18446744073709551615f64.into()]);
+        assert_eq!(values(&dataframe, "tags"), vec!["[\"a\"]".into(), AnyType::Null, "{\"b\":1}".into()]);
+
+        assert_eq!(
+            read("{\"a\": 1}".as_bytes(), &JsonReadOptions::default()).unwrap_err(),
+            DataFrameError::Parse { line: 1, message: "expected an array of records".to_string() }
+        );
+        assert_eq!(
+            read("[{\"a\": 1}, 2]".as_bytes(), &JsonReadOptions::default()).unwrap_err(),
+            DataFrameError::Parse { line: 2, message: "expected a record".to_string() }
+        );
+    }
+
+    #[test]
+    fn read_ndjson_lines() {
+        let text = "{\"time\": \"2024-01-02T08:00:00Z\", \"ticker\": \"vod\", \"size\": 100}\n\n{\"time\": 1704182405000, \"ticker\": \"aapl\", \"size\": null}\n";
+        let schema = Schema::new(vec![Field::new("size", DataType::UInt32, true)]);
+        let dataframe = read_ndjson(text.as_bytes(), &JsonReadOptions::default().with_schema(schema).with_timestamp_field("time")).unwrap();
+        assert!(dataframe.get_column_by_name("time").is_err());
+        assert_eq!(values(&dataframe, "size"), vec![AnyType::UInt32(100), AnyType::Null]);
+        let start = start();
+        assert_eq!(dataframe.get_datetimes(), &vec![start, start + chrono::Duration::seconds(5)]);
+
+        match read_ndjson("{\"a\": 1}\n{\"a\": }\n".as_bytes(), &JsonReadOptions::default()).unwrap_err() {
+            DataFrameError::Parse { line, .. } => assert_eq!(line, 2),
+            error => panic!("unexpected error {:?}", error),
+        }
+        assert_eq!(
+            read_ndjson("{\"a\": 1}\n{\"a\": \"x\"}\n".as_bytes(), &JsonReadOptions::default().with_schema(Schema::new(vec![Field::new("a", DataType::Int64, true)]))).unwrap_err(),
+            DataFrameError::Parse { line: 2, message: "'x' in column 'a' is not a i64".to_string() }
+        );
+    }
+
+    #[test]
+    fn write_orientations() {
+        let start = start();
+        let mut dataframe = DataFrame::new(vec!["ticker", "price"]).unwrap();
+        dataframe.add_row_at(start, vec!["vod".into(), 70.5f64.into()]).unwrap();
+        dataframe.add_row_at(start, vec![AnyType::Null, f64::NAN.into()]).unwrap();
+
+        let mut written = vec![];
+        write(&mut written, &dataframe, &JsonWriteOptions::default()).unwrap();
+        assert_eq!(String::from_utf8(written).unwrap(), r#"[{"ticker":"vod","price":70.5},{"ticker":null,"price":null}]"#);
+
+        let mut written = vec![];
+        write(&mut written, &dataframe, &JsonWriteOptions::default().with_orientation(JsonOrientation::Columns).with_timestamp_field("time")).unwrap();
+        assert_eq!(
+            String::from_utf8(written).unwrap(),
+            r#"{"time":["2024-01-02T08:00:00+00:00","2024-01-02T08:00:00+00:00"],"ticker":["vod",null],"price":[70.5,null]}"#
+        );
+
+        let mut written = vec![];
+        write_ndjson(&mut written, &dataframe, &JsonWriteOptions::default().with_timestamp_field("time")).unwrap();
+        let read_back = read_ndjson(written.as_slice(), &JsonReadOptions::default().with_timestamp_field("time")).unwrap();
+        assert_eq!(values(&read_back, "ticker"), values(&dataframe, "ticker"));
+        assert_eq!(values(&read_back, "price"), vec![70.5f64.into(), AnyType::Null]);
+        assert_eq!(read_back.get_datetimes(), dataframe.get_datetimes());
+    }
+}
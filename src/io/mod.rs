@@ -0,0 +1,205 @@
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod csv;
+pub mod json;
+#[cfg(feature = "parquet")]
+pub mod parquet;
+pub mod snapshot;
+
+use crate::cell::types::datatypes::{
+    AnyType,
+    DataType,
+};
+use crate::error::{
+    DataFrameError,
+    Result,
+};
+use crate::frame::DataFrame;
+use crate::schema::{
+    Field,
+    Schema,
+};
+use chrono::{
+    DateTime,
+    NaiveDate,
+    NaiveDateTime,
+    NaiveTime,
+    Utc,
+};
+use std::rc::Rc;
+
+// a value read from a file as its text and the type it looks to be, None for a null
+pub type RawValue = Option<(String, DataType)>;
+
+// a frame of the values read from each line of a file. columns without a type in the schema take the widest type
+// of their values and a column of nulls is left without a type. the timestamp column is used as the time of each
+// row rather than being read as a column, whole numbers in it are milliseconds since the epoch. without one rows are
+// stamped as they are added
+pub fn build_frame(names: &[String], records: &[(u64, Vec<RawValue>)], schema: Option<&Schema>, timestamp_column: Option<&str>) -> Result<DataFrame> {
+    let timestamp_index = match timestamp_column {
+        Some(name) => Some(names.iter().position(|column_name| column_name == name).ok_or_else(|| DataFrameError::UnknownColumn(name.to_string()))?),
+        None => None,
+    };
+    if let Some(field) = schema.and_then(|schema| schema.get_fields().iter().find(|field| !names.iter().any(|name| **name == *field.name))) {
+        return Err(DataFrameError::UnknownColumn(field.name.to_string()));
+    }
+
+    let fields: Vec<(usize, Field)> = names.iter()
+        .enumerate()
+        .filter(|(index, _)| Some(*index) != timestamp_index)
+        .map(|(index, name)| {
+            let field = match schema.and_then(|schema| schema.get_field(name)) {
+                Some(field) => field.clone(),
+                None => {
+                    let dtype = records.iter()
+                        .filter_map(|(_, values)| values[index].as_ref())
+                        .fold(DataType::Null, |dtype, (_, found)| widen(dtype, *found));
+                    Field::new(name.as_str(), dtype, true)
+                },
+            };
+            (index, field)
+        })
+        .collect();
+
+    let mut dataframe = DataFrame::with_schema(Schema::new(fields.iter().map(|(_, field)| field.clone()).collect()))?;
+    for (line, raw_values) in records.iter() {
+        let mut values = Vec::with_capacity(fields.len());
+        for (index, field) in fields.iter() {
+            let value = match &raw_values[*index] {
+                None if !field.nullable => return Err(DataFrameError::Parse { line: *line, message: format!("column '{}' does not allow nulls", field.name) }),
+                None => AnyType::Null,
+                Some((text, _)) => parse_value(text, field.dtype).ok_or_else(|| DataFrameError::Parse {
+                    line: *line,
+                    message: format!("'{}' in column '{}' is not a {}", text, field.name, field.dtype),
+                })?,
+            };
+            values.push(value);
+        }
+        match timestamp_index {
+            Some(index) => {
+                let datetime = match &raw_values[index] {
+                    Some((text, DataType::Int64)) => text.parse().ok().and_then(DateTime::from_timestamp_millis),
+                    Some((text, _)) => parse_datetime(text),
+                    None => None,
+                };
+                let datetime = datetime.ok_or_else(|| DataFrameError::Parse {
+                    line: *line,
+                    message: format!("'{}' is not a timestamp", raw_values[index].as_ref().map(|(text, _)| text.as_str()).unwrap_or("null")),
+                })?;
+                dataframe.add_row_at(datetime, values)?
+            },
+            None => dataframe.add_row(values)?,
+        };
+    }
+    Ok(dataframe)
+}
+
+// the narrowest of bool, i64, f64 and str that the text can be read as
+pub fn infer_dtype(text: &str) -> DataType {
+    if parse_bool(text).is_some() {
+        DataType::Boolean
+    } else if text.parse::<i64>().is_ok() {
+        DataType::Int64
+    } else if text.parse::<f64>().is_ok() {
+        DataType::Float64
+    } else {
+        DataType::Utf8
+    }
+}
+
+// the type able to hold values of both types, integers widen to floats and anything else falls back to strings
+pub fn widen(current: DataType, found: DataType) -> DataType {
+    match (current, found) {
+        (DataType::Null, found) => found,
+        (current, DataType::Null) => current,
+        (current, found) if current == found => current,
+        (current, found) if current.is_numeric() && found.is_numeric() => DataType::Float64,
+        _ => DataType::Utf8,
+    }
+}
+
+// reads text as a value of the given type, None if it is not one
+pub fn parse_value(text: &str, dtype: DataType) -> Option<AnyType> {
+    let value = match dtype {
+        DataType::Null => return None,
+        DataType::Boolean => AnyType::Boolean(parse_bool(text)?),
+        DataType::Utf8 => AnyType::Utf8(Rc::from(text)),
+        DataType::UInt8 => AnyType::UInt8(text.parse().ok()?),
+        DataType::UInt16 => AnyType::UInt16(text.parse().ok()?),
+        DataType::UInt32 => AnyType::UInt32(text.parse().ok()?),
+        DataType::UInt64 => AnyType::UInt64(text.parse().ok()?),
+        DataType::USize => AnyType::USize(text.parse().ok()?),
+        DataType::Int8 => AnyType::Int8(text.parse().ok()?),
+        DataType::Int16 => AnyType::Int16(text.parse().ok()?),
+        DataType::Int32 => AnyType::Int32(text.parse().ok()?),
+        DataType::Int64 => AnyType::Int64(text.parse().ok()?),
+        DataType::ISize => AnyType::ISize(text.parse().ok()?),
+        DataType::Float32 => AnyType::Float32(text.parse().ok()?),
+        DataType::Float64 => AnyType::Float64(text.parse().ok()?),
+        DataType::Date => AnyType::Date(NaiveDate::parse_from_str(text, "%Y-%m-%d").ok()?),
+        DataType::Datetime => AnyType::Datetime(parse_datetime(text)?),
+        // durations are written as a whole number of nanoseconds
+        DataType::Duration => AnyType::Duration(chrono::Duration::nanoseconds(text.parse().ok()?)),
+        DataType::Time => AnyType::Time(NaiveTime::parse_from_str(text, "%H:%M:%S%.f").ok()?),
+    };
+    Some(value)
+}
+
+// rfc 3339, or a date and time without an offset which is taken to be utc
+pub fn parse_datetime(text: &str) -> Option<DateTime<Utc>> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(text) {
+        return Some(datetime.with_timezone(&Utc));
+    }
+    ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"].iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+        .map(|datetime| datetime.and_utc())
+}
+
+fn parse_bool(text: &str) -> Option<bool> {
+    match text {
+        "true" | "True" | "TRUE" => Some(true),
+        "false" | "False" | "FALSE" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_helpers {
+    use crate::cell::types::datatypes::AnyType;
+    use crate::frame::DataFrame;
+    use chrono::{
+        DateTime,
+        Utc,
+    };
+
+    // the values of a column in row order
+    pub fn values(dataframe: &DataFrame, name: &str) -> Vec<AnyType> {
+        dataframe.get_column_by_name(name).unwrap().iter_values().collect()
+    }
+
+    // the time the rows of the reader tests start from
+    pub fn start() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2024-01-02T08:00:00Z").unwrap().with_timezone(&Utc)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn infer_and_parse() {
+        assert_eq!(infer_dtype("TRUE"), DataType::Boolean);
+        assert_eq!(infer_dtype("-12"), DataType::Int64);
+        assert_eq!(infer_dtype("1e3"), DataType::Float64);
+        assert_eq!(infer_dtype("12a"), DataType::Utf8);
+        assert_eq!(widen(DataType::Int64, DataType::Float64), DataType::Float64);
+        assert_eq!(widen(DataType::Null, DataType::Boolean), DataType::Boolean);
+        assert_eq!(widen(DataType::Boolean, DataType::Int64), DataType::Utf8);
+
+        assert_eq!(parse_value("300", DataType::UInt8), None);
+        assert_eq!(parse_value("2024-01-02", DataType::Date), Some(AnyType::Date(NaiveDate::from_ymd_opt(2024, 1, 2).unwrap())));
+        assert_eq!(parse_value("1500", DataType::Duration), Some(AnyType::Duration(chrono::Duration::nanoseconds(1500))));
+        assert_eq!(parse_datetime("2024-01-02 08:00:00"), parse_datetime("2024-01-02T09:00:00+01:00"));
+        assert!(parse_datetime("yesterday").is_none());
+    }
+}
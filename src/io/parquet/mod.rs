@@ -0,0 +1,298 @@
+use crate::error::{
+    DataFrameError,
+    Result,
+};
+use crate::frame::DataFrame;
+use crate::io::arrow::{
+    from_arrow_error,
+    from_record_batch,
+    to_record_batch,
+    DTYPE_METADATA,
+};
+use ::arrow::array::ArrayRef;
+use ::arrow::compute::{
+    cast,
+    concat_batches,
+};
+use ::arrow::datatypes::{
+    DataType as ArrowDataType,
+    Field as ArrowField,
+    Schema as ArrowSchema,
+    TimeUnit,
+};
+use ::arrow::error::ArrowError;
+use ::arrow::record_batch::RecordBatch;
+use ::parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use ::parquet::arrow::{
+    ArrowWriter,
+    ProjectionMask,
+};
+use ::parquet::basic::{
+    Compression,
+    GzipLevel,
+    ZstdLevel,
+};
+use ::parquet::errors::ParquetError;
+use ::parquet::file::properties::WriterProperties;
+use ::parquet::file::reader::ChunkReader;
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParquetCompression {
+    Uncompressed,
+    Snappy,
+    Gzip,
+    Zstd,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ParquetReadOptions {
+    // only these columns are read, None reads them all
+    pub columns: Option<Vec<String>>,
+    // None, the default, stamps the rows with the time they are read at
+    pub timestamp_column: Option<String>,
+}
+
+impl ParquetReadOptions {
+    pub fn with_columns<S: Into<String>>(mut self, columns: Vec<S>) -> Self {
+        self.columns = Some(columns.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn with_timestamp_column<S: Into<String>>(mut self, column_name: Option<S>) -> Self {
+        self.timestamp_column = column_name.map(Into::into);
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ParquetWriteOptions {
+    // the most rows held in each row group
+    pub row_group_size: usize,
+    pub compression: ParquetCompression,
+    // None, the default, leaves out the time of each row
+    pub timestamp_column: Option<String>,
+}
+
+impl Default for ParquetWriteOptions {
+    fn default() -> Self {
+        Self {
+            row_group_size: 1024 * 1024,
+            compression: ParquetCompression::Snappy,
+            timestamp_column: None,
+        }
+    }
+}
+
+impl ParquetWriteOptions {
+    pub fn with_row_group_size(mut self, row_group_size: usize) -> Self {
+        self.row_group_size = row_group_size;
+        self
+    }
+
+    pub fn with_compression(mut self, compression: ParquetCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    pub fn with_timestamp_column<S: Into<String>>(mut self, column_name: Option<S>) -> Self {
+        self.timestamp_column = column_name.map(Into::into);
+        self
+    }
+}
+
+// the arrow schema is stored in the file alongside the data so every type is read back as it was written
+pub fn write<W: Write + Send>(writer: W, dataframe: &DataFrame, options: &ParquetWriteOptions) -> Result<()> {
+    if options.row_group_size == 0 {
+        return Err(DataFrameError::InvalidArgument("the row group size has to be at least 1".to_string()));
+    }
+    let compression = match options.compression {
+        ParquetCompression::Uncompressed => Compression::UNCOMPRESSED,
+        ParquetCompression::Snappy => Compression::SNAPPY,
+        ParquetCompression::Gzip => Compression::GZIP(GzipLevel::default()),
+        ParquetCompression::Zstd => Compression::ZSTD(ZstdLevel::default()),
+    };
+    let properties = WriterProperties::builder()
+        .set_max_row_group_size(options.row_group_size)
+        .set_compression(compression)
+        .build();
+    let batch = encode_durations(&to_record_batch(dataframe, options.timestamp_column.as_deref())?)?;
+    let mut writer = ArrowWriter::try_new(writer, batch.schema(), Some(properties)).map_err(from_parquet_error)?;
+    writer.write(&batch).map_err(from_parquet_error)?;
+    writer.close().map_err(from_parquet_error)?;
+    Ok(())
+}
+
+// the timestamp column is always read when there is one, whether or not it is among the projected columns
+pub fn read<R: ChunkReader + 'static>(reader: R, options: &ParquetReadOptions) -> Result<DataFrame> {
+    let builder = ParquetRecordBatchReaderBuilder::try_new(reader).map_err(from_parquet_error)?;
+    let schema = builder.schema().clone();
+    let builder = match &options.columns {
+        Some(columns) => {
+            let mut indices = vec![];
+            for name in columns.iter().chain(options.timestamp_column.iter()) {
+                let index = schema.index_of(name).map_err(|_| DataFrameError::UnknownColumn(name.to_string()))?;
+                if !indices.contains(&index) {
+                    indices.push(index);
+                }
+            }
+            let mask = ProjectionMask::roots(builder.parquet_schema(), indices);
+            builder.with_projection(mask)
+        },
+        None => builder,
+    };
+    let reader = builder.build().map_err(from_parquet_error)?;
+    let schema = ::arrow::record_batch::RecordBatchReader::schema(&reader);
+    let batches = reader.collect::<std::result::Result<Vec<RecordBatch>, ArrowError>>().map_err(|error| DataFrameError::Io(error.to_string()))?;
+    let batch = concat_batches(&schema, &batches).map_err(from_arrow_error)?;
+    from_record_batch(&decode_durations(&batch)?, options.timestamp_column.as_deref())
+}
+
+// parquet has no duration type so durations are written as nanoseconds with their type kept in the field metadata
+fn encode_durations(batch: &RecordBatch) -> Result<RecordBatch> {
+    let duration = ArrowDataType::Duration(TimeUnit::Nanosecond);
+    let mut fields = Vec::with_capacity(batch.num_columns());
+    let mut arrays = Vec::with_capacity(batch.num_columns());
+    for (field, array) in batch.schema().fields().iter().zip(batch.columns()) {
+        if *field.data_type() == duration {
+            let metadata = HashMap::from([(DTYPE_METADATA.to_string(), "duration".to_string())]);
+            fields.push(field.as_ref().clone().with_data_type(ArrowDataType::Int64).with_metadata(metadata));
+            arrays.push(cast(array, &ArrowDataType::Int64).map_err(from_arrow_error)?);
+        } else {
+            fields.push(field.as_ref().clone());
+            arrays.push(array.clone());
+        }
+    }
+    rebuild(fields, arrays)
+}
+
+fn decode_durations(batch: &RecordBatch) -> Result<RecordBatch> {
+    let duration = ArrowDataType::Duration(TimeUnit::Nanosecond);
+    let mut fields = Vec::with_capacity(batch.num_columns());
+    let mut arrays = Vec::with_capacity(batch.num_columns());
+    for (field, array) in batch.schema().fields().iter().zip(batch.columns()) {
+        let is_duration = *field.data_type() == ArrowDataType::Int64 && field.metadata().get(DTYPE_METADATA).map(String::as_str) == Some("duration");
+        if is_duration {
+            fields.push(field.as_ref().clone().with_data_type(duration.clone()).with_metadata(HashMap::new()));
+            arrays.push(cast(array, &duration).map_err(from_arrow_error)?);
+        } else {
+            fields.push(field.as_ref().clone());
+            arrays.push(array.clone());
+        }
+    }
+    rebuild(fields, arrays)
+}
+
+fn rebuild(fields: Vec<ArrowField>, arrays: Vec<ArrayRef>) -> Result<RecordBatch> {
+    RecordBatch::try_new(Arc::new(ArrowSchema::new(fields)), arrays).map_err(from_arrow_error)
+}
+
+fn from_parquet_error(error: ParquetError) -> DataFrameError {
+    DataFrameError::Io(error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::test_helpers::{
+        start,
+        values,
+    };
+    use crate::cell::types::datatypes::AnyType;
+    use chrono::NaiveTime;
+    use std::fs::File;
+
+    fn prices() -> DataFrame {
+        let start = start() + chrono::Duration::nanoseconds(1);
+        let mut dataframe = DataFrame::new(vec!["ticker", "price", "size", "latency", "at", "empty"]).unwrap();
+        for index in 0..10i64 {
+            let ticker = match index % 3 {
+                0 => AnyType::Null,
+                _ => "vod".into(),
+            };
+            let row = vec![
+                ticker,
+                (70.0 + index as f64 / 4.0).into(),
+                AnyType::USize(index as usize * 100),
+                AnyType::Duration(chrono::Duration::nanoseconds(index * 7)),
+                AnyType::Time(NaiveTime::from_hms_nano_opt(8, 0, index as u32, 5).unwrap()),
+                AnyType::Null,
+            ];
+            dataframe.add_row_at(start + chrono::Duration::milliseconds(index * 250), row).unwrap();
+        }
+        dataframe
+    }
+
+    fn round_trip(dataframe: &DataFrame, write_options: &ParquetWriteOptions, read_options: &ParquetReadOptions) -> (DataFrame, usize) {
+        let path = std::env::temp_dir().join(format!("dataframe-{}-{:?}.parquet", std::process::id(), std::thread::current().id()));
+        write(File::create(&path).unwrap(), dataframe, write_options).unwrap();
+        let row_groups = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap()).unwrap().metadata().num_row_groups();
+        let read_back = read(File::open(&path).unwrap(), read_options);
+        std::fs::remove_file(&path).unwrap();
+        (read_back.unwrap(), row_groups)
+    }
+
+    #[test]
+    fn round_trips() {
+        let dataframe = prices();
+        for compression in [ParquetCompression::Uncompressed, ParquetCompression::Snappy, ParquetCompression::Gzip, ParquetCompression::Zstd] {
+            let options = ParquetWriteOptions::default().with_compression(compression).with_row_group_size(4).with_timestamp_column(Some("timestamp"));
+            let (read_back, row_groups) = round_trip(&dataframe, &options, &ParquetReadOptions::default().with_timestamp_column(Some("timestamp")));
+            assert_eq!(row_groups, 3);
+            assert_eq!(read_back.schema(), dataframe.schema());
+            assert_eq!(read_back.get_datetimes(), dataframe.get_datetimes());
+            for column in dataframe.get_columns().iter() {
+                assert_eq!(values(&read_back, &column.name), column.iter_values().collect::<Vec<AnyType>>());
+            }
+        }
+    }
+
+    #[test]
+    fn projection() {
+        let dataframe = prices();
+        let write_options = ParquetWriteOptions::default().with_timestamp_column(Some("timestamp"));
+        let read_options = ParquetReadOptions::default().with_timestamp_column(Some("timestamp")).with_columns(vec!["size", "ticker"]);
+        let (read_back, _) = round_trip(&dataframe, &write_options, &read_options);
+        let names: Vec<String> = read_back.get_columns().iter().map(|column| column.name.to_string()).collect();
+        assert_eq!(names, vec!["ticker", "size"]);
+        assert_eq!(read_back.get_datetimes(), dataframe.get_datetimes());
+
+        let (read_back, _) = round_trip(&dataframe, &ParquetWriteOptions::default(), &ParquetReadOptions::default().with_columns(vec!["price"]));
+        assert_eq!(values(&read_back, "price"), values(&dataframe, "price"));
+
+        let path = std::env::temp_dir().join(format!("dataframe-{}-projection.parquet", std::process::id()));
+        write(File::create(&path).unwrap(), &dataframe, &ParquetWriteOptions::default()).unwrap();
+        let error = read(File::open(&path).unwrap(), &ParquetReadOptions::default().with_columns(vec!["missing"])).unwrap_err();
+        assert_eq!(error, DataFrameError::UnknownColumn("missing".to_string()));
+        let error = read(File::open(&path).unwrap(), &ParquetReadOptions::default().with_timestamp_column(Some("timestamp"))).unwrap_err();
+        assert_eq!(error, DataFrameError::UnknownColumn("timestamp".to_string()));
+        assert_eq!(
+            write(vec![], &dataframe, &ParquetWriteOptions::default().with_row_group_size(0)).unwrap_err(),
+            DataFrameError::InvalidArgument("the row group size has to be at least 1".to_string())
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn default_options() {
+        // a frame with a column of its own named timestamp is written and read back as it is by default
+        let start = start();
+        let mut dataframe = DataFrame::new(vec!["timestamp", "price"]).unwrap();
+        dataframe.add_row_at(start, vec![AnyType::Datetime(start), 70.5f64.into()]).unwrap();
+        dataframe.add_row_at(start, vec![AnyType::Null, 71f64.into()]).unwrap();
+        let (read_back, _) = round_trip(&dataframe, &ParquetWriteOptions::default(), &ParquetReadOptions::default());
+        assert_eq!(read_back.schema(), dataframe.schema());
+        assert_eq!(values(&read_back, "timestamp"), values(&dataframe, "timestamp"));
+        assert_eq!(
+            write(vec![], &dataframe, &ParquetWriteOptions::default().with_timestamp_column(Some("timestamp"))).unwrap_err(),
+            DataFrameError::DuplicateColumn("timestamp".to_string())
+        );
+
+        // as is a file written elsewhere without one
+        let (read_back, _) = round_trip(&prices(), &ParquetWriteOptions::default(), &ParquetReadOptions::default());
+        assert_eq!(read_back.len(), 10);
+        assert_eq!(values(&read_back, "size"), values(&prices(), "size"));
+    }
+}
@@ -0,0 +1,929 @@
+use crate::cell::types::datatypes::{
+    AnyType,
+    DataType,
+};
+use crate::column::buffer::TypedBuffer;
+use crate::column::rolling::{
+    RollingAggregation,
+    RollingWindow,
+    WindowAlignment,
+    WindowSpan,
+};
+use crate::column::{
+    Column,
+    Returns,
+    ReturnsKind,
+    RollingMean,
+};
+use crate::error::{
+    DataFrameError,
+    Result,
+};
+use crate::frame::DataFrame;
+use crate::schema::Field;
+use chrono::{
+    DateTime,
+    Datelike,
+    NaiveDate,
+    NaiveTime,
+    Timelike,
+    Utc,
+};
+use std::fs::File;
+use std::io::{
+    BufReader,
+    BufWriter,
+    ErrorKind,
+    Read,
+    Write,
+};
+use std::path::Path;
+use std::rc::Rc;
+
+// a snapshot is the header followed by sections, each with its own checksum, and ends with an empty end section.
+// all numbers are little endian
+//
+//   header:  magic (8) | version u16 | oldest reader version u16 | crc32 of the preceding 12 bytes
+//   section: tag u16 | flags u16 | payload length u64 | payload | crc32 of the tag, flags, length and payload
+//
+// readers skip sections with a tag they do not know unless the section is flagged as required, and ignore bytes
+// at the end of a section past what they understand, so later versions can add to the format without breaking
+// earlier readers. a snapshot that needs a newer reader says so in the header
+pub const MAGIC: [u8; 8] = *b"DFSNAP\r\n";
+pub const FORMAT_VERSION: u16 = 1;
+// the oldest reader able to read what this version writes
+pub const MIN_READER_VERSION: u16 = 1;
+
+const TAG_END: u16 = 0;
+const TAG_SCHEMA: u16 = 1;
+const TAG_DATETIMES: u16 = 2;
+const TAG_COLUMN: u16 = 3;
+const TAG_ROLLING_WINDOWS: u16 = 4;
+const TAG_ROLLING_RESULTS: u16 = 5;
+const FLAG_REQUIRED: u16 = 1;
+
+// the schema, the time of each row, the typed values and null bitmap of each column and the rolling mean,
+// returns and rolling windows set up on each column. returns are stored as the columns they were written to and
+// rolling results alongside their windows, so only the last window of each is replayed on load to carry on rolling.
+// a rolling window with a custom aggregation cannot be written as it is a closure, which is an error raised before
+// anything is written
+pub fn write<W: Write>(mut writer: W, dataframe: &DataFrame) -> Result<()> {
+    let columns = dataframe.get_columns();
+    let mut windows = vec![];
+    for (index, column) in columns.iter().enumerate() {
+        let mut section = Encoder::new();
+        section.put_u32(index as u32);
+        section.put_u32(column.get_rolling_windows().count() as u32);
+        for window in column.get_rolling_windows() {
+            put_rolling_window(&mut section, window)?;
+        }
+        windows.push(section);
+    }
+
+    let mut header = Encoder::new();
+    header.bytes.extend_from_slice(&MAGIC);
+    header.put_u16(FORMAT_VERSION);
+    header.put_u16(MIN_READER_VERSION);
+    let checksum = crc32(&header.bytes);
+    header.put_u32(checksum);
+    writer.write_all(&header.bytes)?;
+
+    let mut schema = Encoder::new();
+    schema.put_u32(columns.len() as u32);
+    for column in columns.iter() {
+        schema.put_str(&column.name);
+        schema.put_u8(dtype_code(column.dtype()));
+        schema.put_bool(column.is_nullable());
+        put_rolling_mean(&mut schema, &column.rolling_mean)?;
+        put_returns(&mut schema, &column.returns);
+    }
+    write_section(&mut writer, TAG_SCHEMA, FLAG_REQUIRED, &schema.bytes)?;
+
+    let mut datetimes = Encoder::new();
+    datetimes.put_u64(dataframe.len() as u64);
+    for datetime in dataframe.get_datetimes().iter() {
+        datetimes.put_datetime(datetime);
+    }
+    write_section(&mut writer, TAG_DATETIMES, FLAG_REQUIRED, &datetimes.bytes)?;
+
+    for (index, column) in columns.iter().enumerate() {
+        let mut values = Encoder::new();
+        values.put_u32(index as u32);
+        put_buffer(&mut values, column)?;
+        write_section(&mut writer, TAG_COLUMN, FLAG_REQUIRED, &values.bytes)?;
+    }
+
+    for ((index, column), section) in columns.iter().enumerate().zip(windows.iter()) {
+        if column.get_rolling_windows().next().is_some() {
+            write_section(&mut writer, TAG_ROLLING_WINDOWS, 0, &section.bytes)?;
+        }
+        if column.get_rolling_results().next().is_none() {
+            continue;
+        }
+        // the results of the rolling mean come first, then those of each window in order
+        let mut results = Encoder::new();
+        results.put_u32(index as u32);
+        results.put_u32(column.get_rolling_results().count() as u32);
+        for values in column.get_rolling_results() {
+            put_results(&mut results, values);
+        }
+        write_section(&mut writer, TAG_ROLLING_RESULTS, 0, &results.bytes)?;
+    }
+
+    write_section(&mut writer, TAG_END, FLAG_REQUIRED, &[])?;
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn read<R: Read>(mut reader: R) -> Result<DataFrame> {
+    let mut header = [0u8; 16];
+    read_exact(&mut reader, &mut header)?;
+    if header[..8] != MAGIC {
+        return Err(DataFrameError::Snapshot("not a dataframe snapshot".to_string()));
+    }
+    if crc32(&header[..12]) != u32::from_le_bytes([header[12], header[13], header[14], header[15]]) {
+        return Err(DataFrameError::Snapshot("header checksum does not match".to_string()));
+    }
+    let min_reader_version = u16::from_le_bytes([header[10], header[11]]);
+    if min_reader_version > FORMAT_VERSION {
+        return Err(DataFrameError::Snapshot(format!("written for version {} or later but this reader is version {}", min_reader_version, FORMAT_VERSION)));
+    }
+
+    let mut fields: Option<Vec<(Field, RollingMean, Returns)>> = None;
+    let mut datetimes: Option<Vec<DateTime<Utc>>> = None;
+    let mut columns: Vec<Option<Column>> = vec![];
+    let mut windows: Vec<(usize, RollingWindow)> = vec![];
+    let mut results: Vec<(usize, Vec<Vec<Option<f64>>>)> = vec![];
+    loop {
+        let (tag, flags, payload) = read_section(&mut reader)?;
+        let mut decoder = Decoder::new(&payload);
+        match tag {
+            TAG_END => break,
+            TAG_SCHEMA => {
+                let count = decoder.get_u32()? as usize;
+                let mut schema = Vec::with_capacity(count.min(payload.len()));
+                for _ in 0..count {
+                    let name = decoder.get_str()?;
+                    let dtype = from_dtype_code(decoder.get_u8()?)?;
+                    let nullable = decoder.get_bool()?;
+                    let rolling_mean = get_rolling_mean(&mut decoder)?;
+                    let returns = get_returns(&mut decoder)?;
+                    schema.push((Field::new(name, dtype, nullable), rolling_mean, returns));
+                }
+                columns = (0..schema.len()).map(|_| None).collect();
+                fields = Some(schema);
+            },
+            TAG_DATETIMES => {
+                let count = decoder.get_u64()? as usize;
+                let mut values = Vec::with_capacity(count.min(payload.len()));
+                for _ in 0..count {
+                    values.push(decoder.get_datetime()?);
+                }
+                datetimes = Some(values);
+            },
+            TAG_COLUMN => {
+                let (Some(fields), Some(datetimes)) = (&fields, &datetimes) else {
+                    return Err(DataFrameError::Snapshot("column values come before the schema and row times".to_string()));
+                };
+                let index = decoder.get_u32()? as usize;
+                // the rolling mean is put back once every value is in
+                let (field, _, returns) = fields.get(index).ok_or_else(|| DataFrameError::Snapshot(format!("values for column {} which is not in the schema", index)))?;
+                let mut column = Column::from_field(field.clone(), RollingMean::new(false, None), returns.clone());
+                get_buffer(&mut decoder, &mut column, datetimes.len())?;
+                columns[index] = Some(column);
+            },
+            TAG_ROLLING_WINDOWS => {
+                let index = decoder.get_u32()? as usize;
+                let count = decoder.get_u32()?;
+                for _ in 0..count {
+                    windows.push((index, get_rolling_window(&mut decoder)?));
+                }
+            },
+            TAG_ROLLING_RESULTS => {
+                let Some(datetimes) = &datetimes else {
+                    return Err(DataFrameError::Snapshot("rolling results come before the row times".to_string()));
+                };
+                let index = decoder.get_u32()? as usize;
+                let count = decoder.get_u32()?;
+                let mut values = vec![];
+                for _ in 0..count {
+                    values.push(get_results(&mut decoder, datetimes.len())?);
+                }
+                results.push((index, values));
+            },
+            tag if flags & FLAG_REQUIRED != 0 => return Err(DataFrameError::Snapshot(format!("section {} is required but not known to this reader", tag))),
+            _ => {},
+        }
+    }
+
+    let (Some(fields), Some(datetimes)) = (fields, datetimes) else {
+        return Err(DataFrameError::Snapshot("missing the schema or row times".to_string()));
+    };
+    let columns = columns.into_iter()
+        .zip(fields.iter())
+        .map(|(column, (field, _, _))| column.ok_or_else(|| DataFrameError::Snapshot(format!("missing the values of column '{}'", field.name))))
+        .collect::<Result<Vec<Column>>>()?;
+    let mut dataframe = DataFrame::from_columns(datetimes.clone(), columns)?;
+    if let Some(index) = windows.iter().map(|(index, _)| *index).chain(results.iter().map(|(index, _)| *index)).find(|index| *index >= fields.len()) {
+        return Err(DataFrameError::Snapshot(format!("rolling set up for column {} which is not in the schema", index)));
+    }
+    // without results, as when they were left out, every rolling value is calculated again
+    for (index, (field, rolling_mean, _)) in fields.into_iter().enumerate() {
+        let column_windows: Vec<RollingWindow> = windows.iter().filter(|(column, _)| *column == index).map(|(_, window)| window.clone()).collect();
+        let has_mean = rolling_mean.window().is_some();
+        let expected = has_mean as usize + column_windows.len();
+        let column_results: Vec<Option<Vec<Option<f64>>>> = match results.iter().position(|(column, _)| *column == index) {
+            Some(position) => results.swap_remove(position).1.into_iter().map(Some).collect(),
+            None => vec![None; expected],
+        };
+        if column_results.len() != expected {
+            return Err(DataFrameError::Snapshot(format!("rolling results for column '{}' do not match its windows", field.name)));
+        }
+        let mut column_results = column_results.into_iter();
+        let column = dataframe.get_mut_column_by_name(&field.name)?;
+        let mean_results = if has_mean { column_results.next().flatten() } else { None };
+        column.restore_rolling_mean(rolling_mean, &datetimes, mean_results)?;
+        for (window, window_results) in column_windows.into_iter().zip(column_results) {
+            column.restore_rolling_window(window, &datetimes, window_results)?;
+        }
+    }
+    Ok(dataframe)
+}
+
+// written next to the path and moved over it once complete so a crash part way through leaves the last snapshot intact
+pub fn save<P: AsRef<Path>>(path: P, dataframe: &DataFrame) -> Result<()> {
+    let path = path.as_ref();
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".partial");
+    if let Err(error) = write(BufWriter::new(File::create(&partial)?), dataframe) {
+        let _ = std::fs::remove_file(&partial);
+        return Err(error);
+    }
+    std::fs::rename(&partial, path)?;
+    Ok(())
+}
+
+pub fn load<P: AsRef<Path>>(path: P) -> Result<DataFrame> {
+    read(BufReader::new(File::open(path)?))
+}
+
+fn write_section<W: Write>(writer: &mut W, tag: u16, flags: u16, payload: &[u8]) -> Result<()> {
+    let mut section = Encoder::new();
+    section.put_u16(tag);
+    section.put_u16(flags);
+    section.put_u64(payload.len() as u64);
+    section.bytes.extend_from_slice(payload);
+    let checksum = crc32(&section.bytes);
+    section.put_u32(checksum);
+    writer.write_all(&section.bytes)?;
+    Ok(())
+}
+
+fn read_section<R: Read>(reader: &mut R) -> Result<(u16, u16, Vec<u8>)> {
+    let mut header = [0u8; 12];
+    read_exact(reader, &mut header)?;
+    let length = u64::from_le_bytes(header[4..].try_into().unwrap());
+    // read through take so a corrupt length cannot allocate more than is actually there
+    let mut section = header.to_vec();
+    reader.by_ref().take(length).read_to_end(&mut section)?;
+    if section.len() as u64 != 12 + length {
+        return Err(DataFrameError::Snapshot("ends part way through".to_string()));
+    }
+    let mut checksum = [0u8; 4];
+    read_exact(reader, &mut checksum)?;
+    if crc32(&section) != u32::from_le_bytes(checksum) {
+        return Err(DataFrameError::Snapshot("section checksum does not match".to_string()));
+    }
+    let tag = u16::from_le_bytes([header[0], header[1]]);
+    let flags = u16::from_le_bytes([header[2], header[3]]);
+    Ok((tag, flags, section.split_off(12)))
+}
+
+fn read_exact<R: Read>(reader: &mut R, buffer: &mut [u8]) -> Result<()> {
+    reader.read_exact(buffer).map_err(|error| match error.kind() {
+        ErrorKind::UnexpectedEof => DataFrameError::Snapshot("ends part way through".to_string()),
+        _ => DataFrameError::from(error),
+    })
+}
+
+// the typed values of every row, placeholders included, after a bitmap with a bit set for each row that is not null
+fn put_buffer(encoder: &mut Encoder, column: &Column) -> Result<()> {
+    let buffer = column.get_buffer();
+    let mut validity = vec![0u8; buffer.len().div_ceil(8)];
+    for index in 0..buffer.len() {
+        if buffer.is_valid(index) {
+            validity[index / 8] |= 1 << (index % 8);
+        }
+    }
+    encoder.bytes.extend_from_slice(&validity);
+    match buffer.get_values() {
+        TypedBuffer::Null => {},
+        TypedBuffer::Boolean(values) => values.iter().for_each(|value| encoder.put_bool(*value)),
+        TypedBuffer::Utf8(values) => values.iter().for_each(|value| encoder.put_str(value)),
+        TypedBuffer::UInt8(values) => values.iter().for_each(|value| encoder.put_u8(*value)),
+        TypedBuffer::UInt16(values) => values.iter().for_each(|value| encoder.put_u16(*value)),
+        TypedBuffer::UInt32(values) => values.iter().for_each(|value| encoder.put_u32(*value)),
+        TypedBuffer::UInt64(values) => values.iter().for_each(|value| encoder.put_u64(*value)),
+        TypedBuffer::USize(values) => values.iter().for_each(|value| encoder.put_u64(*value as u64)),
+        TypedBuffer::Int8(values) => values.iter().for_each(|value| encoder.bytes.extend_from_slice(&value.to_le_bytes())),
+        TypedBuffer::Int16(values) => values.iter().for_each(|value| encoder.bytes.extend_from_slice(&value.to_le_bytes())),
+        TypedBuffer::Int32(values) => values.iter().for_each(|value| encoder.bytes.extend_from_slice(&value.to_le_bytes())),
+        TypedBuffer::Int64(values) => values.iter().for_each(|value| encoder.put_i64(*value)),
+        TypedBuffer::ISize(values) => values.iter().for_each(|value| encoder.put_i64(*value as i64)),
+        TypedBuffer::Float32(values) => values.iter().for_each(|value| encoder.bytes.extend_from_slice(&value.to_le_bytes())),
+        TypedBuffer::Float64(values) => values.iter().for_each(|value| encoder.put_f64(*value)),
+        TypedBuffer::Date(values) => values.iter().for_each(|value| encoder.bytes.extend_from_slice(&value.num_days_from_ce().to_le_bytes())),
+        TypedBuffer::Datetime(values) => values.iter().for_each(|value| encoder.put_datetime(value)),
+        TypedBuffer::Duration(values) => {
+            for value in values.iter() {
+                encoder.put_i64(value.num_seconds());
+                encoder.bytes.extend_from_slice(&value.subsec_nanos().to_le_bytes());
+            }
+        },
+        TypedBuffer::Time(values) => {
+            for value in values.iter() {
+                encoder.put_u32(value.num_seconds_from_midnight());
+                encoder.put_u32(value.nanosecond());
+            }
+        },
+    }
+    if buffer.dtype() == DataType::Null && buffer.null_count() != buffer.len() {
+        return Err(DataFrameError::Snapshot(format!("column '{}' has values but no type", column.name)));
+    }
+    Ok(())
+}
+
+fn get_buffer(decoder: &mut Decoder, column: &mut Column, len: usize) -> Result<()> {
+    let validity = decoder.take(len.div_ceil(8))?.to_vec();
+    let dtype = column.dtype();
+    for index in 0..len {
+        let value = match dtype {
+            DataType::Null => AnyType::Null,
+            DataType::Boolean => AnyType::Boolean(decoder.get_bool()?),
+            DataType::Utf8 => AnyType::Utf8(Rc::from(decoder.get_str()?)),
+            DataType::UInt8 => AnyType::UInt8(decoder.get_u8()?),
+            DataType::UInt16 => AnyType::UInt16(decoder.get_u16()?),
+            DataType::UInt32 => AnyType::UInt32(decoder.get_u32()?),
+            DataType::UInt64 => AnyType::UInt64(decoder.get_u64()?),
+            DataType::USize => AnyType::USize(decoder.get_u64()? as usize),
+            DataType::Int8 => AnyType::Int8(i8::from_le_bytes(decoder.get_array()?)),
+            DataType::Int16 => AnyType::Int16(i16::from_le_bytes(decoder.get_array()?)),
+            DataType::Int32 => AnyType::Int32(i32::from_le_bytes(decoder.get_array()?)),
+            DataType::Int64 => AnyType::Int64(decoder.get_i64()?),
+            DataType::ISize => AnyType::ISize(decoder.get_i64()? as isize),
+            DataType::Float32 => AnyType::Float32(f32::from_le_bytes(decoder.get_array()?)),
+            DataType::Float64 => AnyType::Float64(decoder.get_f64()?),
+            DataType::Date => {
+                let days = i32::from_le_bytes(decoder.get_array()?);
+                AnyType::Date(NaiveDate::from_num_days_from_ce_opt(days).ok_or_else(|| out_of_range("date"))?)
+            },
+            DataType::Datetime => AnyType::Datetime(decoder.get_datetime()?),
+            DataType::Duration => {
+                let seconds = decoder.get_i64()?;
+                let nanos = i32::from_le_bytes(decoder.get_array()?);
+                let duration = chrono::Duration::try_seconds(seconds).and_then(|duration| duration.checked_add(&chrono::Duration::nanoseconds(nanos as i64)));
+                AnyType::Duration(duration.ok_or_else(|| out_of_range("duration"))?)
+            },
+            DataType::Time => {
+                let seconds = decoder.get_u32()?;
+                let nanos = decoder.get_u32()?;
+                AnyType::Time(NaiveTime::from_num_seconds_from_midnight_opt(seconds, nanos).ok_or_else(|| out_of_range("time"))?)
+            },
+        };
+        // nulls hold a placeholder which is read past and dropped
+        let value = match validity[index / 8] & (1 << (index % 8)) {
+            0 => AnyType::Null,
+            _ => value,
+        };
+        column.push(value)?;
+    }
+    Ok(())
+}
+
+// a bitmap with a bit set for each result there is, then every result with a placeholder for those there are not
+fn put_results(encoder: &mut Encoder, results: &[Option<f64>]) {
+    let mut validity = vec![0u8; results.len().div_ceil(8)];
+    for (index, result) in results.iter().enumerate() {
+        if result.is_some() {
+            validity[index / 8] |= 1 << (index % 8);
+        }
+    }
+    encoder.bytes.extend_from_slice(&validity);
+    for result in results.iter() {
+        encoder.put_f64(result.unwrap_or(0f64));
+    }
+}
+
+fn get_results(decoder: &mut Decoder, len: usize) -> Result<Vec<Option<f64>>> {
+    let validity = decoder.take(len.div_ceil(8))?.to_vec();
+    (0..len)
+        .map(|index| {
+            let result = decoder.get_f64()?;
+            Ok((validity[index / 8] & (1 << (index % 8)) != 0).then_some(result))
+        })
+        .collect()
+}
+
+fn put_rolling_mean(encoder: &mut Encoder, rolling_mean: &RollingMean) -> Result<()> {
+    encoder.put_bool(rolling_mean.should_calculate());
+    encoder.put_u64(rolling_mean.mean_over() as u64);
+    put_optional_duration(encoder, rolling_mean.duration())
+}
+
+fn get_rolling_mean(decoder: &mut Decoder) -> Result<RollingMean> {
+    let should_calculate = decoder.get_bool()?;
+    let mean_over = decoder.get_u64()? as usize;
+    let rolling_mean = match get_optional_duration(decoder)? {
+        Some(duration) => RollingMean::over_duration(duration),
+        None => RollingMean::new(should_calculate, Some(mean_over)),
+    };
+    Ok(rolling_mean)
+}
+
+fn put_returns(encoder: &mut Encoder, returns: &Returns) {
+    encoder.put_bool(returns.should_calculate);
+    match &returns.column_name {
+        Some(column_name) => {
+            encoder.put_bool(true);
+            encoder.put_str(column_name);
+        },
+        None => encoder.put_bool(false),
+    }
+    let kind = match returns.kind {
+        ReturnsKind::Difference(_) => 0,
+        ReturnsKind::Percentage(_) => 1,
+        ReturnsKind::Log(_) => 2,
+    };
+    encoder.put_u8(kind);
+    encoder.put_u64(returns.kind.lag() as u64);
+}
+
+fn get_returns(decoder: &mut Decoder) -> Result<Returns> {
+    let should_calculate = decoder.get_bool()?;
+    let column_name = match decoder.get_bool()? {
+        true => Some(decoder.get_str()?),
+        false => None,
+    };
+    let kind = decoder.get_u8()?;
+    let lag = decoder.get_u64()? as usize;
+    let kind = match kind {
+        0 => ReturnsKind::Difference(lag),
+        1 => ReturnsKind::Percentage(lag),
+        2 => ReturnsKind::Log(lag),
+        kind => return Err(DataFrameError::Snapshot(format!("unknown returns kind {}", kind))),
+    };
+    Ok(Returns::new(should_calculate, column_name.as_deref()).with_kind(kind))
+}
+
+fn put_rolling_window(encoder: &mut Encoder, window: &RollingWindow) -> Result<()> {
+    encoder.put_str(&window.name);
+    match window.span {
+        WindowSpan::Rows(size) => {
+            encoder.put_u8(0);
+            encoder.put_u64(size as u64);
+        },
+        WindowSpan::Duration(duration) => {
+            encoder.put_u8(1);
+            encoder.put_i64(duration.num_nanoseconds().ok_or_else(|| DataFrameError::Overflow(window.name.to_string()))?);
+        },
+    }
+    let (aggregation, quantile) = match window.aggregation {
+        RollingAggregation::Mean => (0, 0f64),
+        RollingAggregation::Sum => (1, 0f64),
+        RollingAggregation::Min => (2, 0f64),
+        RollingAggregation::Max => (3, 0f64),
+        RollingAggregation::Variance => (4, 0f64),
+        RollingAggregation::Std => (5, 0f64),
+        RollingAggregation::Median => (6, 0f64),
+        RollingAggregation::Quantile(quantile) => (7, quantile),
+        RollingAggregation::Custom(_) => return Err(DataFrameError::InvalidArgument(format!("rolling window '{}' has a custom aggregation which cannot be written", window.name))),
+    };
+    encoder.put_u8(aggregation);
+    encoder.put_f64(quantile);
+    encoder.put_u64(window.min_periods as u64);
+    encoder.put_bool(window.alignment == WindowAlignment::Centred);
+    Ok(())
+}
+
+fn get_rolling_window(decoder: &mut Decoder) -> Result<RollingWindow> {
+    let name = decoder.get_str()?;
+    let span = match decoder.get_u8()? {
+        0 => WindowSpan::Rows(decoder.get_u64()? as usize),
+        1 => WindowSpan::Duration(chrono::Duration::nanoseconds(decoder.get_i64()?)),
+        span => return Err(DataFrameError::Snapshot(format!("unknown span {} for rolling window '{}'", span, name))),
+    };
+    let aggregation = decoder.get_u8()?;
+    let quantile = decoder.get_f64()?;
+    let aggregation = match aggregation {
+        0 => RollingAggregation::Mean,
+        1 => RollingAggregation::Sum,
+        2 => RollingAggregation::Min,
+        3 => RollingAggregation::Max,
+        4 => RollingAggregation::Variance,
+        5 => RollingAggregation::Std,
+        6 => RollingAggregation::Median,
+        7 => RollingAggregation::Quantile(quantile),
+        aggregation => return Err(DataFrameError::Snapshot(format!("unknown aggregation {} for rolling window '{}'", aggregation, name))),
+    };
+    let min_periods = decoder.get_u64()? as usize;
+    let alignment = match decoder.get_bool()? {
+        true => WindowAlignment::Centred,
+        false => WindowAlignment::Trailing,
+    };
+    Ok(RollingWindow { name: Rc::from(name), span, aggregation, min_periods, alignment })
+}
+
+fn put_optional_duration(encoder: &mut Encoder, duration: Option<chrono::Duration>) -> Result<()> {
+    match duration {
+        Some(duration) => {
+            encoder.put_bool(true);
+            encoder.put_i64(duration.num_nanoseconds().ok_or_else(|| DataFrameError::Snapshot("duration does not fit in i64 nanoseconds".to_string()))?);
+        },
+        None => encoder.put_bool(false),
+    }
+    Ok(())
+}
+
+fn get_optional_duration(decoder: &mut Decoder) -> Result<Option<chrono::Duration>> {
+    match decoder.get_bool()? {
+        true => Ok(Some(chrono::Duration::nanoseconds(decoder.get_i64()?))),
+        false => Ok(None),
+    }
+}
+
+// codes are fixed once written, new types take the next free code
+fn dtype_code(dtype: DataType) -> u8 {
+    match dtype {
+        DataType::Null => 0,
+        DataType::Boolean => 1,
+        DataType::Utf8 => 2,
+        DataType::UInt8 => 3,
+        DataType::UInt16 => 4,
+        DataType::UInt32 => 5,
+        DataType::UInt64 => 6,
+        DataType::USize => 7,
+        DataType::Int8 => 8,
+        DataType::Int16 => 9,
+        DataType::Int32 => 10,
+        DataType::Int64 => 11,
+        DataType::ISize => 12,
+        DataType::Float32 => 13,
+        DataType::Float64 => 14,
+        DataType::Date => 15,
+        DataType::Datetime => 16,
+        DataType::Duration => 17,
+        DataType::Time => 18,
+    }
+}
+
+fn from_dtype_code(code: u8) -> Result<DataType> {
+    let dtype = match code {
+        0 => DataType::Null,
+        1 => DataType::Boolean,
+        2 => DataType::Utf8,
+        3 => DataType::UInt8,
+        4 => DataType::UInt16,
+        5 => DataType::UInt32,
+        6 => DataType::UInt64,
+        7 => DataType::USize,
+        8 => DataType::Int8,
+        9 => DataType::Int16,
+        10 => DataType::Int32,
+        11 => DataType::Int64,
+        12 => DataType::ISize,
+        13 => DataType::Float32,
+        14 => DataType::Float64,
+        15 => DataType::Date,
+        16 => DataType::Datetime,
+        17 => DataType::Duration,
+        18 => DataType::Time,
+        code => return Err(DataFrameError::Snapshot(format!("unknown type code {}", code))),
+    };
+    Ok(dtype)
+}
+
+fn out_of_range(name: &str) -> DataFrameError {
+    DataFrameError::Snapshot(format!("{} out of range", name))
+}
+
+struct Encoder {
+    bytes: Vec<u8>,
+}
+
+impl Encoder {
+    fn new() -> Self {
+        Self { bytes: vec![] }
+    }
+
+    fn put_u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    fn put_bool(&mut self, value: bool) {
+        self.bytes.push(value as u8);
+    }
+
+    fn put_u16(&mut self, value: u16) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn put_u32(&mut self, value: u32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn put_u64(&mut self, value: u64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn put_i64(&mut self, value: i64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn put_f64(&mut self, value: f64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    // the length in bytes then the utf-8 bytes
+    fn put_str(&mut self, value: &str) {
+        self.put_u32(value.len() as u32);
+        self.bytes.extend_from_slice(value.as_bytes());
+    }
+
+    // seconds since the epoch then the nanoseconds within that second
+    fn put_datetime(&mut self, value: &DateTime<Utc>) {
+        self.put_i64(value.timestamp());
+        self.put_u32(value.timestamp_subsec_nanos());
+    }
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self.position.checked_add(len).filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| DataFrameError::Snapshot("section ends before its values".to_string()))?;
+        let bytes = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn get_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn get_u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn get_bool(&mut self) -> Result<bool> {
+        Ok(self.get_u8()? != 0)
+    }
+
+    fn get_u16(&mut self) -> Result<u16> {
+        Ok(u16::from_le_bytes(self.get_array()?))
+    }
+
+    fn get_u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.get_array()?))
+    }
+
+    fn get_u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.get_array()?))
+    }
+
+    fn get_i64(&mut self) -> Result<i64> {
+        Ok(i64::from_le_bytes(self.get_array()?))
+    }
+
+    fn get_f64(&mut self) -> Result<f64> {
+        Ok(f64::from_le_bytes(self.get_array()?))
+    }
+
+    fn get_str(&mut self) -> Result<String> {
+        let len = self.get_u32()? as usize;
+        let bytes = self.take(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| DataFrameError::Snapshot("text is not valid utf-8".to_string()))
+    }
+
+    fn get_datetime(&mut self) -> Result<DateTime<Utc>> {
+        let seconds = self.get_i64()?;
+        let nanos = self.get_u32()?;
+        DateTime::from_timestamp(seconds, nanos).ok_or_else(|| out_of_range("timestamp"))
+    }
+}
+
+// crc-32 as used by zip and png
+const CRC_TABLE: [u32; 256] = crc_table();
+
+const fn crc_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut index = 0;
+    while index < 256 {
+        let mut crc = index as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[index] = crc;
+        index += 1;
+    }
+    table
+}
+
+pub fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |crc, byte| CRC_TABLE[((crc ^ *byte as u32) & 0xff) as usize] ^ (crc >> 8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::test_helpers::start;
+    use crate::column::rolling::RollingFn;
+    fn prices() -> DataFrame {
+        let start = start() + chrono::Duration::nanoseconds(1);
+        let mut dataframe = DataFrame::new(vec!["ticker", "price", "size", "latency", "day", "at", "seen", "flag", "empty"]).unwrap();
+        dataframe.update_column_rolling_mean("price", RollingMean::over_duration(chrono::Duration::seconds(1))).unwrap();
+        dataframe.update_column_rolling_mean("size", RollingMean::new(true, Some(2))).unwrap();
+        dataframe.create_returns_for_column("price", "price_returns", ReturnsKind::Log(2), RollingMean::new(false, None)).unwrap();
+        dataframe.add_rolling_window("size", RollingWindow::new("size_sum", 3, RollingAggregation::Sum).with_min_periods(1).with_alignment(WindowAlignment::Centred)).unwrap();
+        dataframe.add_rolling_window("price", RollingWindow::over_duration("price_p90", chrono::Duration::milliseconds(600), RollingAggregation::Quantile(0.9))).unwrap();
+        for index in 0..10i64 {
+            let ticker = match index % 3 {
+                0 => AnyType::Null,
+                _ => "vod".into(),
+            };
+            let row = vec![
+                ticker,
+                (70.0 + index as f64 / 4.0).into(),
+                AnyType::USize(index as usize * 100),
+                AnyType::Duration(chrono::Duration::nanoseconds(-index * 1_000_000_007)),
+                AnyType::Date(NaiveDate::from_ymd_opt(1969, 12, 31).unwrap() + chrono::Duration::days(index)),
+                AnyType::Time(NaiveTime::from_hms_nano_opt(8, 0, index as u32, 5).unwrap()),
+                AnyType::Datetime(start - chrono::Duration::days(index * 20_000)),
+                match index % 4 {
+                    0 => AnyType::Null,
+                    index => AnyType::Boolean(index % 2 == 0),
+                },
+                AnyType::Null,
+            ];
+            dataframe.add_row_at(start + chrono::Duration::milliseconds(index * 250), row).unwrap();
+        }
+        dataframe
+    }
+
+    fn snapshot(dataframe: &DataFrame) -> Vec<u8> {
+        let mut bytes = vec![];
+        write(&mut bytes, dataframe).unwrap();
+        bytes
+    }
+
+    fn header(version: u16, min_reader_version: u16) -> Vec<u8> {
+        let mut header = MAGIC.to_vec();
+        header.extend_from_slice(&version.to_le_bytes());
+        header.extend_from_slice(&min_reader_version.to_le_bytes());
+        let checksum = crc32(&header);
+        header.extend_from_slice(&checksum.to_le_bytes());
+        header
+    }
+
+    // the snapshot with a section added before the end section
+    fn with_section(bytes: &[u8], tag: u16, flags: u16) -> Vec<u8> {
+        let (body, end) = bytes.split_at(bytes.len() - 16);
+        let mut bytes = body.to_vec();
+        write_section(&mut bytes, tag, flags, b"added later").unwrap();
+        bytes.extend_from_slice(end);
+        bytes
+    }
+
+    #[test]
+    fn checksum() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn round_trip() {
+        let dataframe = prices();
+        let bytes = snapshot(&dataframe);
+        assert_eq!(bytes[..8], MAGIC);
+
+        let mut read_back = read(bytes.as_slice()).unwrap();
+        assert_eq!(read_back.schema(), dataframe.schema());
+        assert_eq!(read_back.get_datetimes(), dataframe.get_datetimes());
+        for (column, original) in read_back.get_columns().iter().zip(dataframe.get_columns().iter()) {
+            assert_eq!(column.iter_values().collect::<Vec<AnyType>>(), original.iter_values().collect::<Vec<AnyType>>());
+            assert_eq!(column.returns.column_name, original.returns.column_name);
+            assert_eq!(column.returns.kind, original.returns.kind);
+            for index in 0..dataframe.len() {
+                assert_eq!(column.get_rolling_mean(index), original.get_rolling_mean(index));
+                for name in ["size_sum", "price_p90"] {
+                    assert_eq!(column.get_rolling_value(name, index), original.get_rolling_value(name, index));
+                }
+            }
+        }
+        let names: Vec<String> = read_back.get_column_by_name("price").unwrap().get_rolling_windows().map(|window| window.name.to_string()).collect();
+        assert_eq!(names, vec!["price_p90"]);
+
+        // the returns carry on being calculated for rows added after loading
+        let datetime = *dataframe.get_datetimes().last().unwrap() + chrono::Duration::milliseconds(250);
+        let mut row: Vec<AnyType> = dataframe.get_columns()[..9].iter().map(|column| column.get_value(9).unwrap()).collect();
+        row[1] = 80f64.into();
+        read_back.add_row_at(datetime, row).unwrap();
+        assert_eq!(read_back.get_column_by_name("price_returns").unwrap().get_value(10), Some((80f64 / 72.0).ln().into()));
+        assert_eq!(read_back.get_column_by_name("size").unwrap().get_rolling_mean(10), Some(AnyType::USize(900)));
+        let mut original = prices();
+        original.add_row_at(datetime, read_back.get_row(10).unwrap().get_cells()[..9].iter().map(|cell| cell.get_value().clone()).collect()).unwrap();
+        for index in 0..original.len() {
+            assert_eq!(read_back.get_column_by_name("price").unwrap().get_rolling_value("price_p90", index), original.get_column_by_name("price").unwrap().get_rolling_value("price_p90", index));
+        }
+
+        let path = std::env::temp_dir().join(format!("dataframe-{}.snapshot", std::process::id()));
+        dataframe.save_snapshot(&path).unwrap();
+        let loaded = DataFrame::load_snapshot(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap().get_datetimes(), dataframe.get_datetimes());
+
+        let empty = read(snapshot(&DataFrame::new(Vec::<&str>::new()).unwrap()).as_slice()).unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn rolling_results() {
+        // without the results section the rolling values are calculated again and come out the same
+        let dataframe = prices();
+        let bytes = snapshot(&dataframe);
+        let mut stripped = bytes[..16].to_vec();
+        let mut position = 16;
+        while position < bytes.len() {
+            let tag = u16::from_le_bytes([bytes[position], bytes[position + 1]]);
+            let len = u64::from_le_bytes(bytes[position + 4..position + 12].try_into().unwrap()) as usize;
+            let end = position + 12 + len + 4;
+            if tag != TAG_ROLLING_RESULTS {
+                stripped.extend_from_slice(&bytes[position..end]);
+            }
+            position = end;
+        }
+        assert!(stripped.len() < bytes.len());
+        let with_results = read(bytes.as_slice()).unwrap();
+        let without_results = read(stripped.as_slice()).unwrap();
+        for (column, original) in without_results.get_columns().iter().zip(with_results.get_columns().iter()) {
+            assert_eq!(column.get_rolling_results().collect::<Vec<&[Option<f64>]>>(), original.get_rolling_results().collect::<Vec<&[Option<f64>]>>());
+        }
+
+        // a custom aggregation is a closure so cannot be written, which fails before anything is
+        let mut dataframe = prices();
+        let custom: RollingFn = Rc::new(|values: &[f64]| values.first().copied());
+        dataframe.add_rolling_window("price", RollingWindow::new("price_first", 2, RollingAggregation::Custom(custom))).unwrap();
+        let mut bytes = vec![];
+        let error = write(&mut bytes, &dataframe).unwrap_err();
+        assert_eq!(error, DataFrameError::InvalidArgument("rolling window 'price_first' has a custom aggregation which cannot be written".to_string()));
+        assert!(bytes.is_empty());
+        let path = std::env::temp_dir().join(format!("dataframe-{}-custom.snapshot", std::process::id()));
+        assert_eq!(dataframe.save_snapshot(&path).unwrap_err(), error);
+        assert!(!path.exists());
+        assert!(!path.with_extension("snapshot.partial").exists());
+    }
+
+    #[test]
+    fn corruption() {
+        let bytes = snapshot(&prices());
+        for index in [3, 11, 20, bytes.len() / 2, bytes.len() - 1] {
+            let mut corrupt = bytes.clone();
+            corrupt[index] ^= 0x10;
+            assert!(matches!(read(corrupt.as_slice()), Err(DataFrameError::Snapshot(_))), "byte {} was not caught", index);
+        }
+        for len in [0, 10, 16, bytes.len() / 3, bytes.len() - 16, bytes.len() - 1] {
+            assert_eq!(read(&bytes[..len]).unwrap_err(), DataFrameError::Snapshot("ends part way through".to_string()), "length {}", len);
+        }
+        assert_eq!(read(&b"a,b,c\n1,2,3\n1,2,3\n"[..]).unwrap_err(), DataFrameError::Snapshot("not a dataframe snapshot".to_string()));
+    }
+
+    #[test]
+    fn versions() {
+        let bytes = snapshot(&prices());
+        assert_eq!(bytes[..16], header(FORMAT_VERSION, MIN_READER_VERSION));
+
+        // a later version that earlier readers can still read, with a section they skip
+        let mut later = header(FORMAT_VERSION + 1, MIN_READER_VERSION);
+        later.extend_from_slice(&with_section(&bytes, 99, 0)[16..]);
+        assert_eq!(read(later.as_slice()).unwrap().get_datetimes(), prices().get_datetimes());
+
+        let required = with_section(&bytes, 99, FLAG_REQUIRED);
+        assert_eq!(read(required.as_slice()).unwrap_err(), DataFrameError::Snapshot("section 99 is required but not known to this reader".to_string()));
+
+        let mut newer = header(FORMAT_VERSION + 1, FORMAT_VERSION + 1);
+        newer.extend_from_slice(&bytes[16..]);
+        assert_eq!(
+            read(newer.as_slice()).unwrap_err(),
+            DataFrameError::Snapshot(format!("written for version {} or later but this reader is version {}", FORMAT_VERSION + 1, FORMAT_VERSION))
+        );
+    }
+}
@@ -3,6 +3,12 @@ pub mod cell;
 pub mod column;
 pub mod row;
 pub mod frame;
+pub mod error;
+pub mod clock;
+pub mod schema;
+pub mod io;
+#[cfg(feature = "serde")]
+pub mod serialization;
 
 use cell::{
     types::datatypes::AnyType,
@@ -16,15 +22,15 @@ fn main() {
         "rando",
         "second"
     ];
-    let mut dataframe = DataFrame::new(columns);
+    let mut dataframe = DataFrame::new(columns).unwrap();
     let cell_values: Vec<AnyType> = vec![
         6.into(),
         "whoop".into()
     ];
-    dataframe.add_row(cell_values);
+    dataframe.add_row(cell_values).unwrap();
     for column in dataframe.get_columns().iter() {
-        for cell in column.get_cells().iter() {
-            println!("{}", cell.borrow().get_value());
+        for value in column.iter_values() {
+            println!("{}", value);
         }
     }
     println!("{:?}", dataframe);
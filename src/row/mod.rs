@@ -1,61 +1,40 @@
 pub mod types;
 
-use std::rc::{ Rc, Weak };
-use std::cell::{
-    RefCell,
-    Ref
-};
 use crate::cell::{
-    RcCell,
-    AnyTypeCell
+    Cell,
 };
 use chrono::{
     DateTime,
     Utc
 };
 
-#[derive(Debug)]
+// rows are a logical index into the columns, a Row is a snapshot of the values at that index
+#[derive(Debug, Clone)]
 pub struct Row {
-    cells: RefCell<Vec<Weak<AnyTypeCell>>>,
+    cells: Vec<Cell>,
     datetime: DateTime<Utc>,
     pub index: usize,
 }
 
 impl Row {
-    pub fn new(index: usize) -> RcRow {
-        Rc::new(RefCell::new(Self {
-            cells: RefCell::new(vec![]),
-            datetime: Utc::now(),
+    pub fn new(index: usize, datetime: DateTime<Utc>, cells: Vec<Cell>) -> Self {
+        Self {
+            cells,
+            datetime,
             index
-        }))
-    }
-
-    pub fn add_cell(&mut self, cell: &RcCell) {
-        self.cells.borrow_mut().push(Rc::downgrade(cell));
-    }
-
-    pub fn drop_cell(&mut self, cell: &RcCell) {
-        self.cells.borrow_mut().retain(|c| c.upgrade().unwrap() != Rc::clone(cell));
-    }
-
-    pub fn get_cells(&self) -> Ref<Vec<Weak<AnyTypeCell>>> {
-        self.cells.borrow()
+        }
     }
 
-    pub fn get_cell(&self, cell_index: usize) -> Option<RcCell> {
-        self.cells.borrow()[cell_index].upgrade()
+    pub fn get_cells(&self) -> &Vec<Cell> {
+        &self.cells
     }
 
-    pub fn get_last_cell(&self) -> Option<RcCell> {
-        let last_cell = self.cells.borrow().clone();
-        if let Some(cell) = last_cell.iter().last() {
-            return cell.upgrade();
-        }
-        None
+    pub fn get_cell(&self, cell_index: usize) -> Option<&Cell> {
+        self.cells.get(cell_index)
     }
 
-    pub fn update_index(&mut self, index: usize) {
-        self.index = index;
+    pub fn get_last_cell(&self) -> Option<&Cell> {
+        self.cells.last()
     }
 
     pub fn get_datetime(&self) -> DateTime<Utc> {
@@ -70,61 +49,28 @@ impl PartialEq for Row {
 }
 impl Eq for Row {}
 
-pub type RcRow = Rc<RefCell<Row>>;
-
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::cell::{
-        types::datatypes::AnyType,
-        Cell,
-        RcCell,
-    };
-    #[test]
-    fn add_cell() {
-        let value: AnyType = 67u16.into();
-        let row: RcRow = Row::new(3);
-        let cell: RcCell = Cell::new(value, &row, "timmeh");
-
-        row.borrow_mut().add_cell(&cell);
-
-        assert!(cell == row.borrow().get_cells()[0].upgrade().unwrap());
-    }
-
+    use crate::cell::types::datatypes::AnyType;
     #[test]
-    fn add_cell_ref_count() {
+    fn get_cells() {
         let value: AnyType = 67u16.into();
-        let row: RcRow = Row::new(3);
-        let cell: RcCell = Cell::new(value, &row, "timmeh");
-
-        row.borrow_mut().add_cell(&cell);
+        let cell = Cell::new(value, 3, "timmeh", None);
+        let row = Row::new(3, Utc::now(), vec![cell.clone()]);
 
-        assert!(1 == row.borrow().get_cells()[0].strong_count());
-        assert!(1 == row.borrow().get_cells()[0].weak_count());
+        assert!(row.get_cells()[0] == cell);
+        assert!(row.get_cell(0) == Some(&cell));
+        assert!(row.get_cell(1).is_none());
     }
 
     #[test]
-    fn drop_cell() {
-        let value: AnyType = 67u16.into();
-        let row: RcRow = Row::new(3);
-        let cell: RcCell = Cell::new(value, &row, "timmeh");
-
-        row.borrow_mut().add_cell(&cell);
-        assert!(row.borrow().get_cells().len() == 1);
+    fn get_last_cell() {
+        let row = Row::new(3, Utc::now(), vec![
+            Cell::new(67u16.into(), 3, "timmeh", None),
+            Cell::new("whoop".into(), 3, "second", None),
+        ]);
 
-        row.borrow_mut().drop_cell(&cell);
-        assert!(row.borrow().get_cells().len() == 0);
+        assert_eq!(row.get_last_cell().unwrap().get_column_name(), "second");
     }
-
-    #[test]
-    fn update_index() {
-        let value: AnyType = 67u16.into();
-        let row: RcRow = Row::new(3);
-        let cell: RcCell = Cell::new(value, &row, "timmeh");
-
-        row.borrow_mut().add_cell(&cell);
-        row.borrow_mut().update_index(7);
-        assert!(row.borrow().index == 7);
-    }
-
-}
\ No newline at end of file
+}
@@ -0,0 +1,69 @@
+use crate::cell::types::datatypes::DataType;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    pub name: Rc<str>,
+    pub dtype: DataType,
+    pub nullable: bool,
+}
+
+impl Field {
+    pub fn new<S: Into<Rc<str>>>(name: S, dtype: DataType, nullable: bool) -> Self {
+        Self {
+            name: name.into(),
+            dtype,
+            nullable,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Schema {
+    fields: Vec<Field>,
+}
+
+impl Schema {
+    pub fn new(fields: Vec<Field>) -> Self {
+        Self {
+            fields
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    pub fn get_fields(&self) -> &Vec<Field> {
+        &self.fields
+    }
+
+    pub fn get_field(&self, name: &str) -> Option<&Field> {
+        self.fields.iter().find(|f| &*f.name == name)
+    }
+
+    pub fn get_dtype(&self, name: &str) -> Option<DataType> {
+        self.get_field(name).map(|f| f.dtype)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn get_field() {
+        let schema = Schema::new(vec![
+            Field::new("price", DataType::Float64, false),
+            Field::new("venue", DataType::Utf8, true),
+        ]);
+
+        assert_eq!(schema.len(), 2);
+        assert_eq!(schema.get_dtype("venue"), Some(DataType::Utf8));
+        assert!(!schema.get_field("price").unwrap().nullable);
+        assert!(schema.get_field("missing").is_none());
+    }
+}
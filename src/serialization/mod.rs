@@ -0,0 +1,440 @@
+use crate::cell::types::datatypes::{
+    AnyType,
+    DataType,
+};
+use crate::column::rolling::{
+    RollingAggregation,
+    RollingWindow,
+    WindowAlignment,
+    WindowSpan,
+};
+use crate::column::{
+    Column,
+    Returns,
+    RollingMean,
+};
+use crate::frame::DataFrame;
+use crate::schema::{
+    Field,
+    Schema,
+};
+use chrono::{
+    DateTime,
+    NaiveDate,
+    NaiveTime,
+    Utc,
+};
+use serde::de::Error;
+use serde::ser::Error as SerError;
+use serde::{
+    Deserialize,
+    Deserializer,
+    Serialize,
+    Serializer,
+};
+use std::rc::Rc;
+
+// values are tagged with the name of their type, e.g. {"dtype": "i64", "value": 5}, with durations as nanoseconds
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "dtype", content = "value")]
+enum Tagged {
+    #[serde(rename = "null")]
+    Null,
+    #[serde(rename = "bool")]
+    Boolean(bool),
+    #[serde(rename = "str")]
+    Utf8(Rc<str>),
+    #[serde(rename = "u8")]
+    UInt8(u8),
+    #[serde(rename = "u16")]
+    UInt16(u16),
+    #[serde(rename = "u32")]
+    UInt32(u32),
+    #[serde(rename = "u64")]
+    UInt64(u64),
+    #[serde(rename = "usize")]
+    USize(usize),
+    #[serde(rename = "i8")]
+    Int8(i8),
+    #[serde(rename = "i16")]
+    Int16(i16),
+    #[serde(rename = "i32")]
+    Int32(i32),
+    #[serde(rename = "i64")]
+    Int64(i64),
+    #[serde(rename = "isize")]
+    ISize(isize),
+    #[serde(rename = "f32")]
+    Float32(f32),
+    #[serde(rename = "f64")]
+    Float64(f64),
+    #[serde(rename = "date")]
+    Date(NaiveDate),
+    #[serde(rename = "datetime")]
+    Datetime(DateTime<Utc>),
+    #[serde(rename = "duration")]
+    Duration(i64),
+    #[serde(rename = "time")]
+    Time(NaiveTime),
+}
+
+impl Serialize for AnyType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let tagged = match self.clone() {
+            AnyType::Null => Tagged::Null,
+            AnyType::Boolean(value) => Tagged::Boolean(value),
+            AnyType::Utf8(value) => Tagged::Utf8(value),
+            AnyType::UInt8(value) => Tagged::UInt8(value),
+            AnyType::UInt16(value) => Tagged::UInt16(value),
+            AnyType::UInt32(value) => Tagged::UInt32(value),
+            AnyType::UInt64(value) => Tagged::UInt64(value),
+            AnyType::USize(value) => Tagged::USize(value),
+            AnyType::Int8(value) => Tagged::Int8(value),
+            AnyType::Int16(value) => Tagged::Int16(value),
+            AnyType::Int32(value) => Tagged::Int32(value),
+            AnyType::Int64(value) => Tagged::Int64(value),
+            AnyType::ISize(value) => Tagged::ISize(value),
+            AnyType::Float32(value) => Tagged::Float32(value),
+            AnyType::Float64(value) => Tagged::Float64(value),
+            AnyType::Date(value) => Tagged::Date(value),
+            AnyType::Datetime(value) => Tagged::Datetime(value),
+            AnyType::Duration(value) => Tagged::Duration(value.num_nanoseconds().ok_or_else(|| serde::ser::Error::custom("duration does not fit in i64 nanoseconds"))?),
+            AnyType::Time(value) => Tagged::Time(value),
+        };
+        tagged.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for AnyType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = match Tagged::deserialize(deserializer)? {
+            Tagged::Null => AnyType::Null,
+            Tagged::Boolean(value) => AnyType::Boolean(value),
+            Tagged::Utf8(value) => AnyType::Utf8(value),
+            Tagged::UInt8(value) => AnyType::UInt8(value),
+            Tagged::UInt16(value) => AnyType::UInt16(value),
+            Tagged::UInt32(value) => AnyType::UInt32(value),
+            Tagged::UInt64(value) => AnyType::UInt64(value),
+            Tagged::USize(value) => AnyType::USize(value),
+            Tagged::Int8(value) => AnyType::Int8(value),
+            Tagged::Int16(value) => AnyType::Int16(value),
+            Tagged::Int32(value) => AnyType::Int32(value),
+            Tagged::Int64(value) => AnyType::Int64(value),
+            Tagged::ISize(value) => AnyType::ISize(value),
+            Tagged::Float32(value) => AnyType::Float32(value),
+            Tagged::Float64(value) => AnyType::Float64(value),
+            Tagged::Date(value) => AnyType::Date(value),
+            Tagged::Datetime(value) => AnyType::Datetime(value),
+            Tagged::Duration(value) => AnyType::Duration(chrono::Duration::nanoseconds(value)),
+            Tagged::Time(value) => AnyType::Time(value),
+        };
+        Ok(value)
+    }
+}
+
+// types are written with the same names they are displayed with
+impl Serialize for DataType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for DataType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        let dtype = match name.as_str() {
+            "null" => DataType::Null,
+            "bool" => DataType::Boolean,
+            "str" => DataType::Utf8,
+            "u8" => DataType::UInt8,
+            "u16" => DataType::UInt16,
+            "u32" => DataType::UInt32,
+            "u64" => DataType::UInt64,
+            "usize" => DataType::USize,
+            "i8" => DataType::Int8,
+            "i16" => DataType::Int16,
+            "i32" => DataType::Int32,
+            "i64" => DataType::Int64,
+            "isize" => DataType::ISize,
+            "f32" => DataType::Float32,
+            "f64" => DataType::Float64,
+            "date" => DataType::Date,
+            "datetime" => DataType::Datetime,
+            "duration" => DataType::Duration,
+            "time" => DataType::Time,
+            name => return Err(D::Error::custom(format!("unknown type '{}'", name))),
+        };
+        Ok(dtype)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct FieldRepr {
+    name: Rc<str>,
+    dtype: DataType,
+    nullable: bool,
+}
+
+impl Serialize for Field {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        FieldRepr { name: Rc::clone(&self.name), dtype: self.dtype, nullable: self.nullable }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Field {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let field = FieldRepr::deserialize(deserializer)?;
+        Ok(Field::new(field.name, field.dtype, field.nullable))
+    }
+}
+
+impl Serialize for Schema {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.get_fields().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Schema {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Schema::new(Vec::<Field>::deserialize(deserializer)?))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum SpanRepr {
+    Rows(usize),
+    // nanoseconds
+    Duration(i64),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum AggregationRepr {
+    Mean,
+    Sum,
+    Min,
+    Max,
+    Variance,
+    Std,
+    Median,
+    Quantile(f64),
+}
+
+#[derive(Serialize, Deserialize)]
+struct RollingWindowRepr {
+    name: Rc<str>,
+    span: SpanRepr,
+    aggregation: AggregationRepr,
+    min_periods: usize,
+    centred: bool,
+}
+
+// a custom aggregation is a closure so a window with one cannot be serialized
+impl Serialize for RollingWindow {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let span = match self.span {
+            WindowSpan::Rows(size) => SpanRepr::Rows(size),
+            WindowSpan::Duration(duration) => SpanRepr::Duration(duration.num_nanoseconds()
+                .ok_or_else(|| S::Error::custom(format!("rolling window '{}' covers a duration too long for i64 nanoseconds", self.name)))?),
+        };
+        let aggregation = match self.aggregation {
+            RollingAggregation::Mean => AggregationRepr::Mean,
+            RollingAggregation::Sum => AggregationRepr::Sum,
+            RollingAggregation::Min => AggregationRepr::Min,
+            RollingAggregation::Max => AggregationRepr::Max,
+            RollingAggregation::Variance => AggregationRepr::Variance,
+            RollingAggregation::Std => AggregationRepr::Std,
+            RollingAggregation::Median => AggregationRepr::Median,
+            RollingAggregation::Quantile(quantile) => AggregationRepr::Quantile(quantile),
+            RollingAggregation::Custom(_) => return Err(S::Error::custom(format!("rolling window '{}' has a custom aggregation which cannot be serialized", self.name))),
+        };
+        RollingWindowRepr {
+            name: Rc::clone(&self.name),
+            span,
+            aggregation,
+            min_periods: self.min_periods,
+            centred: self.alignment == WindowAlignment::Centred,
+        }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for RollingWindow {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let window = RollingWindowRepr::deserialize(deserializer)?;
+        let aggregation = match window.aggregation {
+            AggregationRepr::Mean => RollingAggregation::Mean,
+            AggregationRepr::Sum => RollingAggregation::Sum,
+            AggregationRepr::Min => RollingAggregation::Min,
+            AggregationRepr::Max => RollingAggregation::Max,
+            AggregationRepr::Variance => RollingAggregation::Variance,
+            AggregationRepr::Std => RollingAggregation::Std,
+            AggregationRepr::Median => RollingAggregation::Median,
+            AggregationRepr::Quantile(quantile) => RollingAggregation::Quantile(quantile),
+        };
+        Ok(RollingWindow {
+            name: window.name,
+            span: match window.span {
+                SpanRepr::Rows(size) => WindowSpan::Rows(size),
+                SpanRepr::Duration(nanoseconds) => WindowSpan::Duration(chrono::Duration::nanoseconds(nanoseconds)),
+            },
+            aggregation,
+            min_periods: window.min_periods,
+            alignment: match window.centred {
+                true => WindowAlignment::Centred,
+                false => WindowAlignment::Trailing,
+            },
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct ColumnRepr {
+    field: Field,
+    rolling_mean: RollingMean,
+    returns: Returns,
+    #[serde(default)]
+    rolling_windows: Vec<RollingWindow>,
+    values: Vec<AnyType>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DataFrameRepr {
+    datetimes: Vec<DateTime<Utc>>,
+    columns: Vec<ColumnRepr>,
+}
+
+// the values and times of every row with the rolling mean, returns and rolling windows set up on each column.
+// rolling values are calculated again when read, and a frame with a custom rolling window cannot be serialized
+impl Serialize for DataFrame {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let columns = self.get_columns().iter()
+            .map(|column| ColumnRepr {
+                field: column.get_field(),
+                rolling_mean: column.rolling_mean.clone(),
+                returns: column.returns.clone(),
+                rolling_windows: column.get_rolling_windows().cloned().collect(),
+                values: column.iter_values().collect(),
+            })
+            .collect();
+        DataFrameRepr { datetimes: self.get_datetimes().clone(), columns }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for DataFrame {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = DataFrameRepr::deserialize(deserializer)?;
+        let mut columns = Vec::with_capacity(repr.columns.len());
+        let mut windows = vec![];
+        for column_repr in repr.columns.into_iter() {
+            windows.extend(column_repr.rolling_windows.into_iter().map(|window| (Rc::clone(&column_repr.field.name), window)));
+            let mut column = Column::from_field(column_repr.field, column_repr.rolling_mean, column_repr.returns);
+            for value in column_repr.values.into_iter() {
+                column.push(value).map_err(D::Error::custom)?;
+            }
+            columns.push(column);
+        }
+        let mut dataframe = DataFrame::from_columns(repr.datetimes, columns).map_err(D::Error::custom)?;
+        for (column_name, window) in windows.into_iter() {
+            dataframe.add_rolling_window(&column_name, window).map_err(D::Error::custom)?;
+        }
+        Ok(dataframe)
+    }
+}
+
+// for durations held as a whole number of nanoseconds
+pub mod optional_nanos {
+    use serde::{
+        Deserialize,
+        Deserializer,
+        Serialize,
+        Serializer,
+    };
+
+    pub fn serialize<S: Serializer>(duration: &Option<chrono::Duration>, serializer: S) -> Result<S::Ok, S::Error> {
+        duration.and_then(|duration| duration.num_nanoseconds()).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<chrono::Duration>, D::Error> {
+        Ok(Option::<i64>::deserialize(deserializer)?.map(chrono::Duration::nanoseconds))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::column::ReturnsKind;
+    #[test]
+    fn any_type() {
+        assert_eq!(serde_json::to_string(&AnyType::Int64(5)).unwrap(), r#"{"dtype":"i64","value":5}"#);
+        assert_eq!(serde_json::to_string(&AnyType::Null).unwrap(), r#"{"dtype":"null"}"#);
+        assert_eq!(serde_json::to_string(&AnyType::Duration(chrono::Duration::microseconds(3))).unwrap(), r#"{"dtype":"duration","value":3000}"#);
+        let values = vec![
+            AnyType::Null,
+            true.into(),
+            "vod".into(),
+            AnyType::UInt8(3),
+            AnyType::ISize(-4),
+            AnyType::Float32(1.5),
+            AnyType::Date(NaiveDate::from_ymd_opt(2024, 1, 2).unwrap()),
+            AnyType::Datetime(DateTime::parse_from_rfc3339("2024-01-02T08:00:00.5Z").unwrap().with_timezone(&Utc)),
+            AnyType::Time(NaiveTime::from_hms_opt(8, 0, 1).unwrap()),
+        ];
+        let read_back: Vec<AnyType> = serde_json::from_str(&serde_json::to_string(&values).unwrap()).unwrap();
+        assert_eq!(read_back, values);
+        assert!(serde_json::from_str::<AnyType>(r#"{"dtype":"i8","value":300}"#).is_err());
+    }
+
+    #[test]
+    fn schema() {
+        let schema = Schema::new(vec![Field::new("price", DataType::Float64, false), Field::new("venue", DataType::Utf8, true)]);
+        let text = serde_json::to_string(&schema).unwrap();
+        assert_eq!(text, r#"[{"name":"price","dtype":"f64","nullable":false},{"name":"venue","dtype":"str","nullable":true}]"#);
+        assert_eq!(serde_json::from_str::<Schema>(&text).unwrap(), schema);
+        assert!(serde_json::from_str::<DataType>(r#""decimal""#).is_err());
+    }
+
+    #[test]
+    fn dataframe() {
+        let start = DateTime::parse_from_rfc3339("2024-01-02T08:00:00Z").unwrap().with_timezone(&Utc);
+        let mut dataframe = DataFrame::new(vec!["venue", "price"]).unwrap();
+        dataframe.update_column_rolling_mean("price", RollingMean::over_duration(chrono::Duration::seconds(10))).unwrap();
+        dataframe.create_returns_for_column("price", "price_returns", ReturnsKind::Percentage(1), RollingMean::new(true, Some(2))).unwrap();
+        for (seconds, venue, price) in [(0, Some("lse"), 10f64), (5, None, 12.0), (20, Some("cboe"), 9.0)] {
+            dataframe.add_row_at(start + chrono::Duration::seconds(seconds), vec![venue.into(), price.into()]).unwrap();
+        }
+
+        let read_back: DataFrame = serde_json::from_str(&serde_json::to_string(&dataframe).unwrap()).unwrap();
+        assert_eq!(read_back.get_datetimes(), dataframe.get_datetimes());
+        assert_eq!(read_back.schema(), dataframe.schema());
+        for (column, original) in read_back.get_columns().iter().zip(dataframe.get_columns().iter()) {
+            assert_eq!(column.iter_values().collect::<Vec<AnyType>>(), original.iter_values().collect::<Vec<AnyType>>());
+            assert_eq!((0..3).map(|index| column.get_rolling_mean(index)).collect::<Vec<Option<AnyType>>>(), (0..3).map(|index| original.get_rolling_mean(index)).collect::<Vec<Option<AnyType>>>());
+        }
+
+        // the returns keep being calculated for rows added after reading
+        let mut read_back = read_back;
+        read_back.add_row_at(start + chrono::Duration::seconds(25), vec!["lse".into(), 18f64.into()]).unwrap();
+        assert_eq!(read_back.get_column_by_name("price_returns").unwrap().get_value(3), Some(1f64.into()));
+        assert_eq!(read_back.get_column_by_name("price").unwrap().get_rolling_mean(3), Some(13.5f64.into()));
+
+        // named rolling windows are kept, apart from custom ones which cannot be serialized
+        let mut dataframe = dataframe;
+        dataframe.add_rolling_window("price", RollingWindow::new("price_median", 2, RollingAggregation::Median).with_min_periods(1)).unwrap();
+        dataframe.add_rolling_window("price", RollingWindow::over_duration("price_max", chrono::Duration::seconds(6), RollingAggregation::Max)).unwrap();
+        dataframe.add_rolling_window("price_returns", RollingWindow::new("q", 3, RollingAggregation::Quantile(0.25)).with_alignment(WindowAlignment::Centred)).unwrap();
+        let read_back: DataFrame = serde_json::from_str(&serde_json::to_string(&dataframe).unwrap()).unwrap();
+        for (column, original) in read_back.get_columns().iter().zip(dataframe.get_columns().iter()) {
+            let names = |column: &Column| column.get_rolling_windows().map(|window| window.name.to_string()).collect::<Vec<String>>();
+            assert_eq!(names(column), names(original));
+            assert_eq!(column.get_rolling_results().collect::<Vec<&[Option<f64>]>>(), original.get_rolling_results().collect::<Vec<&[Option<f64>]>>());
+        }
+        let custom: crate::column::rolling::RollingFn = Rc::new(|values: &[f64]| values.last().copied());
+        dataframe.add_rolling_window("price", RollingWindow::new("price_last", 2, RollingAggregation::Custom(custom))).unwrap();
+        let error = serde_json::to_string(&dataframe).unwrap_err();
+        assert_eq!(error.to_string(), "rolling window 'price_last' has a custom aggregation which cannot be serialized");
+
+        let text = r#"{"datetimes":[],"columns":[{"field":{"name":"a","dtype":"i64","nullable":true},"rolling_mean":{"should_calculate":false,"mean_over":0,"duration":null},"returns":{"should_calculate":false,"column_name":null,"kind":{"Difference":1}},"values":[{"dtype":"i64","value":1}]}]}"#;
+        assert!(serde_json::from_str::<DataFrame>(text).is_err());
+    }
+}